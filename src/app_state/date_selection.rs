@@ -1,4 +1,4 @@
-use chrono::{DateTime, Datelike, Duration, Local};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum DateField {
@@ -9,6 +9,16 @@ pub enum DateField {
     Minute,
 }
 
+impl DateField {
+    /// Number of digits typed before the value is applied.
+    pub fn width(&self) -> usize {
+        match self {
+            DateField::Year => 4,
+            _ => 2,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum QuickRange {
     LastHour,
@@ -62,6 +72,9 @@ impl QuickRange {
     }
 }
 
+/// Format typed date-times are read with unless the config sets another.
+pub const DEFAULT_DATE_INPUT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
 #[derive(Debug)]
 pub struct DateSelection {
     pub profile_name: String,
@@ -74,6 +87,18 @@ pub struct DateSelection {
     pub selected_quick_range: Option<usize>,
     pub custom_selection: bool,
     pub active_column: ActiveColumn,
+    /// Digits typed into the focused custom field, applied once the field is complete.
+    pub pending_digits: String,
+    /// Set while picking a second range to show next to the open log viewer.
+    pub for_comparison: bool,
+    /// Set when the range is read from every configured profile instead of just one.
+    pub all_profiles: bool,
+    /// A whole date-time being typed into the focused custom field, replacing it on Enter.
+    pub typed_input: Option<String>,
+    /// Why the last typed date-time could not be read.
+    pub typed_error: Option<String>,
+    /// chrono format typed date-times are read with.
+    pub date_format: String,
 }
 
 impl Default for DateSelection {
@@ -89,6 +114,12 @@ impl Default for DateSelection {
             selected_quick_range: Some(0),
             custom_selection: false,
             active_column: ActiveColumn::QuickRanges,
+            pending_digits: String::new(),
+            for_comparison: false,
+            all_profiles: false,
+            typed_input: None,
+            typed_error: None,
+            date_format: DEFAULT_DATE_INPUT_FORMAT.to_string(),
         }
     }
 }
@@ -107,18 +138,28 @@ impl DateSelection {
             selected_quick_range: Some(0),
             custom_selection: false,
             active_column: ActiveColumn::QuickRanges,
+            pending_digits: String::new(),
+            for_comparison: false,
+            all_profiles: false,
+            typed_input: None,
+            typed_error: None,
+            date_format: DEFAULT_DATE_INPUT_FORMAT.to_string(),
         }
     }
 
     pub fn toggle_selection(&mut self) {
         self.is_selecting_from = !self.is_selecting_from;
+        self.pending_digits.clear();
     }
 
+    /// Switches between the quick ranges and editing the custom range.
     pub fn toggle_custom(&mut self) {
-        self.custom_selection = !self.custom_selection;
-        if !self.custom_selection {
+        if self.custom_selection {
+            self.select_column(ActiveColumn::QuickRanges);
             self.selected_quick_range = Some(0);
             self.apply_quick_range(0);
+        } else {
+            self.select_column(ActiveColumn::CustomRange);
         }
     }
 
@@ -150,6 +191,7 @@ impl DateSelection {
     }
 
     pub fn next_field(&mut self) {
+        self.pending_digits.clear();
         self.current_field = match self.current_field {
             DateField::Year => DateField::Month,
             DateField::Month => DateField::Day,
@@ -160,6 +202,7 @@ impl DateSelection {
     }
 
     pub fn previous_field(&mut self) {
+        self.pending_digits.clear();
         self.current_field = match self.current_field {
             DateField::Year => DateField::Minute,
             DateField::Month => DateField::Year,
@@ -170,6 +213,7 @@ impl DateSelection {
     }
 
     pub fn adjust_current_field(&mut self, increment: bool) {
+        self.pending_digits.clear();
         let date = if self.is_selecting_from {
             &mut self.from_date
         } else {
@@ -202,27 +246,98 @@ impl DateSelection {
             }
         }
 
-        // Ensure dates stay in order
-        if self.is_selecting_from && self.from_date > self.to_date {
-            self.from_date = self.to_date;
-        } else if !self.is_selecting_from && self.to_date < self.from_date {
-            self.to_date = self.from_date;
+        self.keep_dates_ordered();
+    }
+
+    /// Types a digit into the focused field. Once the field is complete the value is applied
+    /// (if it forms a valid date) and focus moves to the next field.
+    pub fn type_digit(&mut self, digit: char) {
+        self.pending_digits.push(digit);
+        if self.pending_digits.len() < self.current_field.width() {
+            return;
         }
+
+        let value: u32 = self.pending_digits.parse().unwrap_or_default();
+        let date = if self.is_selecting_from {
+            &mut self.from_date
+        } else {
+            &mut self.to_date
+        };
+        let updated = match self.current_field {
+            DateField::Year => date.with_year(value as i32),
+            DateField::Month => date.with_month(value),
+            DateField::Day => date.with_day(value),
+            DateField::Hour => date.with_hour(value),
+            DateField::Minute => date.with_minute(value),
+        };
+        if let Some(updated) = updated {
+            *date = updated;
+            self.keep_dates_ordered();
+        }
+
+        self.next_field();
     }
 
-    pub fn switch_column(&mut self, column: ActiveColumn) {
-        self.active_column = column.clone();
-        match column {
-            ActiveColumn::QuickRanges => {
-                self.custom_selection = false;
+    /// Discards a partially typed value.
+    pub fn clear_pending_digits(&mut self) {
+        self.pending_digits.clear();
+    }
+
+    /// Starts typing a whole date-time into the focused custom field, instead of field by field.
+    pub fn start_typed_input(&mut self) {
+        self.pending_digits.clear();
+        self.typed_error = None;
+        self.typed_input = Some(String::new());
+    }
+
+    /// Adds a character to the typed date-time, or removes the last one for `None`.
+    pub fn edit_typed_input(&mut self, c: Option<char>) {
+        if let Some(input) = &mut self.typed_input {
+            match c {
+                Some(c) => input.push(c),
+                None => {
+                    input.pop();
+                }
             }
-            ActiveColumn::CustomRange => {
-                self.custom_selection = true;
+            self.typed_error = None;
+        }
+    }
+
+    pub fn cancel_typed_input(&mut self) {
+        self.typed_input = None;
+        self.typed_error = None;
+    }
+
+    /// Reads the typed date-time into the focused field. On failure the text is kept for
+    /// correcting, and `typed_error` says what was wrong.
+    pub fn finish_typed_input(&mut self) {
+        let Some(input) = &self.typed_input else {
+            return;
+        };
+        match parse_date_input(input.trim(), &self.date_format) {
+            Ok(date) => {
+                if self.is_selecting_from {
+                    self.from_date = date;
+                } else {
+                    self.to_date = date;
+                }
+                self.keep_dates_ordered();
+                self.cancel_typed_input();
             }
+            Err(error) => self.typed_error = Some(error),
+        }
+    }
+
+    fn keep_dates_ordered(&mut self) {
+        if self.is_selecting_from && self.from_date > self.to_date {
+            self.from_date = self.to_date;
+        } else if !self.is_selecting_from && self.to_date < self.from_date {
+            self.to_date = self.from_date;
         }
     }
 
     pub fn select_column(&mut self, column: ActiveColumn) {
+        self.pending_digits.clear();
         self.active_column = column.clone();
         match column {
             ActiveColumn::QuickRanges => {
@@ -233,13 +348,28 @@ impl DateSelection {
             }
         }
     }
+}
 
-    pub fn select_from(&mut self) {
-        self.is_selecting_from = true;
+/// Reads a local date-time in `format`, a date alone at midnight, or an RFC 3339 time with its
+/// own offset, as pasted from logs and other tools.
+fn parse_date_input(input: &str, format: &str) -> Result<DateTime<Local>, String> {
+    if input.is_empty() {
+        return Err("Type a date and time".to_string());
     }
-
-    pub fn select_to(&mut self) {
-        self.is_selecting_from = false;
+    if let Ok(date) = DateTime::parse_from_rfc3339(input) {
+        return Ok(date.with_timezone(&Local));
+    }
+    let naive = NaiveDateTime::parse_from_str(input, format).or_else(|error| {
+        NaiveDate::parse_from_str(input, format)
+            .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .map_err(|_| error)
+    });
+    match naive {
+        Ok(naive) => Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .ok_or_else(|| format!("{} does not exist in the local time zone", input)),
+        Err(error) => Err(format!("Expected {}: {}", format, error)),
     }
 }
 
@@ -249,10 +379,3 @@ pub enum ActiveColumn {
     QuickRanges,
     CustomRange,
 }
-
-// Make sure you have this enum defined
-#[derive(PartialEq)]
-pub enum ActiveField {
-    From,
-    To,
-}
@@ -0,0 +1,405 @@
+use crate::utils::log_utils::structured_body;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Eq,
+    NotEq,
+    Lt,
+    Gt,
+    LtEq,
+    GtEq,
+    Contains,
+}
+
+impl Operator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operator::Eq => "=",
+            Operator::NotEq => "!=",
+            Operator::Lt => "<",
+            Operator::Gt => ">",
+            Operator::LtEq => "<=",
+            Operator::GtEq => ">=",
+            Operator::Contains => " contains ",
+        }
+    }
+}
+
+/// Symbolic operators, longest first so `<=` wins over `<`.
+const SYMBOL_OPERATORS: &[(&str, Operator)] = &[
+    ("!=", Operator::NotEq),
+    ("<=", Operator::LtEq),
+    (">=", Operator::GtEq),
+    ("=", Operator::Eq),
+    ("<", Operator::Lt),
+    (">", Operator::Gt),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TermKind {
+    /// Case-insensitive substring match.
+    Keyword(String),
+    /// Quoted text matched as a single substring, spaces included.
+    Phrase(String),
+    /// Comparison against a field of a JSON message, e.g. `status>=500`.
+    Field {
+        path: String,
+        operator: Operator,
+        value: String,
+        /// The term as typed, used as a substring for non-JSON messages.
+        raw: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterTerm {
+    pub kind: TermKind,
+    /// Set for terms prefixed with `-`, which exclude matching events.
+    pub negated: bool,
+}
+
+/// A parsed log filter. Every term must match (negated terms must not).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterQuery {
+    pub terms: Vec<FilterTerm>,
+}
+
+impl FilterQuery {
+    pub fn parse(input: &str) -> Self {
+        let tokens = tokenize(input);
+        let mut terms = Vec::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let token = &tokens[i];
+            let (negated, text) = if token.negated && token.text.is_empty() && !token.quoted {
+                // A lone `-` is searched for as is
+                (false, "-".to_string())
+            } else {
+                (token.negated, token.text.clone())
+            };
+
+            // `path contains value` spans three whitespace-separated tokens
+            if !token.quoted && is_field_path(&text) {
+                if let Some(value) = tokens
+                    .get(i + 1)
+                    .filter(|t| !t.quoted && t.text.eq_ignore_ascii_case("contains"))
+                    .and(tokens.get(i + 2))
+                {
+                    terms.push(FilterTerm {
+                        kind: TermKind::Field {
+                            path: text.clone(),
+                            operator: Operator::Contains,
+                            value: value.text.to_lowercase(),
+                            raw: value.text.to_lowercase(),
+                        },
+                        negated,
+                    });
+                    i += 3;
+                    continue;
+                }
+            }
+
+            let kind = if token.quoted {
+                TermKind::Phrase(text.to_lowercase())
+            } else if let Some(field) = parse_field_term(&text) {
+                field
+            } else {
+                TermKind::Keyword(text.to_lowercase())
+            };
+
+            if !matches!(&kind, TermKind::Keyword(k) | TermKind::Phrase(k) if k.is_empty()) {
+                terms.push(FilterTerm { kind, negated });
+            }
+            i += 1;
+        }
+
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, message: &str) -> bool {
+        if self.terms.is_empty() {
+            return true;
+        }
+
+        let message_lower = message.to_lowercase();
+        let has_field_terms = self
+            .terms
+            .iter()
+            .any(|term| matches!(term.kind, TermKind::Field { .. }));
+        let json = if has_field_terms {
+            structured_body(message)
+        } else {
+            None
+        };
+
+        self.terms
+            .iter()
+            .all(|term| term.matches(&message_lower, json.as_ref()) != term.negated)
+    }
+
+    /// Lowercased texts worth highlighting in matching messages.
+    pub fn highlight_terms(&self) -> Vec<String> {
+        self.terms
+            .iter()
+            .filter(|term| !term.negated)
+            .filter_map(|term| match &term.kind {
+                TermKind::Keyword(text) | TermKind::Phrase(text) => Some(text.clone()),
+                TermKind::Field {
+                    operator: Operator::Contains,
+                    value,
+                    ..
+                } => Some(value.clone()),
+                TermKind::Field { .. } => None,
+            })
+            .collect()
+    }
+}
+
+impl FilterTerm {
+    /// The term as interpreted by the parser, e.g. `-"timed out"` or `status>=500`.
+    pub fn label(&self) -> String {
+        let body = match &self.kind {
+            TermKind::Keyword(text) => text.clone(),
+            TermKind::Phrase(text) => format!("\"{}\"", text),
+            TermKind::Field {
+                path,
+                operator,
+                value,
+                ..
+            } => format!("{}{}{}", path, operator.symbol(), value),
+        };
+        if self.negated {
+            format!("-{}", body)
+        } else {
+            body
+        }
+    }
+
+    fn matches(&self, message_lower: &str, json: Option<&serde_json::Value>) -> bool {
+        match &self.kind {
+            TermKind::Keyword(text) | TermKind::Phrase(text) => message_lower.contains(text),
+            TermKind::Field {
+                path,
+                operator,
+                value,
+                raw,
+            } => match json {
+                Some(json) => {
+                    lookup(json, path).is_some_and(|field| compare_field(field, *operator, value))
+                }
+                // Plain-text messages fall back to a substring search for the term
+                None => message_lower.contains(raw),
+            },
+        }
+    }
+}
+
+struct Token {
+    text: String,
+    quoted: bool,
+    /// Whether the token began with a `-` outside quotes; `"-x"` searches for `-x`.
+    negated: bool,
+}
+
+/// Splits on whitespace, keeping double-quoted sections (and a leading `-`) together.
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut negated = false;
+    let mut in_quotes = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            '-' if current.is_empty() && !quoted && !negated => negated = true,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() || quoted || negated {
+                    tokens.push(Token {
+                        text: std::mem::take(&mut current),
+                        quoted,
+                        negated,
+                    });
+                }
+                quoted = false;
+                negated = false;
+            }
+            c => current.push(c),
+        }
+    }
+
+    if !current.is_empty() || quoted || negated {
+        tokens.push(Token {
+            text: current,
+            quoted,
+            negated,
+        });
+    }
+
+    tokens
+}
+
+fn is_field_path(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$' | '@'))
+}
+
+fn parse_field_term(text: &str) -> Option<TermKind> {
+    let (position, symbol, operator) = SYMBOL_OPERATORS
+        .iter()
+        .filter_map(|(symbol, operator)| {
+            text.find(symbol)
+                .map(|position| (position, *symbol, *operator))
+        })
+        // Leftmost operator wins; ties go to the longer symbol listed first
+        .min_by_key(|(position, _, _)| *position)?;
+
+    let path = &text[..position];
+    let value = &text[position + symbol.len()..];
+    if !is_field_path(path) {
+        return None;
+    }
+
+    Some(TermKind::Field {
+        path: path.to_string(),
+        operator,
+        value: value.to_string(),
+        raw: text.to_lowercase(),
+    })
+}
+
+/// Resolves a dotted path such as `request.headers.host`, indexing arrays by number.
+fn lookup<'a>(json: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.').try_fold(json, |value, key| match value {
+        serde_json::Value::Object(map) => map.get(key),
+        serde_json::Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+fn compare_field(field: &serde_json::Value, operator: Operator, value: &str) -> bool {
+    let field_text = match field {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    if operator == Operator::Contains {
+        return field_text.to_lowercase().contains(&value.to_lowercase());
+    }
+
+    // Compare numerically when both sides are numbers, otherwise case-insensitively as text
+    let ordering = match (field_text.parse::<f64>(), value.parse::<f64>()) {
+        (Ok(left), Ok(right)) => left.partial_cmp(&right),
+        _ => Some(field_text.to_lowercase().cmp(&value.to_lowercase())),
+    };
+
+    match (operator, ordering) {
+        (Operator::Eq, Some(ordering)) => ordering == Ordering::Equal,
+        (Operator::NotEq, Some(ordering)) => ordering != Ordering::Equal,
+        (Operator::Lt, Some(ordering)) => ordering == Ordering::Less,
+        (Operator::Gt, Some(ordering)) => ordering == Ordering::Greater,
+        (Operator::LtEq, Some(ordering)) => ordering != Ordering::Greater,
+        (Operator::GtEq, Some(ordering)) => ordering != Ordering::Less,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(input: &str) -> Vec<(TermKind, bool)> {
+        FilterQuery::parse(input)
+            .terms
+            .into_iter()
+            .map(|term| (term.kind, term.negated))
+            .collect()
+    }
+
+    fn field(path: &str, operator: Operator, value: &str, raw: &str) -> TermKind {
+        TermKind::Field {
+            path: path.to_string(),
+            operator,
+            value: value.to_string(),
+            raw: raw.to_string(),
+        }
+    }
+
+    #[test]
+    fn dash_inside_quotes_is_searched_for() {
+        assert_eq!(
+            kinds(r#""--dry-run" -"timed out" -foo"#),
+            [
+                (TermKind::Phrase("--dry-run".to_string()), false),
+                (TermKind::Phrase("timed out".to_string()), true),
+                (TermKind::Keyword("foo".to_string()), true),
+            ]
+        );
+        assert_eq!(kinds("-"), [(TermKind::Keyword("-".to_string()), false)]);
+    }
+
+    #[test]
+    fn longer_operators_win() {
+        assert_eq!(
+            kinds("status<=500 status<500 code!=0"),
+            [
+                (field("status", Operator::LtEq, "500", "status<=500"), false),
+                (field("status", Operator::Lt, "500", "status<500"), false),
+                (field("code", Operator::NotEq, "0", "code!=0"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn contains_spans_three_tokens() {
+        assert_eq!(
+            kinds("-request.path contains Users"),
+            [(
+                field("request.path", Operator::Contains, "users", "users"),
+                true
+            )]
+        );
+        let query = FilterQuery::parse("request.path contains users");
+        assert!(query.matches(r#"{"request":{"path":"/api/Users/1"}}"#));
+        assert!(!query.matches(r#"{"request":{"path":"/api/orders"}}"#));
+    }
+
+    #[test]
+    fn negated_field_terms_exclude_matches() {
+        let query = FilterQuery::parse("-level=debug");
+        assert!(!query.matches(r#"{"level":"DEBUG"}"#));
+        assert!(query.matches(r#"{"level":"info"}"#));
+        // Missing fields don't match, so the negation keeps the event
+        assert!(query.matches(r#"{"msg":"hi"}"#));
+    }
+
+    #[test]
+    fn numbers_compare_numerically_and_text_as_text() {
+        let query = FilterQuery::parse("status>=500");
+        assert!(query.matches(r#"{"status":503}"#));
+        assert!(query.matches(r#"{"status":"1000"}"#));
+        assert!(!query.matches(r#"{"status":99}"#));
+
+        let query = FilterQuery::parse("name>m");
+        assert!(query.matches(r#"{"name":"Zed"}"#));
+        assert!(!query.matches(r#"{"name":"alice"}"#));
+    }
+
+    #[test]
+    fn plain_text_falls_back_to_the_raw_term() {
+        let query = FilterQuery::parse("status=500");
+        assert!(query.matches("GET /api status=500 in 12ms"));
+        assert!(!query.matches("GET /api status=200 in 12ms"));
+    }
+}
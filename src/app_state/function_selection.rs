@@ -1,128 +1,469 @@
 use anyhow::Result;
-use aws_config::{BehaviorVersion, Region};
 use aws_sdk_lambda::Client as LambdaClient;
+use chrono::Utc;
 use ratatui::widgets::ListState;
-use std::sync::{Arc, Mutex};
-use tokio::spawn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::OnceCell;
+use tokio::task::JoinSet;
 
 use crate::toml_parser::Profile;
-use crate::utils::file_utils::{cache_functions, load_cached_functions};
+use crate::utils::aws_utils::{lambda_log_group, load_aws_config};
+use crate::utils::clipboard::copy_to_clipboard;
+use crate::utils::error_log;
+use crate::utils::file_utils::{
+    cache_functions, load_cached_functions, load_state, update_state, FunctionNote,
+};
+use crate::utils::shutdown::spawn;
+
+/// A function from `ListFunctions`, with the configuration the list sorts by.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionSummary {
+    pub name: String,
+    /// ISO-8601 in UTC as Lambda returns it, so it sorts as text.
+    pub last_modified: Option<String>,
+    pub runtime: Option<String>,
+    /// Needed to look up the function's tags; missing from caches written before tags were shown.
+    #[serde(default)]
+    pub arn: Option<String>,
+}
+
+/// Tags of a function, looked up once the function is highlighted.
+#[derive(Debug, Clone)]
+pub enum FunctionTags {
+    Loading,
+    /// Sorted by key.
+    Loaded(Vec<(String, String)>),
+    Failed(String),
+}
+
+/// Order of the function list, cycled with Ctrl+S and remembered between sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FunctionSort {
+    #[default]
+    Name,
+    /// Most recently deployed first.
+    LastModified,
+    /// Grouped by runtime, then by name.
+    Runtime,
+}
+
+impl FunctionSort {
+    pub fn next(self) -> Self {
+        match self {
+            FunctionSort::Name => FunctionSort::LastModified,
+            FunctionSort::LastModified => FunctionSort::Runtime,
+            FunctionSort::Runtime => FunctionSort::Name,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            FunctionSort::Name => "name",
+            FunctionSort::LastModified => "last modified",
+            FunctionSort::Runtime => "runtime",
+        }
+    }
+}
+
+/// A Lambda client made on first use and shared by the tasks holding a clone.
+#[derive(Debug, Clone, Default)]
+struct SharedClient(Arc<OnceCell<LambdaClient>>);
+
+impl SharedClient {
+    async fn get(&self, profile: &Profile) -> LambdaClient {
+        self.0
+            .get_or_init(|| async {
+                LambdaClient::new(&load_aws_config(&profile.name, &profile.region).await)
+            })
+            .await
+            .clone()
+    }
+}
+
+/// Tag lookups run at once while a tag filter needs every function's tags.
+const TAG_LOOKUP_CONCURRENCY: usize = 8;
+
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+
+/// A function's name and the outcome of looking up its tags.
+type TagsUpdate = (String, Result<Vec<(String, String)>, String>);
+
+#[derive(Debug)]
+pub enum FunctionLoadUpdate {
+    Cached(Vec<FunctionSummary>),
+    Fetched(Vec<FunctionSummary>),
+    Failed(String),
+}
 
 #[derive(Debug)]
 pub struct FunctionSelection {
     pub profile: Profile,
-    pub lambda_functions: Arc<Mutex<Vec<String>>>,
+    /// Every function in the region, in `sort` order.
+    pub lambda_functions: Vec<FunctionSummary>,
     pub filtered_functions: Vec<String>,
+    pub sort: FunctionSort,
     pub selected_index: usize,
     pub filter_input: String,
+    /// `key=value` (or just `key`) terms every listed function's tags must have, typed after Ctrl+T.
+    pub tag_filter_input: String,
+    /// Set while the tag filter is being typed.
+    pub editing_tag_filter: bool,
     pub list_state: ListState,
+    /// Rows the list showed on the last draw, used as the PageUp/PageDown step.
+    pub page_size: usize,
+    pub is_loading: bool,
+    pub load_error: Option<String>,
+    load_rx: Option<UnboundedReceiver<FunctionLoadUpdate>>,
+    /// Tags by function name, kept for the session so each function is asked about once.
+    pub tags: HashMap<String, FunctionTags>,
+    tags_tx: UnboundedSender<TagsUpdate>,
+    tags_rx: UnboundedReceiver<TagsUpdate>,
+    /// Lambda client the tag lookups share, so credentials are resolved once per profile.
+    tags_client: SharedClient,
+    /// Notes by function name, as saved in the state file.
+    pub notes: HashMap<String, FunctionNote>,
+    status_message: Option<(String, Instant)>,
 }
 
 impl FunctionSelection {
     pub fn new(profile: Profile) -> Self {
+        let (tags_tx, tags_rx) = unbounded_channel();
+        let state = load_state().unwrap_or_default();
         Self {
             profile,
-            lambda_functions: Arc::new(Mutex::new(Vec::new())),
+            lambda_functions: Vec::new(),
             filtered_functions: Vec::new(),
+            sort: state.function_sort,
             selected_index: 0,
             filter_input: String::new(),
+            tag_filter_input: String::new(),
+            editing_tag_filter: false,
             list_state: ListState::default(),
+            page_size: 10,
+            is_loading: false,
+            load_error: None,
+            load_rx: None,
+            tags: HashMap::new(),
+            tags_tx,
+            tags_rx,
+            tags_client: SharedClient::default(),
+            notes: state.notes,
+            status_message: None,
         }
     }
 
-    pub async fn load_functions(&mut self) -> Result<()> {
-        // Try to load from cache first
-        if let Some(cached_functions) =
-            load_cached_functions(&self.profile.name, &self.profile.region)?
-        {
-            // Update UI immediately with cached data
-            self.lambda_functions.lock().unwrap().clear();
-            self.lambda_functions
-                .lock()
-                .unwrap()
-                .extend(cached_functions);
-            self.filtered_functions = self.lambda_functions.lock().unwrap().clone();
-            self.list_state.select(Some(0));
-
-            // Clone necessary data for background task
-            let profile_name = self.profile.name.clone();
-            let profile_region = self.profile.region.clone();
-            let lambda_functions = Arc::clone(&self.lambda_functions);
-
-            // Spawn background task to update cache
-            spawn(async move {
-                if let Err(e) = update_functions_in_background(
-                    profile_name.clone(),
-                    profile_region.clone(),
-                    lambda_functions,
-                )
-                .await
+    /// Starts loading functions in the background. Results are picked up by `poll_updates`.
+    pub fn load_functions(&mut self) {
+        self.start_load(true);
+    }
+
+    /// Lists the functions again from Lambda, skipping the cache, e.g. to pick up one just
+    /// deployed. The filters and the highlighted function stay; tags are looked up again.
+    pub fn reload_functions(&mut self) {
+        if self.is_loading {
+            return;
+        }
+        self.tags.clear();
+        self.start_load(false);
+    }
+
+    fn start_load(&mut self, use_cache: bool) {
+        let (tx, rx) = unbounded_channel();
+        self.load_rx = Some(rx);
+        self.is_loading = true;
+        self.load_error = None;
+
+        let profile_name = self.profile.name.clone();
+        let profile_region = self.profile.region.clone();
+
+        spawn(async move {
+            // Serve the cached list first so the UI has something to show immediately
+            let cached = use_cache
+                .then(|| load_cached_functions(&profile_name, &profile_region).ok())
+                .flatten()
+                .flatten();
+            if let Some(cached_functions) = cached {
+                if tx
+                    .send(FunctionLoadUpdate::Cached(cached_functions))
+                    .is_err()
                 {
-                    eprintln!("Background update failed: {}", e);
+                    return;
+                }
+            }
+
+            let update = match fetch_functions(&profile_name, &profile_region).await {
+                Ok(functions) => {
+                    // Cache the functions
+                    let _ = cache_functions(&profile_name, &profile_region, &functions);
+                    FunctionLoadUpdate::Fetched(functions)
                 }
-            });
+                Err(e) => FunctionLoadUpdate::Failed(format!("{:#}", e)),
+            };
+            let _ = tx.send(update);
+        });
+    }
+
+    /// Applies any results sent by the background loader since the last call.
+    pub fn poll_updates(&mut self) {
+        self.poll_tags();
+        let Some(rx) = self.load_rx.as_mut() else {
+            return;
+        };
 
-            return Ok(());
+        let mut updates = Vec::new();
+        while let Ok(update) = rx.try_recv() {
+            updates.push(update);
         }
 
-        // If no cache exists, load directly from AWS
-        self.load_functions_from_aws().await
+        for update in updates {
+            match update {
+                FunctionLoadUpdate::Cached(functions) => self.set_functions(functions),
+                FunctionLoadUpdate::Fetched(functions) => {
+                    self.set_functions(functions);
+                    self.is_loading = false;
+                    self.load_rx = None;
+                }
+                FunctionLoadUpdate::Failed(error) => {
+                    error_log::log_error(&format!("load functions: {}", self.profile.name), &error);
+                    self.load_error = Some(error);
+                    self.is_loading = false;
+                    self.load_rx = None;
+                }
+            }
+        }
     }
 
-    async fn load_functions_from_aws(&mut self) -> Result<()> {
-        let config = aws_config::defaults(BehaviorVersion::latest())
-            .profile_name(&self.profile.name)
-            .region(Region::new(self.profile.region.clone()))
-            .load()
-            .await;
+    /// Stores fetched tags, then asks for the highlighted function's if they aren't known.
+    /// One lookup runs at a time, so scrolling past functions doesn't ask about each of them.
+    fn poll_tags(&mut self) {
+        let mut received = false;
+        while let Ok((name, result)) = self.tags_rx.try_recv() {
+            let tags = match result {
+                Ok(tags) => FunctionTags::Loaded(tags),
+                Err(error) => {
+                    error_log::log_error(&format!("list tags: {}", name), &error);
+                    FunctionTags::Failed(error)
+                }
+            };
+            self.tags.insert(name, tags);
+            received = true;
+        }
+        if received && !self.tag_filter_input.trim().is_empty() {
+            self.refilter_keeping_selection();
+        }
+
+        if self
+            .tags
+            .values()
+            .any(|tags| matches!(tags, FunctionTags::Loading))
+        {
+            return;
+        }
+        let Some((name, arn)) = self
+            .selected_function()
+            .filter(|name| !self.tags.contains_key(*name))
+            .and_then(|name| self.summary(name))
+            .and_then(|summary| Some((summary.name.clone(), summary.arn.clone()?)))
+        else {
+            return;
+        };
 
-        let client = LambdaClient::new(&config);
-        let mut functions = Vec::new();
-        let mut next_marker = None;
+        self.tags.insert(name.clone(), FunctionTags::Loading);
+        let tx = self.tags_tx.clone();
+        let client = self.tags_client.clone();
+        let profile = self.profile.clone();
+        spawn(async move {
+            let result = fetch_tags(&client.get(&profile).await, &arn)
+                .await
+                .map_err(|e| format!("{:#}", e));
+            let _ = tx.send((name, result));
+        });
+    }
 
-        loop {
-            let mut request = client.list_functions();
-            if let Some(marker) = next_marker {
-                request = request.marker(marker);
-            }
+    /// Looks up the tags of every function not yet known, a few at a time, for the tag filter.
+    fn request_all_tags(&mut self) {
+        let wanted: Vec<(String, String)> = self
+            .lambda_functions
+            .iter()
+            .filter(|summary| !self.tags.contains_key(&summary.name))
+            .filter_map(|summary| Some((summary.name.clone(), summary.arn.clone()?)))
+            .collect();
+        if wanted.is_empty() {
+            return;
+        }
+        for (name, _) in &wanted {
+            self.tags.insert(name.clone(), FunctionTags::Loading);
+        }
 
-            let response = request.send().await?;
-            let function_list = response.functions();
-            for function in function_list {
-                if let Some(name) = &function.function_name {
-                    functions.push(name.clone())
+        let tx = self.tags_tx.clone();
+        let client = self.tags_client.clone();
+        let profile = self.profile.clone();
+        spawn(async move {
+            let client = client.get(&profile).await;
+            let mut wanted = wanted.into_iter();
+            let mut tasks = JoinSet::new();
+            loop {
+                while tasks.len() < TAG_LOOKUP_CONCURRENCY {
+                    let Some((name, arn)) = wanted.next() else {
+                        break;
+                    };
+                    let client = client.clone();
+                    tasks.spawn(async move {
+                        let result = fetch_tags(&client, &arn)
+                            .await
+                            .map_err(|e| format!("{:#}", e));
+                        (name, result)
+                    });
+                }
+                match tasks.join_next().await {
+                    Some(Ok(update)) => {
+                        if tx.send(update).is_err() {
+                            return;
+                        }
+                    }
+                    Some(Err(_)) => {}
+                    None => break,
                 }
             }
+        });
+    }
 
-            next_marker = response.next_marker().map(String::from);
-            if next_marker.is_none() {
-                break;
+    pub fn start_tag_filter(&mut self) {
+        self.editing_tag_filter = true;
+    }
+
+    /// Extends or shortens the tag filter, fetching the tags it needs on first use.
+    pub fn edit_tag_filter(&mut self, c: Option<char>) {
+        match c {
+            Some(c) => self.tag_filter_input.push(c),
+            None => {
+                self.tag_filter_input.pop();
             }
         }
+        if !self.tag_filter_input.trim().is_empty() {
+            self.request_all_tags();
+        }
+        self.update_filter();
+    }
 
-        functions.sort();
+    pub fn clear_tag_filter(&mut self) {
+        self.editing_tag_filter = false;
+        self.tag_filter_input.clear();
+        self.update_filter();
+    }
 
-        // Cache the functions
-        cache_functions(&self.profile.name, &self.profile.region, &functions)?;
+    /// Functions whose tags are still being looked up for the tag filter.
+    pub fn pending_tag_lookups(&self) -> usize {
+        self.tags
+            .values()
+            .filter(|tags| matches!(tags, FunctionTags::Loading))
+            .count()
+    }
 
-        self.lambda_functions.lock().unwrap().clear();
-        self.lambda_functions.lock().unwrap().extend(functions);
-        self.filtered_functions = self.lambda_functions.lock().unwrap().clone();
-        self.list_state.select(Some(0));
-        Ok(())
+    /// Whether a function's tags can't be known: the lookup failed, or the function came from
+    /// a cache written before ARNs were kept.
+    pub fn tags_unknown(&self, name: &str) -> bool {
+        matches!(self.tags.get(name), Some(FunctionTags::Failed(_)))
+            || self
+                .summary(name)
+                .is_some_and(|summary| summary.arn.is_none())
     }
 
-    pub async fn update_filter(&mut self) -> Result<()> {
-        let lambda_functions = self.lambda_functions.lock().unwrap().clone();
+    /// Whether a function's known tags have every term of the tag filter: `key=value`
+    /// compares case-insensitively, a bare `key` only needs the tag to be there. Functions
+    /// whose tags can't be known stay listed, marked in the list, rather than vanishing.
+    fn matches_tag_filter(&self, name: &str) -> bool {
+        let terms: Vec<String> = self
+            .tag_filter_input
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        if terms.is_empty() || self.tags_unknown(name) {
+            return true;
+        }
+        let Some(FunctionTags::Loaded(tags)) = self.tags.get(name) else {
+            return false;
+        };
+        terms.iter().all(|term| {
+            let (key, value) = match term.split_once('=') {
+                Some((key, value)) => (key, Some(value)),
+                None => (term.as_str(), None),
+            };
+            tags.iter().any(|(k, v)| {
+                k.to_lowercase() == key && value.is_none_or(|value| v.to_lowercase() == value)
+            })
+        })
+    }
+
+    fn set_functions(&mut self, functions: Vec<FunctionSummary>) {
+        self.lambda_functions = functions;
+        if !self.tag_filter_input.trim().is_empty() {
+            self.request_all_tags();
+        }
+        self.apply_sort();
+    }
+
+    /// Switches to the next sort order and remembers it for the next session.
+    pub fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        let sort = self.sort;
+        let _ = update_state(|state| state.function_sort = sort);
+        self.apply_sort();
+    }
+
+    /// Sorts the functions and filters them again, keeping the highlighted one selected.
+    fn apply_sort(&mut self) {
+        match self.sort {
+            FunctionSort::Name => self.lambda_functions.sort_by(|a, b| a.name.cmp(&b.name)),
+            // Newest first; a missing date sorts as the oldest
+            FunctionSort::LastModified => self.lambda_functions.sort_by(|a, b| {
+                b.last_modified
+                    .cmp(&a.last_modified)
+                    .then_with(|| a.name.cmp(&b.name))
+            }),
+            FunctionSort::Runtime => self
+                .lambda_functions
+                .sort_by(|a, b| a.runtime.cmp(&b.runtime).then_with(|| a.name.cmp(&b.name))),
+        }
+        self.refilter_keeping_selection();
+    }
+
+    fn refilter_keeping_selection(&mut self) {
+        let selected = self.selected_function().cloned();
+        self.update_filter();
+
+        if let Some(index) =
+            selected.and_then(|name| self.filtered_functions.iter().position(|f| *f == name))
+        {
+            self.selected_index = index;
+            self.list_state.select(Some(index));
+        }
+    }
 
+    pub fn summary(&self, name: &str) -> Option<&FunctionSummary> {
+        self.lambda_functions.iter().find(|f| f.name == name)
+    }
+
+    pub fn update_filter(&mut self) {
         if self.filter_input.is_empty() {
-            self.filtered_functions = lambda_functions;
+            self.filtered_functions = self
+                .lambda_functions
+                .iter()
+                .map(|f| f.name.clone())
+                .collect();
         } else {
             let filter_lower = self.filter_input.to_lowercase();
             let keywords: Vec<&str> = filter_lower.split_whitespace().collect();
 
-            self.filtered_functions = lambda_functions
+            self.filtered_functions = self
+                .lambda_functions
                 .iter()
+                .map(|f| &f.name)
                 .filter(|name| {
                     let function_name = name.to_lowercase();
                     keywords
@@ -132,10 +473,106 @@ impl FunctionSelection {
                 .cloned()
                 .collect();
         }
+        if !self.tag_filter_input.trim().is_empty() {
+            let mut names = std::mem::take(&mut self.filtered_functions);
+            names.retain(|name| self.matches_tag_filter(name));
+            self.filtered_functions = names;
+        }
 
         self.selected_index = 0;
         self.list_state.select(Some(0));
-        Ok(())
+    }
+
+    pub fn selected_function(&self) -> Option<&String> {
+        self.filtered_functions.get(self.selected_index)
+    }
+
+    /// Copies the highlighted function's log group name, e.g. for AWS CLI commands.
+    pub fn copy_log_group(&mut self) {
+        let Some(log_group) = self.selected_function().map(|name| lambda_log_group(name)) else {
+            return;
+        };
+        let message = match copy_to_clipboard(&log_group) {
+            Ok(()) => format!("Copied {}", log_group),
+            Err(e) => format!("Copy failed: {}", e),
+        };
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// The highlighted function's note, or an empty one to start writing.
+    pub fn selected_note(&self) -> Option<(String, String)> {
+        let name = self.selected_function()?;
+        let text = self
+            .notes
+            .get(name)
+            .map(|note| note.text.clone())
+            .unwrap_or_default();
+        Some((name.clone(), text))
+    }
+
+    /// Saves a function's note as edited; an empty one removes it.
+    pub fn save_note(&mut self, function_name: String, text: &str) {
+        let text = text.trim_end();
+        let unchanged = self
+            .notes
+            .get(&function_name)
+            .map_or(text.is_empty(), |note| note.text == text);
+        if unchanged {
+            return;
+        }
+
+        let note = (!text.is_empty()).then(|| FunctionNote {
+            text: text.to_string(),
+            edited_at: Utc::now().timestamp_millis(),
+        });
+        let saved = update_state(|state| match &note {
+            Some(note) => {
+                state.notes.insert(function_name.clone(), note.clone());
+            }
+            None => {
+                state.notes.remove(&function_name);
+            }
+        });
+        let message = match saved {
+            Ok(()) if note.is_some() => "Note saved".to_string(),
+            Ok(()) => "Note removed".to_string(),
+            Err(e) => format!("Could not save note: {:#}", e),
+        };
+        match note {
+            Some(note) => self.notes.insert(function_name, note),
+            None => self.notes.remove(&function_name),
+        };
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// Reports an editor that failed to open or exited with an error.
+    pub fn set_status(&mut self, message: String) {
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// The outcome of the last copy or note edit, for a few seconds after it.
+    pub fn status_message(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < STATUS_MESSAGE_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    /// Moves the selection to the function the filter names exactly, or to the only match.
+    pub fn select_exact_match(&mut self) {
+        let index = if self.filtered_functions.len() == 1 {
+            Some(0)
+        } else {
+            let filter = self.filter_input.trim();
+            self.filtered_functions
+                .iter()
+                .position(|name| !filter.is_empty() && name.eq_ignore_ascii_case(filter))
+        };
+
+        if let Some(index) = index {
+            self.selected_index = index;
+            self.list_state.select(Some(index));
+        }
     }
 
     pub fn next(&mut self) {
@@ -151,18 +588,40 @@ impl FunctionSelection {
             self.list_state.select(Some(self.selected_index));
         }
     }
+
+    pub fn page_up(&mut self) {
+        if !self.filtered_functions.is_empty() {
+            self.selected_index = self.selected_index.saturating_sub(self.page_size.max(1));
+            self.list_state.select(Some(self.selected_index));
+        }
+    }
+
+    pub fn page_down(&mut self) {
+        if !self.filtered_functions.is_empty() {
+            self.selected_index = (self.selected_index + self.page_size.max(1))
+                .min(self.filtered_functions.len() - 1);
+            self.list_state.select(Some(self.selected_index));
+        }
+    }
+
+    /// The window of `filtered_functions` to draw, keeping the selection centered.
+    pub fn get_visible_range(&self, visible_height: usize) -> (usize, usize) {
+        let total_functions = self.filtered_functions.len();
+        let half_height = visible_height / 2;
+
+        let start = if self.selected_index + half_height >= total_functions {
+            total_functions.saturating_sub(visible_height)
+        } else {
+            self.selected_index.saturating_sub(half_height)
+        };
+        let end = (start + visible_height).min(total_functions);
+
+        (start, end)
+    }
 }
 
-async fn update_functions_in_background(
-    profile_name: String,
-    profile_region: String,
-    lambda_functions: Arc<Mutex<Vec<String>>>,
-) -> Result<()> {
-    let config = aws_config::defaults(BehaviorVersion::latest())
-        .profile_name(&profile_name)
-        .region(Region::new(profile_region.clone()))
-        .load()
-        .await;
+async fn fetch_functions(profile_name: &str, profile_region: &str) -> Result<Vec<FunctionSummary>> {
+    let config = load_aws_config(profile_name, profile_region).await;
 
     let client = LambdaClient::new(&config);
     let mut functions = Vec::new();
@@ -178,7 +637,12 @@ async fn update_functions_in_background(
         let function_list = response.functions();
         for function in function_list {
             if let Some(name) = &function.function_name {
-                functions.push(name.clone())
+                functions.push(FunctionSummary {
+                    name: name.clone(),
+                    last_modified: function.last_modified.clone(),
+                    runtime: function.runtime.as_ref().map(|r| r.as_str().to_string()),
+                    arn: function.function_arn.clone(),
+                })
             }
         }
 
@@ -188,15 +652,16 @@ async fn update_functions_in_background(
         }
     }
 
-    functions.sort();
-
-    // Update cache
-    cache_functions(&profile_name, &profile_region, &functions)?;
+    Ok(functions)
+}
 
-    // Update the shared functions list
-    let mut functions_lock = lambda_functions.lock().unwrap();
-    functions_lock.clear();
-    functions_lock.extend(functions);
+async fn fetch_tags(client: &LambdaClient, arn: &str) -> Result<Vec<(String, String)>> {
+    let response = client.list_tags().resource(arn).send().await?;
 
-    Ok(())
+    let mut tags: Vec<(String, String)> = response
+        .tags()
+        .map(|tags| tags.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default();
+    tags.sort();
+    Ok(tags)
 }
@@ -1,27 +1,481 @@
 use anyhow::Result;
-use aws_config::Region;
-use aws_sdk_cloudwatchlogs::types::OutputLogEvent;
+use aws_sdk_cloudwatchlogs::types::{FilteredLogEvent, OrderBy, OutputLogEvent};
 use aws_sdk_cloudwatchlogs::Client as CloudWatchLogsClient;
 use chrono::{DateTime, Local};
-use serde_json;
-use std::sync::{Arc, Mutex};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use regex_lite::Regex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::{AbortHandle, JoinHandle, JoinSet};
 
-use crate::utils::ui_utils::format_json;
+use crate::app_state::filter_query::FilterQuery;
+use crate::app_state::LogPanel;
+use crate::toml_parser::{JsonView, LogViewSettings, Profile, TimeField, MAX_LATEST_EVENTS};
+use crate::utils::aws_utils::{console_logs_url, lambda_log_group, load_aws_config};
+use crate::utils::clipboard::copy_to_clipboard;
+use crate::utils::error_log;
+use crate::utils::export::{
+    event_to_json, event_to_text, events_to_markdown, export_stem, write_export, ExportFormat,
+};
+use crate::utils::file_utils::{load_state, update_state};
+use crate::utils::lambda_report::ReportSummary;
+use crate::utils::log_utils::{
+    collapse_whitespace, constant_phrase, correlation_id, ends_invocation, flatten_json,
+    looks_like_stderr, request_id,
+};
+use crate::utils::shutdown::spawn;
+use crate::utils::time_utils::{format_timestamp, iso8601_pair, millis_to_local};
+use crate::utils::ui_utils::{format_log_message, ring_bell, wrap_with_indent};
+
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+
+/// Lines of a message formatted on expand; the rest is only formatted on request.
+const EXPANDED_LINE_LIMIT: usize = 2_000;
+
+/// Upper bound on streams read when collecting the latest events.
+const MAX_LATEST_STREAMS: usize = 50;
+
+/// How often follow mode asks CloudWatch for new events.
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A range ending at most this long ago counts as ending now, so `t` can start tailing it.
+const TAIL_MAX_RANGE_AGE: chrono::Duration = chrono::Duration::minutes(5);
+
+/// Events can be ingested a little after newer ones, so each follow poll re-reads this far back.
+const FOLLOW_LOOKBACK_MILLIS: i64 = 10_000;
+
+/// Follow mode's event rate counts the events of this last stretch of time.
+const FOLLOW_RATE_WINDOW_MILLIS: i64 = 60_000;
+
+/// Shortest stretch the rate is averaged over, so the first few events don't read as a surge.
+const FOLLOW_RATE_MIN_WINDOW_MILLIS: i64 = 10_000;
+
+/// Number of time buckets the event-volume histogram splits the range into.
+pub const HISTOGRAM_BUCKETS: usize = 60;
+
+/// Loads smaller than this are easy enough to read without a narrowing hint.
+const NARROW_HINT_MIN_EVENTS: usize = 1_000;
+
+/// The hinted window is this fraction of the range (1/10th).
+const NARROW_HINT_WINDOW_DIVISOR: i64 = 10;
+
+/// Share of the events the hinted window must hold to be worth suggesting.
+const NARROW_HINT_MIN_SHARE: f64 = 0.5;
+
+/// Most events the reading view lays out at once; more than that is a list, not a read.
+const READING_VIEW_MAX_EVENTS: usize = 200;
+
+/// Lines `+`/`-` lengthen or shorten the detail view's page jump by.
+pub const PAGE_JUMP_STEP: isize = 5;
+
+/// Alerts kept from following; older ones are dropped and only counted.
+const MAX_ALERTS: usize = 500;
+
+/// How long the list border stays red after a watched pattern matches.
+const ALERT_FLASH_DURATION: Duration = Duration::from_secs(1);
+
+/// Quiet time after the last range nudge before the new range is read, so a burst of
+/// nudges makes one request.
+const NUDGE_DEBOUNCE: Duration = Duration::from_millis(600);
+
+/// Narrowest range zooming in goes down to.
+const MIN_NUDGED_RANGE: chrono::Duration = chrono::Duration::minutes(1);
+
+/// A nudge moving either end by less than this leaves the range as it was, e.g. one
+/// pushed against now moments after it was loaded.
+const MIN_RANGE_MOVE: chrono::Duration = chrono::Duration::seconds(1);
+
+#[derive(Debug)]
+pub enum LogLoadUpdate {
+    Connected(CloudWatchLogsClient),
+    /// The log group's retention in days; `None` when its events never expire.
+    Retention(Option<i32>),
+    Page {
+        events: Vec<FilteredLogEvent>,
+        next_token: Option<String>,
+        elapsed: Duration,
+    },
+    Finished,
+    Failed(String),
+    /// An update from one profile of an aggregated view.
+    FromProfile(String, Box<LogLoadUpdate>),
+}
+
+/// Why a load stopped before CloudWatch ran out of pages.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Truncation {
+    EventLimit,
+    Cancelled,
+}
+
+impl Truncation {
+    pub fn description(&self) -> &str {
+        match self {
+            Truncation::EventLimit => "event limit reached",
+            Truncation::Cancelled => "loading cancelled",
+        }
+    }
+}
+
+/// Identifies an event across filtering and reloads, which shift its index, and across
+/// overlapping polls, which return it again: its times and a hash of its stream and message.
+type EventKey = (Option<i64>, Option<i64>, u64);
+
+fn event_key(event: &FilteredLogEvent) -> EventKey {
+    let mut hasher = DefaultHasher::new();
+    event.log_stream_name.hash(&mut hasher);
+    event.message.hash(&mut hasher);
+    (event.timestamp, event.ingestion_time, hasher.finish())
+}
+
+/// Where the reader is: the selected event, and how far down its detail view they were.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ReadPosition {
+    event: EventKey,
+    scroll: usize,
+}
+
+/// The expanded event's message, formatted once instead of on every frame and keypress.
+#[derive(Debug)]
+pub struct ExpandedContent {
+    key: EventKey,
+    pub lines: Vec<Line<'static>>,
+    /// Line count of the whole message, which exceeds `lines` until the rest is loaded.
+    pub total_lines: usize,
+    /// First line and name of each top-level field when the message is a JSON object.
+    pub sections: Vec<(usize, String)>,
+    /// The lines' layout at the width they were last drawn at.
+    wrapped: Option<WrappedRows>,
+}
+
+/// Where each line of an expanded message starts once wrapped, so scrolling can go by the
+/// rows on screen rather than by message line.
+#[derive(Debug)]
+struct WrappedRows {
+    width: usize,
+    indent: usize,
+    /// First row of each line, followed by the total row count.
+    starts: Vec<usize>,
+}
+
+/// An event kept in view below the list while the selection moves on.
+#[derive(Debug)]
+pub struct PinnedEvent {
+    pub event: FilteredLogEvent,
+    pub content: ExpandedContent,
+    pub scroll: usize,
+    /// Lines the panel had room for in the last frame, set by the view.
+    pub height: usize,
+}
+
+impl PinnedEvent {
+    fn max_scroll(&self) -> usize {
+        self.content.lines.len().saturating_sub(self.height.max(1))
+    }
+}
+
+/// The selected JSON event's leaf fields as a flat `key = value` table, shown over the list.
+#[derive(Debug)]
+pub struct FieldsView {
+    pub rows: Vec<(String, String)>,
+    pub scroll: usize,
+}
+
+/// Loaded events per log stream, busiest first, shown over the list. Each stream is one
+/// execution environment, so a hot container stands out at the top.
+#[derive(Debug)]
+pub struct StreamCounts {
+    pub rows: Vec<(String, usize)>,
+    pub cursor: usize,
+}
+
+impl ExpandedContent {
+    pub fn is_partial(&self) -> bool {
+        self.lines.len() < self.total_lines
+    }
+
+    /// Wraps the lines to `width` with continuation rows indented by `indent`, unless they
+    /// already are. Returns whether the layout changed.
+    pub fn wrap(&mut self, width: usize, indent: usize) -> bool {
+        if self
+            .wrapped
+            .as_ref()
+            .is_some_and(|wrapped| wrapped.width == width && wrapped.indent == indent)
+        {
+            return false;
+        }
+        let mut starts = Vec::with_capacity(self.lines.len() + 1);
+        let mut rows = 0;
+        for line in &self.lines {
+            starts.push(rows);
+            rows += wrap_with_indent(vec![line.clone()], width, indent).len();
+        }
+        starts.push(rows);
+        self.wrapped = Some(WrappedRows {
+            width,
+            indent,
+            starts,
+        });
+        true
+    }
+
+    /// Rows the message takes on screen, or its line count before it was first drawn.
+    pub fn row_count(&self) -> usize {
+        self.wrapped
+            .as_ref()
+            .and_then(|wrapped| wrapped.starts.last().copied())
+            .unwrap_or(self.lines.len())
+    }
+
+    /// The line shown at `row`.
+    pub fn line_at(&self, row: usize) -> usize {
+        match &self.wrapped {
+            Some(wrapped) => wrapped.starts[..self.lines.len()]
+                .partition_point(|&start| start <= row)
+                .saturating_sub(1),
+            None => row.min(self.lines.len().saturating_sub(1)),
+        }
+    }
+
+    /// The first row of `line`.
+    pub fn row_of(&self, line: usize) -> usize {
+        match &self.wrapped {
+            Some(wrapped) => wrapped.starts[line.min(self.lines.len())],
+            None => line,
+        }
+    }
+
+    /// The top-level field the line at `position` belongs to.
+    pub fn section_at(&self, position: usize) -> Option<&str> {
+        let index = self
+            .sections
+            .partition_point(|(start, _)| *start <= position)
+            .checked_sub(1)?;
+        Some(&self.sections[index].1)
+    }
+}
+
+/// Top-level fields of a formatted JSON object, found from the layout `format_json` and
+/// `format_json_compact` share: each starts a line with two spaces of indent, then the key.
+fn json_sections(message: &str, lines: &[Line<'static>]) -> Vec<(usize, String)> {
+    let is_object = serde_json::from_str::<serde_json::Value>(message).is_ok_and(|v| v.is_object());
+    if !is_object {
+        return Vec::new();
+    }
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| match line.spans.as_slice() {
+            [indent, key, ..] if indent.content == "  " => Some((index, key.content.to_string())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A filter kept aside in its own tab, with the selection it was left at.
+#[derive(Debug, Default, Clone)]
+pub struct FilterTab {
+    pub input: String,
+    selected_log: Option<usize>,
+    start_index: usize,
+}
+
+/// Every listed event's full message one after another, for reading a handful of matches
+/// without expanding each in turn.
+#[derive(Debug, Default)]
+pub struct ReadingView {
+    pub lines: Vec<Line<'static>>,
+    /// First line of each listed event, in list order.
+    pub event_starts: Vec<usize>,
+    pub scroll: usize,
+    /// Rows the view had room for in the last frame, set by the view.
+    pub height: usize,
+}
+
+impl ReadingView {
+    /// Index of the event the top line belongs to.
+    pub fn current_event(&self) -> usize {
+        self.event_starts
+            .partition_point(|&start| start <= self.scroll)
+            .saturating_sub(1)
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.height.max(1))
+    }
+}
+
+/// Filter matches per histogram bucket over the span the buckets split.
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    /// Millisecond span covered: the loaded range, widened to any event outside it.
+    pub range: (i64, i64),
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Bounds of one bucket, as used for the brushed window.
+    pub fn bucket_bounds(&self, bucket: usize) -> (i64, i64) {
+        let (start, end) = self.range;
+        let span = (end - start) as i128;
+        let at = |i: usize| start + (span * i as i128 / HISTOGRAM_BUCKETS as i128) as i64;
+        (at(bucket), at(bucket + 1))
+    }
+}
+
+/// What to fetch: every stream of the group in the range, one stream when `log_stream_name`
+/// is set, or just the newest `latest_count` events regardless of the range.
+#[derive(Debug, Clone)]
+pub struct LogQuery {
+    pub log_group_name: String,
+    pub log_stream_name: Option<String>,
+    pub latest_count: Option<usize>,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+/// Metadata about the most recent `filter_log_events` run, shown in the debug overlay.
+#[derive(Debug, Default, Clone)]
+pub struct FetchStats {
+    pub pages: usize,
+    pub events: usize,
+    pub next_tokens: Vec<String>,
+    pub page_times: Vec<Duration>,
+    pub started_at: Option<Instant>,
+    pub total_time: Option<Duration>,
+}
 
 #[derive(Debug)]
 pub struct LogViewer {
     pub function_name: String,
     pub from_date: DateTime<Local>,
     pub to_date: DateTime<Local>,
-    pub logs: Arc<Mutex<Vec<OutputLogEvent>>>,
-    pub filtered_logs: Vec<OutputLogEvent>,
+    /// When set, events are read from this stream only via `get_log_events`.
+    pub log_stream_name: Option<String>,
+    /// When set, only the most recent events are loaded and the date range is ignored.
+    pub latest_count: Option<usize>,
+    /// Profiles whose accounts are searched together; empty for a single-profile view.
+    pub aggregate_profiles: Vec<String>,
+    /// The same profiles in full, to load them again on refresh.
+    aggregate_sources: Vec<Profile>,
+    /// Region the events are read from; unset when aggregated profiles span several.
+    pub region: Option<String>,
+    /// Which profile each event of an aggregated view came from.
+    event_profiles: HashMap<EventKey, String>,
+    /// Loaded events that look like stderr output, judged once as they arrive.
+    stderr_events: HashSet<EventKey>,
+    /// Profiles of an aggregated view still loading, so one can be stopped on its own.
+    profile_tasks: HashMap<String, AbortHandle>,
+    /// Events each profile of an aggregated view has loaded, against its share of the limit.
+    profile_events: HashMap<String, usize>,
+    /// Whether a profile stopped at its share of the limit in the current load.
+    profile_capped: bool,
+    /// Profiles whose load failed, with the error, while the others carry on.
+    pub profile_errors: Vec<(String, String)>,
+    pub logs: Vec<FilteredLogEvent>,
+    pub filtered_logs: Vec<FilteredLogEvent>,
     pub filter_input: String,
+    /// The function's filter as remembered from the last session, to skip needless saves.
+    saved_filter: String,
+    /// Parsed form of `filter_input`, rebuilt by `update_filter`.
+    pub filter_query: FilterQuery,
     pub scroll_offset: usize, // Changed from scroll_position
     pub selected_log: Option<usize>,
     pub expanded: bool,
     cloudwatch_client: Option<CloudWatchLogsClient>,
     pub scroll_position: usize,
+    /// The last collapsed event and the scroll position it was left at.
+    last_expanded: Option<ReadPosition>,
+    pub expanded_content: Option<ExpandedContent>,
+    /// Shown instead of the list while open.
+    pub reading: Option<ReadingView>,
     pub start_index: usize, // Add this field to track list scroll position
+    pub is_loading: bool,
+    pub load_error: Option<String>,
+    /// Set when pagination stopped early, so the loaded events are not exhaustive.
+    pub truncation: Option<Truncation>,
+    /// Retention of the log group in days, once looked up; unset for groups kept forever.
+    pub retention_days: Option<i32>,
+    load_rx: Option<UnboundedReceiver<LogLoadUpdate>>,
+    load_task: Option<JoinHandle<()>>,
+    /// Keeps polling for new events after the initial load, like `tail -f`.
+    pub follow: bool,
+    follow_rx: Option<UnboundedReceiver<LogLoadUpdate>>,
+    follow_task: Option<JoinHandle<()>>,
+    /// Set while the range is being re-read; pages collect in `refresh_events` meanwhile.
+    pub refreshing: bool,
+    refresh_events: Vec<FilteredLogEvent>,
+    /// When the last load or refresh finished, for the auto-refresh timer.
+    pub last_refreshed: Option<Instant>,
+    pub fetch_stats: FetchStats,
+    pub show_debug: bool,
+    pub settings: LogViewSettings,
+    /// Other end of a multi-event selection started with Shift+↑/↓.
+    pub selection_anchor: Option<usize>,
+    /// Rows the log list had room for in the last frame, set by the view.
+    pub page_size: usize,
+    /// Lines the detail view had room for in the last frame, set by the view.
+    pub detail_height: usize,
+    /// Histogram bucket picked with ←/→, drawn highlighted until it is brushed.
+    pub histogram_cursor: Option<usize>,
+    /// Bucket counts, dropped whenever the events, filter or range change and counted
+    /// again on the next frame rather than on every one.
+    histogram: Option<Histogram>,
+    /// Inclusive-exclusive millisecond window the list is narrowed to from the histogram.
+    pub time_window: Option<(i64, i64)>,
+    /// Busiest window of a high-volume load, as (start, end, events), offered until dismissed.
+    pub narrow_hint: Option<(i64, i64, usize)>,
+    /// Panel keys are routed to.
+    pub focus: LogPanel,
+    /// Hides the filter, histogram, footer and detail header so the logs get the whole pane.
+    /// The filter still shows while it has focus.
+    pub full_width: bool,
+    /// Event shown below the list whatever is selected, set with `p`.
+    pub pinned: Option<PinnedEvent>,
+    /// Flattened fields of the selected event, open after `F`.
+    pub fields: Option<FieldsView>,
+    /// Every filter tab, in order. The active one's copy is stale: its filter and selection
+    /// are the viewer's own fields until another tab is switched to.
+    pub filter_tabs: Vec<FilterTab>,
+    pub active_tab: usize,
+    /// Term typed after `?`, looked up in the list without narrowing it; `n`/`N` repeat it.
+    pub find_input: String,
+    /// Set while the find term is being typed.
+    pub finding: bool,
+    /// Selection the find started from, restored when it is cancelled.
+    find_origin: Option<usize>,
+    /// Pattern typed after `W`, in filter syntax, checked against events that arrive while following.
+    pub watch_input: String,
+    /// Set while the watch pattern is being typed.
+    pub editing_watch: bool,
+    /// The pattern in effect and its parsed form.
+    watch: Option<(String, FilterQuery)>,
+    /// Followed events that matched the watch pattern, oldest first, up to `MAX_ALERTS`.
+    pub alerts: Vec<FilteredLogEvent>,
+    /// Older alerts dropped to stay within `MAX_ALERTS`.
+    pub dropped_alerts: usize,
+    alerted_at: Option<Instant>,
+    /// Highlighted row of the alerts overlay while it is open.
+    pub alerts_cursor: Option<usize>,
+    pub stream_counts: Option<StreamCounts>,
+    /// When the range was last nudged; it is read again once nudging stops.
+    nudged_at: Option<Instant>,
+    /// Only this log stream's events are listed, as picked in the stream counts.
+    pub only_stream: Option<String>,
+    /// When this function's logs were last closed; later events are marked as new.
+    pub last_visit: Option<i64>,
+    /// Request ID of the single invocation shown, when the events were taken from another viewer.
+    pub invocation_id: Option<String>,
+    /// Set once the viewer is shown, so prefetched viewers nobody opened don't count as a visit.
+    pub visited: bool,
+    /// Off for views that aren't the function's own, like piped input or a derived comparison,
+    /// which neither restore nor save its filter and last visit.
+    persist: bool,
+    status_message: Option<(String, Instant)>,
 }
 
 impl LogViewer {
@@ -29,232 +483,3252 @@ impl LogViewer {
         function_name: String,
         from_date: DateTime<Local>,
         to_date: DateTime<Local>,
+        settings: LogViewSettings,
+    ) -> Self {
+        Self::create(function_name, from_date, to_date, settings, true)
+    }
+
+    /// A viewer that leaves the saved state alone: it opens unfiltered and what is typed in it
+    /// is not remembered for `function_name`.
+    pub fn detached(
+        function_name: String,
+        from_date: DateTime<Local>,
+        to_date: DateTime<Local>,
+        settings: LogViewSettings,
+    ) -> Self {
+        Self::create(function_name, from_date, to_date, settings, false)
+    }
+
+    fn create(
+        function_name: String,
+        from_date: DateTime<Local>,
+        to_date: DateTime<Local>,
+        settings: LogViewSettings,
+        persist: bool,
     ) -> Self {
+        // Pick up the search this function was last left with, and when that was
+        let (saved_filter, last_visit) = if persist {
+            let mut state = load_state().unwrap_or_default();
+            (
+                state.filters.remove(&function_name).unwrap_or_default(),
+                state.last_visits.remove(&function_name),
+            )
+        } else {
+            (String::new(), None)
+        };
+
         Self {
+            filter_query: FilterQuery::parse(&saved_filter),
+            filter_input: saved_filter.clone(),
+            saved_filter,
             function_name,
             from_date,
             to_date,
-            logs: Arc::new(Mutex::new(Vec::new())),
+            log_stream_name: None,
+            latest_count: None,
+            aggregate_profiles: Vec::new(),
+            region: None,
+            event_profiles: HashMap::new(),
+            stderr_events: HashSet::new(),
+            aggregate_sources: Vec::new(),
+            profile_tasks: HashMap::new(),
+            profile_events: HashMap::new(),
+            profile_capped: false,
+            profile_errors: Vec::new(),
+            logs: Vec::new(),
             filtered_logs: Vec::new(),
-            filter_input: String::new(),
             scroll_offset: 0,
             selected_log: None,
             expanded: false,
             cloudwatch_client: None,
             scroll_position: 0,
+            last_expanded: None,
+            expanded_content: None,
             start_index: 0, // Initialize start_index
+            is_loading: false,
+            load_error: None,
+            truncation: None,
+            retention_days: None,
+            reading: None,
+            load_rx: None,
+            load_task: None,
+            follow: false,
+            follow_rx: None,
+            follow_task: None,
+            refreshing: false,
+            refresh_events: Vec::new(),
+            last_refreshed: None,
+            fetch_stats: FetchStats::default(),
+            show_debug: false,
+            settings,
+            selection_anchor: None,
+            page_size: 10,
+            detail_height: 10,
+            histogram_cursor: None,
+            histogram: None,
+            time_window: None,
+            narrow_hint: None,
+            focus: LogPanel::default(),
+            full_width: false,
+            pinned: None,
+            fields: None,
+            filter_tabs: vec![FilterTab::default()],
+            active_tab: 0,
+            find_input: String::new(),
+            watch_input: String::new(),
+            editing_watch: false,
+            watch: None,
+            alerts: Vec::new(),
+            dropped_alerts: 0,
+            alerted_at: None,
+            alerts_cursor: None,
+            stream_counts: None,
+            nudged_at: None,
+            only_stream: None,
+            finding: false,
+            find_origin: None,
+            last_visit,
+            invocation_id: None,
+            visited: false,
+            persist,
+            status_message: None,
         }
     }
 
-    pub async fn initialize(&mut self, profile_name: String, region: String) -> Result<()> {
-        let aws_config = aws_config::defaults(aws_config::BehaviorVersion::v2024_03_28())
-            .profile_name(profile_name)
-            .region(Region::new(region.clone()))
-            .load()
-            .await;
+    /// Connects to CloudWatch and starts loading logs in the background.
+    pub fn initialize(&mut self, profile_name: String, region: String) {
+        self.apply_default_filter();
+        let (tx, rx) = self.start_load();
+        let query = self.query();
+        self.region = Some(region.clone());
 
-        self.cloudwatch_client = Some(CloudWatchLogsClient::new(&aws_config));
-        self.load_logs().await?;
-        Ok(())
-    }
+        self.load_rx = Some(rx);
+        self.load_task = Some(spawn(async move {
+            let aws_config = load_aws_config(&profile_name, &region).await;
 
-    async fn load_logs(&mut self) -> Result<()> {
-        let client = self.cloudwatch_client.as_ref().unwrap();
-        let log_group_name = format!("/aws/lambda/{}", self.function_name);
+            let client = CloudWatchLogsClient::new(&aws_config);
+            if tx.send(LogLoadUpdate::Connected(client.clone())).is_err() {
+                return;
+            }
+            // Without permission to describe the group the events still load, just unexplained
+            match fetch_retention(&client, &query.log_group_name).await {
+                Ok(retention) => {
+                    let _ = tx.send(LogLoadUpdate::Retention(retention));
+                }
+                Err(e) => error_log::log_error(
+                    &format!("log group retention: {}", query.log_group_name),
+                    &format!("{:#}", e),
+                ),
+            }
+            fetch_log_events(client, query, tx).await;
+        }));
+    }
 
-        let start_time = self.from_date.timestamp_millis();
-        let end_time = self.to_date.timestamp_millis();
+    /// Loads the range of the same function from several profiles at once, each with its
+    /// own credentials. A profile that fails is reported without stopping the others.
+    pub fn initialize_aggregate(&mut self, profiles: Vec<Profile>) {
+        self.apply_default_filter();
+        self.aggregate_profiles = profiles.iter().map(|p| p.name.clone()).collect();
+        self.region = profiles
+            .first()
+            .map(|first| first.region.clone())
+            .filter(|region| profiles.iter().all(|p| &p.region == region));
+        self.aggregate_sources = profiles;
+        self.load_aggregate();
+    }
 
-        let mut logs = Vec::new();
-        let mut next_token = None;
+    /// Starts every aggregated profile's fetch of the range, each allowed an equal share
+    /// of `max_events`.
+    fn load_aggregate(&mut self) {
+        let (tx, rx) = self.start_load();
+        let query = self.query();
+        self.profile_errors.clear();
+        self.profile_events.clear();
+        self.profile_capped = false;
 
-        loop {
-            let mut request = client
-                .filter_log_events()
-                .log_group_name(&log_group_name)
-                .start_time(start_time as i64)
-                .end_time(end_time as i64)
-                .limit(100);
+        // Dropping the set when the load task is aborted stops every profile's fetch
+        let mut tasks = JoinSet::new();
+        self.profile_tasks = self
+            .aggregate_sources
+            .iter()
+            .map(|profile| {
+                let fetch = fetch_profile_events(profile.clone(), query.clone(), tx.clone());
+                (profile.name.clone(), tasks.spawn(fetch))
+            })
+            .collect();
+        self.load_rx = Some(rx);
+        self.load_task = Some(spawn(
+            async move { while tasks.join_next().await.is_some() {} },
+        ));
+    }
 
-            if let Some(token) = &next_token {
-                request = request.next_token(token);
-            }
+    /// Each aggregated profile's share of `max_events`, so one busy account can't crowd
+    /// out the rest.
+    fn profile_share(&self) -> usize {
+        (self.settings.max_events / self.aggregate_profiles.len().max(1)).max(1)
+    }
 
-            let response = request.send().await?;
+    /// The profile an event was loaded from, in an aggregated view.
+    pub fn event_profile(&self, event: &FilteredLogEvent) -> Option<&str> {
+        if self.aggregate_profiles.is_empty() {
+            return None;
+        }
+        self.event_profiles
+            .get(&event_key(event))
+            .map(String::as_str)
+    }
 
-            if let Some(events) = response.events {
-                logs.extend(events.into_iter().map(|e| {
-                    OutputLogEvent::builder()
-                        .timestamp(e.timestamp.unwrap_or(0))
-                        .message(e.message.unwrap_or(String::new()))
-                        .ingestion_time(e.ingestion_time.unwrap_or(0))
-                        .build()
-                }));
-            }
+    /// Starts with the configured default filter unless the function has its own saved.
+    /// Left as is, it isn't saved as the function's own when the viewer closes.
+    fn apply_default_filter(&mut self) {
+        let default_filter = self.settings.default_filter.trim();
+        if default_filter.is_empty() || !self.filter_input.is_empty() {
+            return;
+        }
+        self.filter_input = default_filter.to_string();
+        self.saved_filter = self.filter_input.clone();
+        self.update_filter();
+    }
 
-            next_token = response.next_token;
-            if next_token.is_none() {
-                break;
-            }
+    fn start_load(
+        &mut self,
+    ) -> (
+        UnboundedSender<LogLoadUpdate>,
+        UnboundedReceiver<LogLoadUpdate>,
+    ) {
+        if let Some(task) = self.load_task.take() {
+            task.abort();
         }
+        self.is_loading = true;
+        self.load_error = None;
+        self.truncation = None;
+        self.fetch_stats = FetchStats {
+            started_at: Some(Instant::now()),
+            ..FetchStats::default()
+        };
+        unbounded_channel()
+    }
 
-        *self.logs.lock().unwrap() = logs;
-        self.update_filter();
-        Ok(())
+    pub fn query(&self) -> LogQuery {
+        LogQuery {
+            log_group_name: self.log_group_name(),
+            log_stream_name: self.log_stream_name.clone(),
+            latest_count: self.latest_count,
+            start_time: self.from_date.timestamp_millis(),
+            end_time: self.to_date.timestamp_millis(),
+        }
     }
 
-    pub fn update_filter(&mut self) {
-        let logs = self.logs.lock().unwrap();
+    /// The retention in days when the range starts before it, so its oldest events are gone.
+    pub fn range_beyond_retention(&self) -> Option<i32> {
+        let days = self.retention_days?;
+        let oldest_kept = Local::now() - chrono::Duration::days(days.into());
+        (self.from_date < oldest_kept).then_some(days)
+    }
 
-        if self.filter_input.is_empty() {
-            self.filtered_logs = logs.clone();
-        } else {
-            let filter_lower = self.filter_input.to_lowercase();
-            let keywords: Vec<&str> = filter_lower.split_whitespace().collect();
+    pub fn log_group_name(&self) -> String {
+        lambda_log_group(&self.function_name)
+    }
 
-            self.filtered_logs = logs
-                .iter()
-                .filter(|log| {
-                    if let Some(message) = log.message.as_ref() {
-                        let message_lower = message.to_lowercase();
-                        keywords
-                            .iter()
-                            .all(|&keyword| message_lower.contains(keyword))
-                    } else {
-                        false
-                    }
-                })
-                .cloned()
-                .collect();
+    /// Applies any pages sent by the background loader or follow poller since the last call.
+    pub fn poll_updates(&mut self) {
+        self.poll_load_updates();
+        self.poll_follow_updates();
+        self.refresh_if_due();
+        if self
+            .nudged_at
+            .is_some_and(|nudged_at| nudged_at.elapsed() >= NUDGE_DEBOUNCE)
+        {
+            self.nudged_at = None;
+            self.refresh();
         }
+    }
 
-        // Reset selection when filter changes
-        self.selected_log = if self.filtered_logs.is_empty() {
-            None
-        } else {
-            Some(0)
-        };
-        self.expanded = false;
+    /// Time left until the next auto-refresh, when one is configured and none is running.
+    pub fn next_refresh_in(&self) -> Option<Duration> {
+        let interval = Duration::from_secs(self.settings.auto_refresh_secs?);
+        if self.is_loading || self.latest_count.is_some() {
+            return None;
+        }
+        let elapsed = self.last_refreshed?.elapsed();
+        Some(interval.saturating_sub(elapsed))
     }
 
-    pub fn scroll_up(&mut self) {
-        if self.expanded {
-            self.scroll_position = self.scroll_position.saturating_sub(1);
+    fn refresh_if_due(&mut self) {
+        if self.next_refresh_in().is_some_and(|left| left.is_zero()) {
+            self.refresh();
         }
     }
 
-    pub fn scroll_down(&mut self) {
-        if let Some(log) = self.get_selected_log() {
-            if let Some(message) = &log.message {
-                let line_count =
-                    if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(message) {
-                        // Count JSON formatted lines
-                        format_json(&json_value, 0).len()
-                    } else {
-                        // Count regular message lines
-                        message.lines().count()
-                    };
-                self.scroll_position = self
-                    .scroll_position
-                    .saturating_add(1)
-                    .min(line_count.saturating_sub(1));
-            }
+    /// Re-reads the whole range in the background, swapping the results in once complete.
+    /// An aggregated view reads every profile again.
+    pub fn refresh(&mut self) {
+        if !self.aggregate_sources.is_empty() {
+            self.refreshing = true;
+            self.refresh_events.clear();
+            self.load_aggregate();
+            return;
         }
+        let Some(client) = self.cloudwatch_client.clone() else {
+            return;
+        };
+
+        let (tx, rx) = self.start_load();
+        let query = self.query();
+        self.refreshing = true;
+        self.refresh_events.clear();
+        self.load_rx = Some(rx);
+        self.load_task = Some(spawn(fetch_log_events(client, query, tx)));
     }
 
-    pub fn update_scroll(&mut self, visible_height: usize) {
-        if let Some(selected) = self.selected_log {
-            // Keep selection in the middle of the visible area when possible
-            let middle = visible_height / 2;
+    /// Replaces the events with a completed refresh, keeping the filter and the selected event.
+    fn apply_refresh(&mut self) {
+        self.refreshing = false;
+        self.logs = std::mem::take(&mut self.refresh_events);
+        self.classify_all();
+        if !self.aggregate_profiles.is_empty() {
+            // Events gone from the range take their profile with them
+            let keys: HashSet<EventKey> = self.logs.iter().map(event_key).collect();
+            self.event_profiles.retain(|key, _| keys.contains(key));
+        }
+        self.refilter_keeping_position();
+    }
 
-            if selected >= middle {
-                self.scroll_offset = selected.saturating_sub(middle);
-            } else {
-                self.scroll_offset = 0;
-            }
+    /// The selected event, with the detail view's scroll when it is expanded or was last
+    /// collapsed at.
+    fn read_position(&self) -> Option<ReadPosition> {
+        let event = event_key(self.get_selected_log()?);
+        let scroll = if self.expanded {
+            self.scroll_position
+        } else {
+            self.last_expanded
+                .filter(|position| position.event == event)
+                .map_or(0, |position| position.scroll)
+        };
+        Some(ReadPosition { event, scroll })
+    }
 
-            // Don't scroll past the end
-            let max_scroll = self.filtered_logs.len().saturating_sub(visible_height);
-            self.scroll_offset = self.scroll_offset.min(max_scroll);
+    /// Selects the event of `position` again, wherever it is listed now, and scrolls its
+    /// detail view back when expanded. False when it is no longer listed.
+    fn restore_read_position(&mut self, position: Option<ReadPosition>) -> bool {
+        let Some(position) = position else {
+            return false;
+        };
+        let Some(index) = self
+            .filtered_logs
+            .iter()
+            .position(|e| event_key(e) == position.event)
+        else {
+            return false;
+        };
+        self.selected_log = Some(index);
+        if self.expanded {
+            self.scroll_position = position.scroll;
         }
+        true
     }
 
-    pub fn toggle_expand(&mut self) {
-        self.expanded = !self.expanded;
-        self.scroll_offset = 0;
+    /// Rebuilds `filtered_logs`, keeping the read position when its event is still listed
+    /// and otherwise selecting the listed event nearest to it in time.
+    fn refilter_keeping_position(&mut self) {
+        let position = self.read_position();
+        self.histogram = None;
+        self.filtered_logs = self
+            .logs
+            .iter()
+            .filter(|log| self.matches_filter(log))
+            .cloned()
+            .collect();
+        self.order_filtered();
+
+        if !self.restore_read_position(position) {
+            self.expanded = false;
+            self.selection_anchor = None;
+            self.selected_log = Some(self.nearest_listed(position).unwrap_or(0));
+        }
+        self.clamp_selection();
+        self.refresh_reading_view();
     }
 
-    pub fn get_selected_log(&self) -> Option<&OutputLogEvent> {
-        self.selected_log.and_then(|i| self.filtered_logs.get(i))
+    /// Index of the listed event closest in time to the one `position` was on.
+    fn nearest_listed(&self, position: Option<ReadPosition>) -> Option<usize> {
+        let (timestamp, ..) = position?.event;
+        let timestamp = timestamp?;
+        self.filtered_logs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, log)| Some((i, log.timestamp?)))
+            .min_by_key(|(_, other)| other.abs_diff(timestamp))
+            .map(|(i, _)| i)
     }
 
-    pub fn page_up(&mut self) {
-        if self.expanded {
-            self.scroll_position = self.scroll_position.saturating_sub(10);
+    /// Keeps the selection, the range anchor and the list scroll inside `filtered_logs`.
+    /// Called after anything that replaces or shrinks it.
+    fn clamp_selection(&mut self) {
+        // Collapsing below leaves nothing for the detail panel to show
+        if self.focus == LogPanel::Detail && (!self.expanded || self.filtered_logs.is_empty()) {
+            self.focus = LogPanel::List;
         }
+        let Some(last) = self.filtered_logs.len().checked_sub(1) else {
+            self.selected_log = None;
+            self.selection_anchor = None;
+            self.expanded = false;
+            self.start_index = 0;
+            return;
+        };
+        self.selected_log = Some(self.selected_log.map_or(0, |i| i.min(last)));
+        self.selection_anchor = self.selection_anchor.map(|i| i.min(last));
+        self.start_index = self.start_index.min(last);
     }
 
-    pub fn page_down(&mut self) {
-        if let Some(log) = self.get_selected_log() {
-            if let Some(message) = &log.message {
-                let line_count =
-                    if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(message) {
-                        format_json(&json_value, 0).len()
+    fn poll_load_updates(&mut self) {
+        let Some(rx) = self.load_rx.as_mut() else {
+            return;
+        };
+
+        let mut updates = Vec::new();
+        while let Ok(update) = rx.try_recv() {
+            updates.push(update);
+        }
+
+        for update in updates {
+            let update = match update {
+                LogLoadUpdate::FromProfile(profile, update) => {
+                    match self.apply_profile_update(profile, *update) {
+                        Some(update) => update,
+                        // Stopped at the event limit; later updates are from the aborted load
+                        None if !self.is_loading => break,
+                        None => continue,
+                    }
+                }
+                update => update,
+            };
+            match update {
+                LogLoadUpdate::Connected(client) => self.cloudwatch_client = Some(client),
+                LogLoadUpdate::Retention(days) => self.retention_days = days,
+                LogLoadUpdate::Page {
+                    mut events,
+                    next_token,
+                    elapsed,
+                } => {
+                    let loaded = if self.refreshing {
+                        self.refresh_events.len()
                     } else {
-                        message.lines().count()
+                        self.logs.len()
                     };
-                self.scroll_position =
-                    (self.scroll_position + 10).min(line_count.saturating_sub(1));
+                    let remaining = self.settings.max_events.saturating_sub(loaded);
+                    let hit_limit = events.len() > remaining
+                        || (events.len() == remaining && next_token.is_some());
+
+                    self.fetch_stats.pages += 1;
+                    self.fetch_stats.events += events.len();
+                    self.fetch_stats.page_times.push(elapsed);
+                    self.fetch_stats.next_tokens.extend(next_token);
+
+                    events.truncate(remaining);
+                    if self.latest_count.is_some() {
+                        // The range is whatever the newest events happen to cover
+                        if let Some(first) = events.first().and_then(|e| e.timestamp) {
+                            self.from_date = millis_to_local(first);
+                        }
+                    }
+                    if self.refreshing {
+                        self.refresh_events.extend(events);
+                    } else {
+                        self.append_logs(events);
+                    }
+
+                    if hit_limit {
+                        self.stop_loading(Truncation::EventLimit);
+                        break;
+                    }
+                }
+                LogLoadUpdate::Finished => self.finish_load(),
+                LogLoadUpdate::Failed(error) => {
+                    // A failed refresh keeps showing the previous results
+                    error_log::log_error(&format!("load logs: {}", self.log_group_name()), &error);
+                    self.follow = false;
+                    self.refreshing = false;
+                    self.refresh_events.clear();
+                    self.load_error = Some(error);
+                    self.finish_load();
+                }
+                LogLoadUpdate::FromProfile(..) => {}
+            }
+            if self.load_rx.is_none() {
+                break;
             }
         }
     }
 
-    pub fn get_visible_range(&self, visible_height: usize) -> (usize, usize) {
-        let total_logs = self.filtered_logs.len();
-        let half_height = visible_height / 2;
+    /// Unwraps one profile's update of an aggregated view into what the load loop applies:
+    /// pages are tagged and merged into time order, and the load only finishes (or fails)
+    /// once every profile is done. A profile that reached its share of the limit is stopped
+    /// and its remaining updates ignored.
+    fn apply_profile_update(
+        &mut self,
+        profile: String,
+        update: LogLoadUpdate,
+    ) -> Option<LogLoadUpdate> {
+        if !self.profile_tasks.contains_key(&profile) {
+            return None;
+        }
+        match update {
+            LogLoadUpdate::Connected(_)
+            | LogLoadUpdate::Retention(_)
+            | LogLoadUpdate::FromProfile(..) => None,
+            LogLoadUpdate::Page {
+                mut events,
+                next_token,
+                elapsed,
+            } => {
+                let share = self.profile_share();
+                let loaded = self.profile_events.entry(profile.clone()).or_default();
+                let remaining = share.saturating_sub(*loaded);
+                let hit_limit =
+                    events.len() > remaining || (events.len() == remaining && next_token.is_some());
+                events.truncate(remaining);
+                *loaded += events.len();
+                self.fetch_stats.events += events.len();
+                for event in &events {
+                    self.event_profiles
+                        .insert(event_key(event), profile.clone());
+                }
 
-        if let Some(selected) = self.selected_log {
-            // Calculate the ideal start position that would center the selected item
-            let ideal_start = selected.saturating_sub(half_height);
+                // Profiles page independently, so each page is merged into time order
+                if self.refreshing {
+                    merge_by(&mut self.refresh_events, events, event_time);
+                } else {
+                    self.merge_logs(events);
+                }
 
-            // Adjust start position if we're too close to the end
-            let start = if selected + half_height >= total_logs {
-                total_logs.saturating_sub(visible_height)
-            } else {
-                ideal_start
-            };
+                // The limit is decided here, as the page passed on below carries no events
+                if hit_limit {
+                    self.fetch_stats.pages += 1;
+                    self.fetch_stats.page_times.push(elapsed);
+                    if let Some(task) = self.profile_tasks.remove(&profile) {
+                        task.abort();
+                    }
+                    self.profile_capped = true;
+                    return self.profile_done();
+                }
 
-            // Calculate end position
-            let end = (start + visible_height).min(total_logs);
+                // Passed on empty so the usual page accounting still applies
+                Some(LogLoadUpdate::Page {
+                    events: Vec::new(),
+                    next_token,
+                    elapsed,
+                })
+            }
+            LogLoadUpdate::Finished => {
+                self.profile_tasks.remove(&profile);
+                self.profile_done()
+            }
+            LogLoadUpdate::Failed(error) => {
+                error_log::log_error(
+                    &format!("load logs: {} ({})", self.log_group_name(), profile),
+                    &error,
+                );
+                self.profile_tasks.remove(&profile);
+                self.profile_errors.push((profile, error));
+                // Only an error when no profile could be read at all
+                if !self.profile_tasks.is_empty()
+                    || self.profile_errors.len() < self.aggregate_profiles.len()
+                {
+                    return self.profile_done();
+                }
+                self.load_error = Some(
+                    self.profile_errors
+                        .iter()
+                        .map(|(profile, error)| format!("{}: {}", profile, error))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                );
+                // A failed refresh keeps showing the previous results
+                self.refreshing = false;
+                self.refresh_events.clear();
+                self.finish_load();
+                None
+            }
+        }
+    }
 
-            (start, end)
-        } else {
-            (0, visible_height.min(total_logs))
+    /// Finishes an aggregated load once its last profile is done, noting the limit when
+    /// any profile stopped at its share.
+    fn profile_done(&mut self) -> Option<LogLoadUpdate> {
+        if !self.profile_tasks.is_empty() {
+            return None;
+        }
+        if self.profile_capped {
+            self.stop_loading(Truncation::EventLimit);
+            return None;
         }
+        Some(LogLoadUpdate::Finished)
     }
 
-    pub fn move_selection(&mut self, direction: i32, visible_height: usize) {
-        if self.filtered_logs.is_empty() {
+    /// Leaves out events already listed, and repeats within `events`, by their time, stream
+    /// and message. Overlapping poll windows can return an event again, and not every event
+    /// carries the id the poller dedupes by.
+    fn drop_known_events(&self, events: Vec<FilteredLogEvent>) -> Vec<FilteredLogEvent> {
+        let Some(oldest) = events.iter().filter_map(|e| e.timestamp).min() else {
+            return events;
+        };
+        // Loaded events are in time order, so only the tail can overlap the new ones
+        let mut known: HashSet<EventKey> = self
+            .logs
+            .iter()
+            .rev()
+            .take_while(|log| log.timestamp.is_none_or(|timestamp| timestamp >= oldest))
+            .map(event_key)
+            .collect();
+        events
+            .into_iter()
+            .filter(|event| known.insert(event_key(event)))
+            .collect()
+    }
+
+    fn poll_follow_updates(&mut self) {
+        let Some(rx) = self.follow_rx.as_mut() else {
             return;
+        };
+
+        let mut updates = Vec::new();
+        while let Ok(update) = rx.try_recv() {
+            updates.push(update);
         }
 
-        if let Some(current) = self.selected_log {
-            let new_index = if direction > 0 {
-                current.saturating_add(1).min(self.filtered_logs.len() - 1)
-            } else {
-                current.saturating_sub(1)
-            };
-            self.selected_log = Some(new_index);
+        for update in updates {
+            match update {
+                LogLoadUpdate::Page { events, .. } => {
+                    if let Some(last) = events.last().and_then(|e| e.timestamp) {
+                        self.to_date = millis_to_local(last);
+                    }
 
-            // Update scroll position for list view
-            if !self.expanded {
-                // Adjust start_index to keep selection visible
-                if new_index >= self.start_index + visible_height {
-                    self.start_index = new_index.saturating_sub(visible_height - 1);
-                } else if new_index < self.start_index {
-                    self.start_index = new_index;
+                    // Stay pinned to the newest event unless the user moved away from it
+                    let at_end = self
+                        .selected_log
+                        .is_none_or(|i| i + 1 >= self.filtered_logs.len());
+                    let events = self.drop_known_events(events);
+                    self.check_watch(&events);
+                    self.append_logs(events);
+                    if at_end && !self.filtered_logs.is_empty() && !self.expanded {
+                        self.selected_log = Some(self.filtered_logs.len() - 1);
+                    }
                 }
+                LogLoadUpdate::Failed(error) => {
+                    error_log::log_error(
+                        &format!("follow logs: {}", self.log_group_name()),
+                        &error,
+                    );
+                    self.load_error = Some(error);
+                    self.stop_follow();
+                }
+                LogLoadUpdate::Connected(_)
+                | LogLoadUpdate::Retention(_)
+                | LogLoadUpdate::Finished
+                | LogLoadUpdate::FromProfile(..) => {}
+            }
+        }
+    }
+
+    /// Records followed events that match the watch pattern, flashing the list and ringing
+    /// the bell when the settings ask for it.
+    fn check_watch(&mut self, events: &[FilteredLogEvent]) {
+        let Some((_, query)) = &self.watch else {
+            return;
+        };
+        let matched: Vec<FilteredLogEvent> = events
+            .iter()
+            .filter(|event| query.matches(event.message.as_deref().unwrap_or("")))
+            .cloned()
+            .collect();
+        if matched.is_empty() {
+            return;
+        }
+
+        self.set_status(format!(
+            "{} new event(s) matched the watch pattern | A: Alerts",
+            matched.len()
+        ));
+        self.alerts.extend(matched);
+        if let Some(excess) = self.alerts.len().checked_sub(MAX_ALERTS) {
+            self.alerts.drain(..excess);
+            self.dropped_alerts += excess;
+            // The open overlay keeps pointing at the same alert, or the oldest kept
+            if let Some(cursor) = &mut self.alerts_cursor {
+                *cursor = cursor.saturating_sub(excess);
             }
         }
+        self.alerted_at = Some(Instant::now());
+        if self.settings.alert_bell {
+            ring_bell();
+        }
+    }
+
+    /// Whether a watched pattern matched moments ago.
+    pub fn is_alerting(&self) -> bool {
+        self.alerted_at
+            .is_some_and(|at| at.elapsed() < ALERT_FLASH_DURATION)
+    }
+
+    /// The watch pattern in effect.
+    pub fn watch_pattern(&self) -> Option<&str> {
+        self.watch.as_ref().map(|(pattern, _)| pattern.as_str())
+    }
+
+    pub fn start_watch_edit(&mut self) {
+        self.editing_watch = true;
+        self.watch_input = self.watch_pattern().unwrap_or_default().to_string();
+    }
+
+    pub fn edit_watch(&mut self, c: Option<char>) {
+        match c {
+            Some(c) => self.watch_input.push(c),
+            None => {
+                self.watch_input.pop();
+            }
+        }
+    }
+
+    /// Puts the typed pattern in effect; an empty one stops watching.
+    pub fn finish_watch_edit(&mut self) {
+        self.editing_watch = false;
+        let pattern = self.watch_input.trim().to_string();
+        if pattern.is_empty() {
+            self.watch = None;
+            self.set_status("Stopped watching");
+            return;
+        }
+        self.set_status(if self.follow {
+            format!("Watching new events for '{}'", pattern)
+        } else {
+            format!(
+                "Set; '{}' is checked against new events while following (t)",
+                pattern
+            )
+        });
+        self.watch = Some((pattern.clone(), FilterQuery::parse(&pattern)));
+    }
+
+    pub fn cancel_watch_edit(&mut self) {
+        self.editing_watch = false;
+        self.watch_input = self.watch_pattern().unwrap_or_default().to_string();
+    }
+
+    /// Opens the list of collected alerts at the newest one, or closes it.
+    pub fn toggle_alerts(&mut self) {
+        if self.alerts_cursor.take().is_some() {
+            return;
+        }
+        match self.alerts.len().checked_sub(1) {
+            Some(last) => self.alerts_cursor = Some(last),
+            None => self.set_status("No alerts yet; W sets a pattern to watch for"),
+        }
+    }
+
+    pub fn move_alerts_cursor(&mut self, delta: isize) {
+        if let Some(cursor) = &mut self.alerts_cursor {
+            *cursor = cursor
+                .saturating_add_signed(delta)
+                .min(self.alerts.len().saturating_sub(1));
+        }
+    }
+
+    /// Closes the alerts and selects the highlighted one in the list.
+    pub fn jump_to_alert(&mut self) {
+        let Some(alert) = self.alerts_cursor.take().and_then(|i| self.alerts.get(i)) else {
+            return;
+        };
+        let key = event_key(alert);
+        match self.filtered_logs.iter().position(|e| event_key(e) == key) {
+            Some(index) => self.select_found(index),
+            None => self.set_status("That alert is hidden by the filter"),
+        }
+    }
+
+    /// Counts the loaded events of each log stream and opens them busiest first, or closes them.
+    pub fn toggle_stream_counts(&mut self) {
+        if self.stream_counts.take().is_some() {
+            return;
+        }
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for stream in self
+            .logs
+            .iter()
+            .filter_map(|log| log.log_stream_name.as_deref())
+        {
+            *counts.entry(stream).or_default() += 1;
+        }
+        if counts.is_empty() {
+            self.set_status("No log stream names among the loaded events");
+            return;
+        }
+
+        let mut rows: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(stream, count)| (stream.to_string(), count))
+            .collect();
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let cursor = self
+            .only_stream
+            .as_ref()
+            .and_then(|only| rows.iter().position(|(stream, _)| stream == only))
+            .unwrap_or(0);
+        self.stream_counts = Some(StreamCounts { rows, cursor });
+    }
+
+    pub fn move_stream_cursor(&mut self, delta: isize) {
+        if let Some(counts) = &mut self.stream_counts {
+            counts.cursor = counts
+                .cursor
+                .saturating_add_signed(delta)
+                .min(counts.rows.len().saturating_sub(1));
+        }
+    }
+
+    /// Closes the stream counts and lists only the highlighted stream's events.
+    pub fn show_only_stream(&mut self) {
+        let Some((stream, _)) = self
+            .stream_counts
+            .take()
+            .and_then(|counts| counts.rows.into_iter().nth(counts.cursor))
+        else {
+            return;
+        };
+        self.only_stream = Some(stream.clone());
+        self.refilter_keeping_position();
+        self.set_status(format!(
+            "Showing {} events from {} (Esc: all streams)",
+            self.filtered_logs.len(),
+            stream
+        ));
+    }
+
+    pub fn clear_only_stream(&mut self) {
+        self.only_stream = None;
+        self.refilter_keeping_position();
+    }
+
+    /// Where the loaded events start and end within the range, as fractions of it, while a
+    /// load is running or after it stopped early. Unset once the whole range has been read
+    /// and for views whose range comes from the events (latest events, following).
+    pub fn coverage(&self) -> Option<(f64, f64)> {
+        if (self.truncation.is_none() && !self.is_loading)
+            || self.latest_count.is_some()
+            || self.follow
+        {
+            return None;
+        }
+        let (from, to) = (
+            self.from_date.timestamp_millis(),
+            self.to_date.timestamp_millis(),
+        );
+        let span = (to - from).max(1) as f64;
+        let (first, last) = self.loaded_span()?;
+        let fraction = |t: i64| ((t - from) as f64 / span).clamp(0.0, 1.0);
+        Some((fraction(first), fraction(last)))
+    }
+
+    /// Timestamps of the first and last loaded events. The events are kept in event-time
+    /// order, so only the ends are looked at.
+    pub fn loaded_span(&self) -> Option<(i64, i64)> {
+        let first = self.logs.iter().find_map(|log| log.timestamp)?;
+        let last = self.logs.iter().rev().find_map(|log| log.timestamp)?;
+        Some((first, last))
+    }
+
+    /// Events per second over the last minute while following, or since the range began when
+    /// that is more recent. Late-ingested events count once they arrive.
+    pub fn follow_rate(&self) -> Option<f64> {
+        if !self.follow {
+            return None;
+        }
+        let now = Local::now().timestamp_millis();
+        let window = (now - self.from_date.timestamp_millis())
+            .clamp(FOLLOW_RATE_MIN_WINDOW_MILLIS, FOLLOW_RATE_WINDOW_MILLIS);
+        let cutoff = now - window;
+        let recent = self
+            .logs
+            .iter()
+            .rev()
+            .take_while(|log| log.timestamp.is_none_or(|timestamp| timestamp >= cutoff))
+            .count();
+        Some(recent as f64 * 1_000.0 / window as f64)
+    }
+
+    /// Starts polling for events newer than the last one loaded. Needs a connected client.
+    fn start_follow(&mut self) {
+        let Some(client) = self.cloudwatch_client.clone() else {
+            return;
+        };
+        if let Some(task) = self.follow_task.take() {
+            task.abort();
+        }
+
+        let since = self
+            .logs
+            .iter()
+            .filter_map(|e| e.timestamp)
+            .max()
+            .map_or(self.from_date.timestamp_millis(), |last| last + 1);
+        let query = self.query();
+        let (tx, rx) = unbounded_channel();
+
+        self.follow = true;
+        self.follow_rx = Some(rx);
+        self.follow_task = Some(spawn(follow_log_events(client, query, since, tx)));
+    }
+
+    pub fn stop_follow(&mut self) {
+        if let Some(task) = self.follow_task.take() {
+            task.abort();
+        }
+        self.follow = false;
+        self.follow_rx = None;
+    }
+
+    /// Starts or stops following new events in a view whose range ends now, appending them
+    /// as they arrive. Toggled on during a load, following starts once the load finishes.
+    pub fn toggle_follow(&mut self) {
+        if self.follow {
+            self.stop_follow();
+            // Everything up to the last poll has been read
+            self.to_date = self.to_date.max(Local::now());
+            self.histogram = None;
+            self.set_status("Stopped following new events");
+            return;
+        }
+        if self.cloudwatch_client.is_none() || !self.aggregate_profiles.is_empty() {
+            self.set_status("Only a single profile's logs can be followed once connected");
+            return;
+        }
+        if self.to_date < Local::now() - TAIL_MAX_RANGE_AGE {
+            self.set_status("The range ends in the past; pick one ending now to follow it");
+            return;
+        }
+
+        if self.is_loading {
+            self.follow = true;
+        } else {
+            self.start_follow();
+        }
+        self.set_status(format!(
+            "Following new events every {}s",
+            FOLLOW_INTERVAL.as_secs()
+        ));
+    }
+
+    /// Stops an in-flight load (keeping what has arrived so far) or, once loaded, follow mode.
+    pub fn cancel_load(&mut self) {
+        if self.refreshing {
+            if let Some(task) = self.load_task.take() {
+                task.abort();
+            }
+            self.refreshing = false;
+            self.refresh_events.clear();
+            self.finish_load();
+            self.set_status("Refresh cancelled");
+        } else if self.is_loading {
+            self.follow = false;
+            self.stop_loading(Truncation::Cancelled);
+            self.set_status("Loading cancelled");
+        } else if self.follow {
+            self.stop_follow();
+            self.set_status("Stopped following new events");
+        }
+    }
+
+    fn stop_loading(&mut self, reason: Truncation) {
+        if let Some(task) = self.load_task.take() {
+            task.abort();
+        }
+        self.truncation = Some(reason);
+        self.finish_load();
+    }
+
+    fn finish_load(&mut self) {
+        if self.refreshing {
+            self.apply_refresh();
+        }
+        self.last_refreshed = Some(Instant::now());
+        if self.time_window.is_none() {
+            self.narrow_hint = self.densest_window();
+        }
+        self.fetch_stats.total_time = self.fetch_stats.started_at.map(|start| start.elapsed());
+        self.is_loading = false;
+        self.load_rx = None;
+        self.load_task = None;
+        // Following was toggled on during the load, however it ended
+        if self.follow && self.follow_task.is_none() {
+            self.start_follow();
+        }
+    }
+
+    fn append_logs(&mut self, events: Vec<FilteredLogEvent>) {
+        self.histogram = None;
+        let matching: Vec<FilteredLogEvent> = events
+            .iter()
+            .filter(|log| self.matches_filter(log))
+            .cloned()
+            .collect();
+
+        self.classify(&events);
+        self.logs.extend(events);
+        if self.settings.order_by == TimeField::Ingestion && !matching.is_empty() {
+            // Late arrivals can land anywhere, so the selected event is looked up again
+            let position = self.read_position();
+            self.filtered_logs.extend(matching);
+            self.order_filtered();
+            self.restore_read_position(position);
+        } else {
+            self.filtered_logs.extend(matching);
+        }
+
+        // Select the first event once some arrive, but leave an existing selection alone
+        self.clamp_selection();
+    }
+
+    /// Replaces the loaded events with `events`, read from somewhere other than a load.
+    pub fn set_logs(&mut self, events: Vec<FilteredLogEvent>) {
+        self.logs = events;
+        self.classify_all();
+        self.update_filter();
+    }
+
+    /// Notes which of `events` look like stderr output, so drawing needn't parse them.
+    fn classify(&mut self, events: &[FilteredLogEvent]) {
+        let stderr = events
+            .iter()
+            .filter(|event| event.message.as_deref().is_some_and(looks_like_stderr))
+            .map(event_key);
+        self.stderr_events.extend(stderr);
+    }
+
+    fn classify_all(&mut self) {
+        let logs = std::mem::take(&mut self.logs);
+        self.stderr_events.clear();
+        self.classify(&logs);
+        self.logs = logs;
+    }
+
+    /// Whether the event looks like stderr output, as judged when it was loaded.
+    pub fn is_stderr(&self, event: &FilteredLogEvent) -> bool {
+        self.stderr_events.contains(&event_key(event))
+    }
+
+    /// Merges events into their place in time order, for pages that can land anywhere in
+    /// the range. Only the new events are checked against the filter.
+    fn merge_logs(&mut self, events: Vec<FilteredLogEvent>) {
+        self.histogram = None;
+        let matching: Vec<FilteredLogEvent> = events
+            .iter()
+            .filter(|log| self.matches_filter(log))
+            .cloned()
+            .collect();
+        self.classify(&events);
+        merge_by(&mut self.logs, events, event_time);
+
+        if !matching.is_empty() {
+            let position = self.read_position();
+            let order = match self.settings.order_by {
+                TimeField::Event => event_time,
+                TimeField::Ingestion => ingestion_order,
+            };
+            merge_by(&mut self.filtered_logs, matching, order);
+            self.restore_read_position(position);
+        }
+        self.clamp_selection();
+    }
+
+    /// Orders the listed events by ingestion time when asked to; they are otherwise left in
+    /// the order they were loaded, which is by event time.
+    fn order_filtered(&mut self) {
+        if self.settings.order_by == TimeField::Ingestion {
+            self.filtered_logs.sort_by_key(ingestion_order);
+        }
+    }
+
+    /// Switches the list between event-time and ingestion-time order, keeping the selection.
+    pub fn toggle_order_by(&mut self) {
+        self.settings.order_by = self.settings.order_by.toggle();
+        self.refilter_keeping_position();
+        self.set_status(format!(
+            "Listing events by {}",
+            self.settings.order_by.label()
+        ));
+    }
+
+    fn matches_filter(&self, log: &FilteredLogEvent) -> bool {
+        let in_window = match (self.time_window, self.time_of(log)) {
+            (Some((start, end)), Some(timestamp)) => (start..end).contains(&timestamp),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let in_stream = self
+            .only_stream
+            .as_ref()
+            .is_none_or(|stream| log.log_stream_name.as_ref() == Some(stream));
+        in_window && in_stream && self.matches_query(log)
+    }
+
+    /// The time of an event on the axis the histogram and its windows use.
+    fn time_of(&self, log: &FilteredLogEvent) -> Option<i64> {
+        match self.settings.time_field {
+            TimeField::Event => log.timestamp,
+            TimeField::Ingestion => log.ingestion_time,
+        }
+    }
+
+    /// Switches the histogram and the narrowed window between event and ingestion time.
+    /// A window brushed on one axis means nothing on the other, so it is dropped.
+    pub fn toggle_time_field(&mut self) {
+        self.settings.time_field = self.settings.time_field.toggle();
+        self.histogram_cursor = None;
+        self.histogram = None;
+        self.narrow_hint = None;
+        if self.time_window.take().is_some() {
+            self.refilter_keeping_position();
+        }
+        self.set_status(format!(
+            "Time windows by {}",
+            self.settings.time_field.label()
+        ));
+    }
+
+    /// The text filter alone, ignoring the histogram window.
+    fn matches_query(&self, log: &FilteredLogEvent) -> bool {
+        if self.filter_query.is_empty() {
+            return true;
+        }
+
+        log.message
+            .as_ref()
+            .is_some_and(|message| self.filter_query.matches(message))
+    }
+
+    /// Empties the filter, which also forgets the one remembered for the function.
+    pub fn clear_filter(&mut self) {
+        self.filter_input.clear();
+        self.update_filter();
+        self.set_status("Filter cleared");
+    }
+
+    pub fn update_filter(&mut self) {
+        self.filter_query = FilterQuery::parse(&self.filter_input);
+        // A range picked under the old filter covers different events under the new one
+        self.selection_anchor = None;
+        self.refilter_keeping_position();
+
+        if self.settings.expand_single_result
+            && !self.expanded
+            && self.filtered_logs.len() == 1
+            && !self.filter_input.trim().is_empty()
+        {
+            // Typing in the filter carries on; only the list gives way to the event
+            let focus = self.focus;
+            self.toggle_expand();
+            if focus == LogPanel::Filter {
+                self.focus = focus;
+            }
+        }
+    }
+
+    /// Sets the active filter aside and starts a new empty one in a tab after it.
+    pub fn new_filter_tab(&mut self) {
+        self.store_filter_tab();
+        self.active_tab += 1;
+        self.filter_tabs
+            .insert(self.active_tab, FilterTab::default());
+        self.load_filter_tab();
+        self.focus = LogPanel::Filter;
+    }
+
+    /// Switches to the next or previous filter tab, wrapping around.
+    pub fn switch_filter_tab(&mut self, direction: isize) {
+        if self.filter_tabs.len() < 2 {
+            return;
+        }
+        self.store_filter_tab();
+        self.active_tab = (self.active_tab as isize + direction)
+            .rem_euclid(self.filter_tabs.len() as isize) as usize;
+        self.load_filter_tab();
+    }
+
+    /// Drops the active filter tab and moves to the one before it. The last tab stays.
+    pub fn close_filter_tab(&mut self) {
+        if self.filter_tabs.len() < 2 {
+            self.set_status("The last filter tab can't be closed");
+            return;
+        }
+        self.filter_tabs.remove(self.active_tab);
+        self.active_tab = self.active_tab.saturating_sub(1);
+        self.load_filter_tab();
+    }
+
+    fn store_filter_tab(&mut self) {
+        self.filter_tabs[self.active_tab] = FilterTab {
+            input: self.filter_input.clone(),
+            selected_log: self.selected_log,
+            start_index: self.start_index,
+        };
+    }
+
+    fn load_filter_tab(&mut self) {
+        let tab = self.filter_tabs[self.active_tab].clone();
+        self.filter_input = tab.input;
+        // Each tab comes back to its own list, not to the event open in the last one
+        self.expanded = false;
+        self.update_filter();
+        self.selected_log = tab.selected_log;
+        self.start_index = tab.start_index;
+        self.clamp_selection();
+    }
+
+    /// Adds the constant part of the selected message to the filter as an excluded phrase,
+    /// hiding every line of its kind. The selection stays where it was in the list.
+    pub fn mute_selected(&mut self) {
+        let Some(message) = self
+            .get_selected_log()
+            .and_then(|log| log.message.as_deref())
+        else {
+            self.set_status("No event selected");
+            return;
+        };
+        let Some(phrase) = constant_phrase(message) else {
+            self.set_status("No constant part of the message to filter out");
+            return;
+        };
+
+        let term = format!("-\"{}\"", phrase);
+        if !self.filter_input.trim().is_empty() {
+            self.filter_input = format!("{} ", self.filter_input.trim_end());
+        }
+        self.filter_input.push_str(&term);
+        let selected = self.selected_log;
+        self.update_filter();
+        self.selected_log = selected;
+        self.clamp_selection();
+        self.set_status(format!("Added {} to the filter", term));
+    }
+
+    /// Opens a filter tab with every loaded event that shares the selected one's correlation
+    /// ID, keeping the current filter in its own tab.
+    pub fn follow_correlation_id(&mut self) {
+        let Some(selected) = self.get_selected_log() else {
+            self.set_status("No event selected");
+            return;
+        };
+        let key = event_key(selected);
+        let message = selected.message.clone().unwrap_or_default();
+        let pattern = match self.settings.correlation_pattern.as_deref().map(Regex::new) {
+            Some(Ok(pattern)) => Some(pattern),
+            Some(Err(e)) => {
+                self.set_status(format!("correlation_pattern is not a valid regex: {}", e));
+                return;
+            }
+            None => None,
+        };
+        let Some(id) = correlation_id(&message, &self.settings.correlation_keys, pattern.as_ref())
+        else {
+            self.set_status("The selected event has no correlation ID");
+            return;
+        };
+
+        self.new_filter_tab();
+        self.filter_input = format!("\"{}\"", id.replace('"', ""));
+        self.update_filter();
+        self.focus = LogPanel::List;
+        self.selected_log = self
+            .filtered_logs
+            .iter()
+            .position(|log| event_key(log) == key)
+            .or(self.selected_log);
+        self.set_status(format!(
+            "{} events share correlation ID {} (Ctrl+Q closes the tab)",
+            self.filtered_logs.len(),
+            id
+        ));
+    }
+
+    /// Opens the reading view on the selected event, or closes it.
+    pub fn toggle_reading_view(&mut self) {
+        if self.reading.take().is_some() {
+            return;
+        }
+        if self.filtered_logs.is_empty() {
+            self.set_status("No events to read");
+            return;
+        }
+        if self.filtered_logs.len() > READING_VIEW_MAX_EVENTS {
+            self.set_status(format!(
+                "Narrow the filter to at most {} events to read them in full",
+                READING_VIEW_MAX_EVENTS
+            ));
+            return;
+        }
+
+        let mut reading = self.build_reading_view();
+        reading.scroll = self
+            .selected_log
+            .and_then(|i| reading.event_starts.get(i).copied())
+            .unwrap_or(0);
+        self.expanded = false;
+        self.focus = LogPanel::List;
+        self.reading = Some(reading);
+    }
+
+    /// Lays the reading view out again for the current filter, from its top.
+    fn refresh_reading_view(&mut self) {
+        if self.reading.is_none() {
+            return;
+        }
+        if self.filtered_logs.is_empty() || self.filtered_logs.len() > READING_VIEW_MAX_EVENTS {
+            self.reading = None;
+            return;
+        }
+        let height = self.reading.as_ref().map_or(0, |r| r.height);
+        self.reading = Some(ReadingView {
+            height,
+            ..self.build_reading_view()
+        });
+    }
+
+    fn build_reading_view(&self) -> ReadingView {
+        let mut reading = ReadingView::default();
+        for log in &self.filtered_logs {
+            reading.event_starts.push(reading.lines.len());
+            reading.lines.push(Line::styled(
+                format!(
+                    "── {} {} ──",
+                    format_timestamp(
+                        log.timestamp.unwrap_or(0),
+                        self.settings.timestamp_mode,
+                        true
+                    ),
+                    log.log_stream_name.as_deref().unwrap_or("")
+                ),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            let (lines, _) = format_log_message(
+                log.message.as_deref().unwrap_or(""),
+                Some(EXPANDED_LINE_LIMIT),
+                self.settings.json_view,
+                self.settings.collapse_stack_traces,
+            );
+            reading.lines.extend(lines);
+            reading.lines.push(Line::default());
+        }
+        reading
+    }
+
+    /// Scrolls the reading view, keeping the list selection on the event at the top.
+    pub fn scroll_reading(&mut self, delta: isize) {
+        let Some(reading) = &mut self.reading else {
+            return;
+        };
+        reading.scroll = reading
+            .scroll
+            .saturating_add_signed(delta)
+            .min(reading.max_scroll());
+        self.selected_log = Some(reading.current_event());
+    }
+
+    /// Brings the start of the next or previous event to the top of the reading view.
+    pub fn jump_reading_event(&mut self, direction: i32) {
+        let Some(reading) = &mut self.reading else {
+            return;
+        };
+        let current = reading.current_event();
+        let target = if direction > 0 {
+            (current + 1).min(reading.event_starts.len() - 1)
+        } else if reading.event_starts[current] < reading.scroll {
+            // Partway into an event, going back starts with that event's own top
+            current
+        } else {
+            current.saturating_sub(1)
+        };
+        reading.scroll = reading.event_starts[target].min(reading.max_scroll());
+        self.selected_log = Some(target);
+    }
+
+    pub fn scroll_up(&mut self) {
+        if self.expanded {
+            self.scroll_position = self.scroll_position.saturating_sub(1);
+        }
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll_position = self
+            .scroll_position
+            .saturating_add(1)
+            .min(self.max_expanded_scroll());
+    }
+
+    /// Furthest the expanded message scrolls: its last row at the bottom of the view.
+    pub fn max_expanded_scroll(&self) -> usize {
+        self.expanded_content.as_ref().map_or(0, |content| {
+            content
+                .row_count()
+                .saturating_sub(self.detail_height.max(1))
+        })
+    }
+
+    pub fn toggle_debug(&mut self) {
+        self.show_debug = !self.show_debug;
+    }
+
+    pub fn toggle_strip_timestamps(&mut self) {
+        self.settings.strip_timestamps = !self.settings.strip_timestamps;
+        self.set_status(if self.settings.strip_timestamps {
+            "Hiding timestamp prefixes in messages"
+        } else {
+            "Showing messages as logged"
+        });
+    }
+
+    pub fn toggle_collapse_whitespace(&mut self) {
+        self.settings.collapse_whitespace = !self.settings.collapse_whitespace;
+        self.reformat_expanded();
+        self.set_status(if self.settings.collapse_whitespace {
+            "Collapsing indentation and repeated spaces"
+        } else {
+            "Showing whitespace as logged"
+        });
+    }
+
+    pub fn toggle_histogram(&mut self) {
+        self.settings.show_histogram = !self.settings.show_histogram;
+        if !self.settings.show_histogram {
+            self.histogram_cursor = None;
+        }
+    }
+
+    /// Events per bucket that match the text filter, whatever window is brushed. Counted
+    /// once after the events, filter or range last changed.
+    pub fn histogram(&mut self) -> &Histogram {
+        if self.histogram.is_none() {
+            self.histogram = Some(self.count_histogram());
+        }
+        self.histogram.as_ref().expect("counted above")
+    }
+
+    fn count_histogram(&self) -> Histogram {
+        let (from, to) = (
+            self.from_date.timestamp_millis(),
+            self.to_date.timestamp_millis(),
+        );
+        let (first, last) = self
+            .logs
+            .iter()
+            .filter_map(|e| self.time_of(e))
+            .fold((from, to - 1), |(first, last), time| {
+                (first.min(time), last.max(time))
+            });
+        let range = (first, (last + 1).max(first + HISTOGRAM_BUCKETS as i64));
+
+        let mut counts = vec![0; HISTOGRAM_BUCKETS];
+        for timestamp in self
+            .logs
+            .iter()
+            .filter(|log| self.matches_query(log))
+            .filter_map(|log| self.time_of(log))
+        {
+            counts[bucket_of(timestamp, range)] += 1;
+        }
+        Histogram { range, counts }
+    }
+
+    /// Moves the histogram cursor. The first press picks the selected event's bucket.
+    pub fn move_histogram_cursor(&mut self, direction: i32) {
+        self.histogram_cursor = Some(match self.histogram_cursor {
+            Some(current) if direction > 0 => (current + 1).min(HISTOGRAM_BUCKETS - 1),
+            Some(current) => current.saturating_sub(1),
+            None => {
+                let range = self.histogram().range;
+                self.get_selected_log()
+                    .and_then(|log| self.time_of(log))
+                    .map_or(0, |timestamp| bucket_of(timestamp, range))
+            }
+        });
+    }
+
+    /// Narrows the list to the bucket under the histogram cursor.
+    pub fn brush_histogram_bucket(&mut self) {
+        let Some(bucket) = self.histogram_cursor else {
+            self.set_status("Pick a histogram bucket with ←/→ first");
+            return;
+        };
+        let window = self.histogram().bucket_bounds(bucket);
+        self.narrow_hint = None;
+        self.time_window = Some(window);
+        self.refilter_keeping_position();
+        self.set_status(format!(
+            "Showing {} - {} ({} events, Esc: full range)",
+            millis_to_local(window.0).format("%H:%M:%S"),
+            millis_to_local(window.1).format("%H:%M:%S"),
+            self.filtered_logs.len()
+        ));
+    }
+
+    /// Finds the window, a tenth of the range wide, holding the most events matching the
+    /// filter. Only returned when the load is large and the window holds most of it.
+    fn densest_window(&self) -> Option<(i64, i64, usize)> {
+        let width = (self.to_date - self.from_date).num_milliseconds() / NARROW_HINT_WINDOW_DIVISOR;
+        let mut timestamps: Vec<i64> = self
+            .logs
+            .iter()
+            .filter(|log| self.matches_query(log))
+            .filter_map(|log| self.time_of(log))
+            .collect();
+        if width <= 0 || timestamps.len() < NARROW_HINT_MIN_EVENTS {
+            return None;
+        }
+        timestamps.sort_unstable();
+
+        // Slide the window's end over the events, pulling its start along behind
+        let mut best = (0, 0);
+        let mut first = 0;
+        for (last, &timestamp) in timestamps.iter().enumerate() {
+            while timestamp - timestamps[first] >= width {
+                first += 1;
+            }
+            if last + 1 - first > best.1 {
+                best = (first, last + 1 - first);
+            }
+        }
+
+        let (first, count) = best;
+        (count as f64 >= timestamps.len() as f64 * NARROW_HINT_MIN_SHARE)
+            .then(|| (timestamps[first], timestamps[first] + width, count))
+    }
+
+    /// Narrows the list to the window the hint suggested.
+    pub fn apply_narrow_hint(&mut self) {
+        let Some((start, end, _)) = self.narrow_hint.take() else {
+            return;
+        };
+        self.time_window = Some((start, end));
+        self.refilter_keeping_position();
+        self.set_status(format!(
+            "Showing {} - {} ({} events, Esc: full range)",
+            millis_to_local(start).format("%H:%M:%S"),
+            millis_to_local(end).format("%H:%M:%S"),
+            self.filtered_logs.len()
+        ));
+    }
+
+    /// Shifts the range by half its length, earlier (-1) or later (1), and reads it again
+    /// once nudging stops. The range never moves past now. Returns whether it moved.
+    pub fn shift_range(&mut self, direction: i32) -> bool {
+        if !self.can_nudge_range() {
+            return false;
+        }
+        let length = self.to_date - self.from_date;
+        let mut shift = length / 2 * direction;
+        let now = Local::now();
+        if self.to_date + shift > now {
+            shift = (now - self.to_date).max(chrono::Duration::zero());
+        }
+        self.set_range(self.from_date + shift, self.to_date + shift)
+    }
+
+    /// Halves (`zoom_in`) or doubles the range around the selected event, or around the
+    /// middle when the selection is outside it, and reads it again once nudging stops.
+    /// Returns whether the range changed.
+    pub fn zoom_range(&mut self, zoom_in: bool) -> bool {
+        if !self.can_nudge_range() {
+            return false;
+        }
+        let length = self.to_date - self.from_date;
+        let center = self
+            .get_selected_log()
+            .and_then(|log| log.timestamp)
+            .map(millis_to_local)
+            .filter(|time| (self.from_date..=self.to_date).contains(time))
+            .unwrap_or(self.from_date + length / 2);
+        let length = if zoom_in {
+            (length / 2).max(MIN_NUDGED_RANGE)
+        } else {
+            length * 2
+        };
+
+        // The selected event keeps its place in the range, e.g. a quarter of the way in
+        let share = (center - self.from_date).num_milliseconds() as f64
+            / self
+                .to_date
+                .signed_duration_since(self.from_date)
+                .num_milliseconds()
+                .max(1) as f64;
+        let before =
+            chrono::Duration::milliseconds((length.num_milliseconds() as f64 * share) as i64);
+        let from = center - before;
+        let to = (from + length).min(Local::now());
+        self.set_range(to - length, to)
+    }
+
+    fn can_nudge_range(&mut self) -> bool {
+        if self.latest_count.is_some() || self.follow {
+            self.set_status("This view has no range to move");
+            return false;
+        }
+        if self.cloudwatch_client.is_none() || !self.aggregate_profiles.is_empty() {
+            self.set_status("The range can only be moved once the first load has connected");
+            return false;
+        }
+        true
+    }
+
+    /// Moves to a nudged range, to be read once nudging stops. A range that came out the
+    /// same, as when it already ends now, is not read again.
+    fn set_range(&mut self, from_date: DateTime<Local>, to_date: DateTime<Local>) -> bool {
+        let moved =
+            |new: DateTime<Local>, old: DateTime<Local>| (new - old).abs() >= MIN_RANGE_MOVE;
+        if !moved(from_date, self.from_date) && !moved(to_date, self.to_date) {
+            self.set_status("The range can't move any further that way");
+            return false;
+        }
+        self.from_date = from_date;
+        self.to_date = to_date;
+        // Windows and buckets were picked within the old range
+        self.time_window = None;
+        self.histogram_cursor = None;
+        self.histogram = None;
+        self.narrow_hint = None;
+        self.nudged_at = Some(Instant::now());
+        self.set_status(format!(
+            "Range {} - {}; reading it once you stop",
+            from_date.format("%Y-%m-%d %H:%M:%S"),
+            to_date.format("%Y-%m-%d %H:%M:%S")
+        ));
+        true
+    }
+
+    /// Drops the brushed window and lists the whole range again.
+    pub fn clear_time_window(&mut self) {
+        self.time_window = None;
+        self.refilter_keeping_position();
+        self.set_status("Showing the full range");
+    }
+
+    pub fn cycle_timestamp_mode(&mut self) {
+        self.settings.timestamp_mode = self.settings.timestamp_mode.next();
+        self.set_status(format!(
+            "Timestamps in {}",
+            self.settings.timestamp_mode.label()
+        ));
+    }
+
+    pub fn toggle_timestamp_millis(&mut self) {
+        self.settings.timestamp_millis = !self.settings.timestamp_millis;
+        self.set_status(if self.settings.timestamp_millis {
+            "Showing milliseconds in the list"
+        } else {
+            "Showing whole seconds in the list"
+        });
+    }
+
+    pub fn toggle_center_selection(&mut self) {
+        self.settings.center_selection = !self.settings.center_selection;
+        self.set_status(if self.settings.center_selection {
+            "Keeping the selection centered"
+        } else {
+            "Scrolling the list at its edges"
+        });
+    }
+
+    pub fn toggle_stderr_marks(&mut self) {
+        self.settings.mark_stderr = !self.settings.mark_stderr;
+        self.set_status(if self.settings.mark_stderr {
+            "Marking stderr-like events"
+        } else {
+            "Stopped marking stderr-like events"
+        });
+    }
+
+    /// Collapsing remembers where the event was scrolled to, so re-expanding the same event
+    /// returns there. Any other event opens at the top.
+    pub fn toggle_expand(&mut self) {
+        let position = self.read_position();
+        if self.expanded {
+            self.last_expanded = position;
+        } else {
+            self.scroll_position = position.map_or(0, |position| position.scroll);
+            if self.expanded_content.as_ref().map(|c| c.key) != position.map(|p| p.event) {
+                self.format_expanded(Some(EXPANDED_LINE_LIMIT));
+            }
+        }
+        self.expanded = !self.expanded;
+        self.scroll_offset = 0;
+        // The detail panel replaces the list, so focus follows it in and out
+        self.focus = if self.expanded {
+            LogPanel::Detail
+        } else {
+            LogPanel::List
+        };
+    }
+
+    /// Moves focus to the next panel on screen. Returns true when it wrapped past the last.
+    pub fn focus_next(&mut self) -> bool {
+        let (focus, wrapped) = match (self.focus, self.expanded) {
+            (LogPanel::Filter, false) => (LogPanel::List, false),
+            (LogPanel::Filter, true) => (LogPanel::Detail, false),
+            (LogPanel::List | LogPanel::Detail, _) => (LogPanel::Filter, true),
+        };
+        self.focus = focus;
+        wrapped
+    }
+
+    /// Hides or brings back the panels around the logs.
+    pub fn toggle_full_width(&mut self) {
+        self.full_width = !self.full_width;
+        if self.full_width && self.focus == LogPanel::Filter {
+            self.focus = if self.expanded {
+                LogPanel::Detail
+            } else {
+                LogPanel::List
+            };
+        }
+    }
+
+    /// Switches the detail view between pretty, compact and raw JSON.
+    pub fn cycle_json_view(&mut self) {
+        self.settings.json_view = self.settings.json_view.next();
+        self.reformat_expanded();
+        self.set_status(format!("JSON layout: {}", self.settings.json_view.label()));
+    }
+
+    /// Shows long stack traces in full, or only their top frames.
+    pub fn toggle_stack_traces(&mut self) {
+        self.settings.collapse_stack_traces = !self.settings.collapse_stack_traces;
+        self.reformat_expanded();
+        self.set_status(if self.settings.collapse_stack_traces {
+            "Collapsing long stack traces"
+        } else {
+            "Showing stack traces in full"
+        });
+    }
+
+    /// Lays the expanded message out again after a layout setting changed. The scroll
+    /// position is kept when it still falls inside the message.
+    fn reformat_expanded(&mut self) {
+        if self.expanded {
+            let limit = match &self.expanded_content {
+                Some(content) if !content.is_partial() => None,
+                _ => Some(EXPANDED_LINE_LIMIT),
+            };
+            // Laid out at the old width, so the scroll stays a row
+            let layout = self
+                .expanded_content
+                .as_ref()
+                .and_then(|c| c.wrapped.as_ref())
+                .map(|wrapped| (wrapped.width, wrapped.indent));
+            self.format_expanded(limit);
+            if let (Some(content), Some((width, indent))) = (self.expanded_content.as_mut(), layout)
+            {
+                content.wrap(width, indent);
+            }
+            self.scroll_position = self.scroll_position.min(self.max_expanded_scroll());
+        } else {
+            // Formatted for the old layout; the next expand formats it again
+            self.expanded_content = None;
+        }
+        if let Some(mut pinned) = self.pinned.take() {
+            pinned.content = self.format_event(&pinned.event, Some(EXPANDED_LINE_LIMIT));
+            pinned.scroll = pinned.scroll.min(pinned.max_scroll());
+            self.pinned = Some(pinned);
+        }
+        self.last_expanded = None;
+    }
+
+    /// Formats the rest of a message that was cut off at `EXPANDED_LINE_LIMIT` lines.
+    pub fn load_full_message(&mut self) {
+        if self
+            .expanded_content
+            .as_ref()
+            .is_some_and(|c| c.is_partial())
+        {
+            self.format_expanded(None);
+            self.set_status("Loaded the rest of the message");
+        }
+    }
+
+    fn format_expanded(&mut self, max_lines: Option<usize>) {
+        self.expanded_content = self
+            .get_selected_log()
+            .map(|log| self.format_event(log, max_lines));
+    }
+
+    fn format_event(&self, log: &FilteredLogEvent, max_lines: Option<usize>) -> ExpandedContent {
+        let message = log.message.as_deref().unwrap_or("");
+        // JSON is laid out afresh anyway, and the spaces in its strings stay as logged
+        let collapsed;
+        let message = if self.settings.collapse_whitespace
+            && serde_json::from_str::<serde_json::Value>(message).is_err()
+        {
+            collapsed = collapse_whitespace(message);
+            collapsed.as_str()
+        } else {
+            message
+        };
+        let (lines, total_lines) = format_log_message(
+            message,
+            max_lines,
+            self.settings.json_view,
+            self.settings.collapse_stack_traces,
+        );
+        let sections = match self.settings.json_view {
+            JsonView::Raw => Vec::new(),
+            _ => json_sections(log.message.as_deref().unwrap_or(""), &lines),
+        };
+        ExpandedContent {
+            key: event_key(log),
+            lines,
+            total_lines,
+            sections,
+            wrapped: None,
+        }
+    }
+
+    /// Keeps the selected event on screen below the list, or lets go of the pinned one.
+    pub fn toggle_pin(&mut self) {
+        if self.pinned.take().is_some() {
+            self.set_status("Unpinned");
+            return;
+        }
+        if let Some(event) = self.get_selected_log().cloned() {
+            let content = self.format_event(&event, Some(EXPANDED_LINE_LIMIT));
+            self.pinned = Some(PinnedEvent {
+                event,
+                content,
+                scroll: 0,
+                height: self.detail_height,
+            });
+            self.set_status("Pinned the event below the list");
+        }
+    }
+
+    pub fn scroll_pinned(&mut self, delta: isize) {
+        if let Some(pinned) = &mut self.pinned {
+            pinned.scroll = pinned
+                .scroll
+                .saturating_add_signed(delta)
+                .min(pinned.max_scroll());
+        }
+    }
+
+    /// Opens the selected event's fields as a flat table, or closes it.
+    pub fn toggle_fields(&mut self) {
+        if self.fields.take().is_some() {
+            return;
+        }
+        let Some(message) = self
+            .get_selected_log()
+            .and_then(|log| log.message.as_deref())
+        else {
+            self.set_status("No event selected");
+            return;
+        };
+        match serde_json::from_str::<serde_json::Value>(message.trim()) {
+            Ok(json @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) => {
+                self.fields = Some(FieldsView {
+                    rows: flatten_json(&json),
+                    scroll: 0,
+                });
+            }
+            _ => self.set_status("Event is not structured"),
+        }
+    }
+
+    pub fn scroll_fields(&mut self, delta: isize) {
+        if let Some(fields) = &mut self.fields {
+            fields.scroll = fields
+                .scroll
+                .saturating_add_signed(delta)
+                .min(fields.rows.len().saturating_sub(1));
+        }
+    }
+
+    /// Copies the open field table as `key=value` lines.
+    pub fn copy_fields(&mut self) {
+        let Some(fields) = &self.fields else {
+            return;
+        };
+        let text = fields
+            .rows
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let count = fields.rows.len();
+
+        match copy_to_clipboard(&text) {
+            Ok(()) => self.set_status(format!("Copied {} field(s)", count)),
+            Err(e) => self.set_status(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Scrolls the expanded message to the start of the next or previous top-level field.
+    pub fn jump_section(&mut self, direction: i32) {
+        let Some(content) = &self.expanded_content else {
+            return;
+        };
+        let line = content.line_at(self.scroll_position);
+        let target = if direction > 0 {
+            content.sections.iter().find(|(start, _)| *start > line)
+        } else {
+            content
+                .sections
+                .iter()
+                .rev()
+                .find(|(start, _)| *start < line)
+        };
+        match target {
+            Some((start, _)) => {
+                self.scroll_position = content.row_of(*start).min(self.max_expanded_scroll())
+            }
+            None if content.sections.is_empty() => {
+                self.set_status("Only JSON objects can be paged by field")
+            }
+            None => {}
+        }
+    }
+
+    /// Starts typing a find term, searching from the selected event.
+    pub fn start_find(&mut self) {
+        self.finding = true;
+        self.find_input.clear();
+        self.find_origin = self.selected_log;
+    }
+
+    /// Extends or shortens the find term and moves to the first match from where the find began.
+    pub fn edit_find(&mut self, c: Option<char>) {
+        match c {
+            Some(c) => self.find_input.push(c),
+            None => {
+                self.find_input.pop();
+            }
+        }
+        let origin = self.find_origin.unwrap_or(0);
+        self.select_found(self.find_match(origin, 1).unwrap_or(origin));
+    }
+
+    pub fn finish_find(&mut self) {
+        self.finding = false;
+    }
+
+    /// Stops typing and goes back to the event the find started from.
+    pub fn cancel_find(&mut self) {
+        self.finding = false;
+        self.find_input.clear();
+        if let Some(origin) = self.find_origin.take() {
+            self.select_found(origin);
+        }
+    }
+
+    /// Moves to the next (1) or previous (-1) event containing the find term, wrapping around.
+    pub fn find_next(&mut self, direction: i32) {
+        if self.find_input.is_empty() {
+            self.set_status("Press ? to find text in the listed events");
+            return;
+        }
+        let Some(current) = self.selected_log else {
+            return;
+        };
+        let len = self.filtered_logs.len();
+        let start = if direction > 0 {
+            (current + 1) % len.max(1)
+        } else {
+            (current + len - 1) % len.max(1)
+        };
+        let Some(index) = self.find_match(start, direction) else {
+            self.set_status(format!("No listed event contains '{}'", self.find_input));
+            return;
+        };
+        self.select_found(index);
+
+        let wrapped = if direction > 0 {
+            index <= current
+        } else {
+            index >= current
+        };
+        if index == current {
+            self.set_status("No other listed event contains it");
+        } else if wrapped {
+            self.set_status(if direction > 0 {
+                "Find wrapped around to the first event"
+            } else {
+                "Find wrapped around to the last event"
+            });
+        }
+    }
+
+    /// First event from `start` on, going in `direction` and wrapping around, that contains the
+    /// find term (case-insensitively).
+    fn find_match(&self, start: usize, direction: i32) -> Option<usize> {
+        let len = self.filtered_logs.len();
+        if len == 0 || self.find_input.is_empty() {
+            return None;
+        }
+        let term = self.find_input.to_lowercase();
+        let start = start.min(len - 1);
+        (0..len)
+            .map(|step| {
+                if direction > 0 {
+                    (start + step) % len
+                } else {
+                    (start + len - step) % len
+                }
+            })
+            .find(|&index| {
+                let message = self.filtered_logs[index].message.as_deref().unwrap_or("");
+                message.to_lowercase().contains(&term)
+            })
+    }
+
+    fn select_found(&mut self, index: usize) {
+        if index < self.filtered_logs.len() {
+            self.selected_log = Some(index);
+            self.selection_anchor = None;
+            self.scroll_offset = 0;
+            if self.expanded {
+                // The detail view shows the found event from its top
+                self.format_expanded(Some(EXPANDED_LINE_LIMIT));
+                self.scroll_position = 0;
+            }
+        }
+    }
+
+    /// The selected message to open in an external editor, pretty-printed when it is JSON,
+    /// with the file extension that suits it.
+    pub fn editor_contents(&self) -> Option<(String, &'static str)> {
+        let message = self.get_selected_log()?.message.as_deref()?;
+        Some(
+            match serde_json::from_str::<serde_json::Value>(message)
+                .ok()
+                .and_then(|json| serde_json::to_string_pretty(&json).ok())
+            {
+                Some(pretty) => (pretty, "json"),
+                None => (message.to_string(), "log"),
+            },
+        )
+    }
+
+    /// Whether the event came in after this function's logs were last closed.
+    pub fn is_new_since_last_visit(&self, event: &FilteredLogEvent) -> bool {
+        self.last_visit
+            .zip(event.timestamp)
+            .is_some_and(|(visit, timestamp)| timestamp > visit)
+    }
+
+    /// Index of the first listed event that is new since the last visit.
+    pub fn first_new_event(&self) -> Option<usize> {
+        self.filtered_logs
+            .iter()
+            .position(|event| self.is_new_since_last_visit(event))
+    }
+
+    pub fn get_selected_log(&self) -> Option<&FilteredLogEvent> {
+        self.selected_log.and_then(|i| self.filtered_logs.get(i))
+    }
+
+    /// Rows a PageUp/PageDown in the detail view moves by.
+    pub fn page_jump(&self) -> usize {
+        self.settings.page_jump.unwrap_or(self.detail_height).max(1)
+    }
+
+    /// Grows or shrinks the page jump by `delta` lines, starting from the current one.
+    pub fn adjust_page_jump(&mut self, delta: isize) {
+        let lines = self.page_jump().saturating_add_signed(delta).max(1);
+        self.settings.page_jump = Some(lines);
+        self.set_status(format!("Page jump: {} lines", lines));
+    }
+
+    /// Goes back to paging by a screenful.
+    pub fn reset_page_jump(&mut self) {
+        self.settings.page_jump = None;
+        self.set_status("Page jump: one screen");
+    }
+
+    pub fn page_up(&mut self) {
+        if self.expanded {
+            self.scroll_position = self.scroll_position.saturating_sub(self.page_jump());
+        }
+    }
+
+    pub fn page_down(&mut self) {
+        if self.expanded {
+            self.scroll_position =
+                (self.scroll_position + self.page_jump()).min(self.max_expanded_scroll());
+        }
+    }
+
+    pub fn get_visible_range(&self, visible_height: usize) -> (usize, usize) {
+        let total_logs = self.filtered_logs.len();
+        let half_height = visible_height / 2;
+
+        if let (Some(selected), false) = (self.selected_log, self.settings.center_selection) {
+            // Scroll only as far as it takes to bring the selection into view
+            let start = if selected < self.start_index {
+                selected
+            } else if selected >= self.start_index + visible_height {
+                selected + 1 - visible_height.max(1)
+            } else {
+                self.start_index
+            };
+            let start = start.min(total_logs.saturating_sub(visible_height));
+            return (start, (start + visible_height).min(total_logs));
+        }
+
+        if let Some(selected) = self.selected_log {
+            // Calculate the ideal start position that would center the selected item
+            let ideal_start = selected.saturating_sub(half_height);
+
+            // Adjust start position if we're too close to the end
+            let start = if selected + half_height >= total_logs {
+                total_logs.saturating_sub(visible_height)
+            } else {
+                ideal_start
+            };
+
+            // Calculate end position
+            let end = (start + visible_height).min(total_logs);
+
+            (start, end)
+        } else {
+            (0, visible_height.min(total_logs))
+        }
+    }
+
+    pub fn move_selection(&mut self, direction: i32, visible_height: usize) {
+        self.selection_anchor = None;
+
+        let last = self.filtered_logs.len().saturating_sub(1);
+        let wrapped = match self.selected_log {
+            Some(0) if direction < 0 => Some(last),
+            Some(current) if direction > 0 && current >= last => Some(0),
+            _ => None,
+        };
+        match wrapped.filter(|_| self.settings.wrap_navigation && last > 0) {
+            Some(index) => {
+                self.selected_log = Some(index);
+                self.start_index = index.saturating_sub(visible_height.saturating_sub(1));
+                self.scroll_offset = 0;
+            }
+            None => self.step_selection(direction, visible_height),
+        }
+    }
+
+    /// Moves the selection while growing a range from where the selection started.
+    pub fn extend_selection(&mut self, direction: i32, visible_height: usize) {
+        if self.selection_anchor.is_none() {
+            self.selection_anchor = self.selected_log;
+        }
+        self.step_selection(direction, visible_height);
+    }
+
+    /// Inclusive bounds of the selected events, covering the anchor when a range is active.
+    pub fn selected_range(&self) -> Option<(usize, usize)> {
+        let selected = self.selected_log?;
+        let anchor = self.selection_anchor.unwrap_or(selected);
+        let end = selected
+            .max(anchor)
+            .min(self.filtered_logs.len().checked_sub(1)?);
+        Some((selected.min(anchor).min(end), end))
+    }
+
+    pub fn selected_events(&self) -> &[FilteredLogEvent] {
+        match self.selected_range() {
+            Some((start, end)) => &self.filtered_logs[start..=end],
+            None => &[],
+        }
+    }
+
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), Instant::now()));
+    }
+
+    pub fn status_message(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < STATUS_MESSAGE_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    /// Copies the first and last timestamps of the selected events as ISO-8601 intervals.
+    pub fn copy_time_range(&mut self) {
+        let events = self.selected_events();
+        let timestamps = events.iter().filter_map(|log| log.timestamp);
+        let (Some(start), Some(end)) = (timestamps.clone().min(), timestamps.max()) else {
+            self.set_status("No event selected");
+            return;
+        };
+        let count = events.len();
+
+        let (start_local, start_utc) = iso8601_pair(start);
+        let (end_local, end_utc) = iso8601_pair(end);
+        let text = format!(
+            "Local: {}/{}\nUTC: {}/{}",
+            start_local, end_local, start_utc, end_utc
+        );
+
+        match copy_to_clipboard(&text) {
+            Ok(()) => self.set_status(format!("Copied time range of {} event(s)", count)),
+            Err(e) => self.set_status(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Copies an AWS console link to the log group (or the stream, when one is open) over the
+    /// viewer's range, for pasting into a ticket or chat.
+    pub fn copy_console_link(&mut self) {
+        let Some(region) = self.region.clone().filter(|region| !region.is_empty()) else {
+            self.set_status("No single region to link to");
+            return;
+        };
+        let url = console_logs_url(
+            &region,
+            &self.log_group_name(),
+            self.log_stream_name.as_deref(),
+            self.from_date.timestamp_millis(),
+            self.to_date.timestamp_millis(),
+        );
+
+        match copy_to_clipboard(&url) {
+            Ok(()) => self.set_status("Copied console link"),
+            Err(e) => self.set_status(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Copies the log group's name, e.g. for AWS CLI commands.
+    pub fn copy_log_group(&mut self) {
+        let log_group = self.log_group_name();
+        match copy_to_clipboard(&log_group) {
+            Ok(()) => self.set_status(format!("Copied {}", log_group)),
+            Err(e) => self.set_status(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Copies the selected event, metadata included, as pretty-printed JSON.
+    pub fn copy_event_json(&mut self) {
+        let Some(event) = self.get_selected_log() else {
+            self.set_status("No event selected");
+            return;
+        };
+        let text = serde_json::to_string_pretty(&event_to_json(event)).unwrap_or_default();
+
+        match copy_to_clipboard(&text) {
+            Ok(()) => self.set_status("Copied event as JSON"),
+            Err(e) => self.set_status(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Copies the events currently on screen, one line each.
+    pub fn copy_visible(&mut self) {
+        let (start, end) = self.get_visible_range(self.page_size);
+        if start == end {
+            self.set_status("No events to copy");
+            return;
+        }
+        let text = self.filtered_logs[start..end]
+            .iter()
+            .map(event_to_text)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match copy_to_clipboard(&text) {
+            Ok(()) => self.set_status(format!("Copied {} visible event(s)", end - start)),
+            Err(e) => self.set_status(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Copies the selected range, or every listed event when no range is selected, as a
+    /// Markdown code block for pasting into an issue or pull request.
+    pub fn copy_markdown(&mut self) {
+        let (events, what) = if self.selection_anchor.is_some() {
+            (self.selected_events(), "selected")
+        } else {
+            (self.filtered_logs.as_slice(), "listed")
+        };
+        if events.is_empty() {
+            self.set_status("No events to copy");
+            return;
+        }
+        let count = events.len();
+        let heading = format!(
+            "`{}`, {} to {}",
+            self.log_group_name(),
+            self.from_date.format("%Y-%m-%d %H:%M"),
+            self.to_date.format("%Y-%m-%d %H:%M")
+        );
+        let text = events_to_markdown(&heading, events);
+
+        match copy_to_clipboard(&text) {
+            Ok(()) => self.set_status(format!("Copied {} {} event(s) as Markdown", count, what)),
+            Err(e) => self.set_status(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// All loaded events of the selected event's invocation, in time order, with its request ID.
+    /// Lines that carry no ID themselves belong to the invocation their log stream was
+    /// running, between its first and last tagged line.
+    pub fn invocation_events(&self) -> Option<(String, Vec<&FilteredLogEvent>)> {
+        self.invocation_of(self.get_selected_log()?)
+    }
+
+    fn invocation_of(
+        &self,
+        selected: &FilteredLogEvent,
+    ) -> Option<(String, Vec<&FilteredLogEvent>)> {
+        let mut stream_events: Vec<&FilteredLogEvent> = self
+            .logs
+            .iter()
+            .filter(|log| log.log_stream_name == selected.log_stream_name)
+            .collect();
+        stream_events.sort_by_key(|log| log.timestamp);
+        let message = |log: &FilteredLogEvent| log.message.clone().unwrap_or_default();
+
+        let selected_key = event_key(selected);
+        let position = stream_events
+            .iter()
+            .position(|log| event_key(log) == selected_key)?;
+
+        // An untagged line was logged by the invocation that last tagged one before it,
+        // unless that invocation had already ended
+        let id = match request_id(&message(selected)) {
+            Some(id) => id,
+            None => {
+                let previous = stream_events[..position]
+                    .iter()
+                    .rev()
+                    .find(|log| request_id(&message(log)).is_some())?;
+                if ends_invocation(&message(previous)) {
+                    return None;
+                }
+                request_id(&message(previous))?
+            }
+        };
+
+        let tagged = |log: &&FilteredLogEvent| request_id(&message(log)).as_deref() == Some(&id);
+        let first = stream_events.iter().position(tagged)?;
+        let last = stream_events.iter().rposition(tagged)?;
+        let events = stream_events[first..=last]
+            .iter()
+            .copied()
+            .filter(|log| request_id(&message(log)).is_none_or(|other| other == id))
+            .collect();
+        Some((id, events))
+    }
+
+    /// A viewer over the invocation that started before the selected event's, built from the
+    /// loaded events of any stream, to show next to this one.
+    pub fn previous_invocation(&mut self) -> Option<LogViewer> {
+        let Some((id, events)) = self.invocation_events() else {
+            self.set_status("The selected event has no request ID to group by");
+            return None;
+        };
+        let started = events.first().and_then(|log| log.timestamp);
+        let message = |log: &FilteredLogEvent| log.message.clone().unwrap_or_default();
+        let previous = self
+            .logs
+            .iter()
+            .filter(|log| log.timestamp < started)
+            .filter(|log| request_id(&message(log)).is_some_and(|other| other != id))
+            .max_by_key(|log| log.timestamp);
+        let Some((previous_id, previous_events)) = previous.and_then(|log| self.invocation_of(log))
+        else {
+            self.set_status("No earlier invocation among the loaded events");
+            return None;
+        };
+
+        let millis = |log: Option<&&FilteredLogEvent>| {
+            log.and_then(|log| log.timestamp).map(millis_to_local)
+        };
+        // Shows the invocation whole, and leaves the function's saved filter alone
+        let mut viewer = LogViewer::detached(
+            self.function_name.clone(),
+            millis(previous_events.first()).unwrap_or(self.from_date),
+            millis(previous_events.last()).unwrap_or(self.to_date),
+            self.settings.clone(),
+        );
+        viewer.invocation_id = Some(previous_id.clone());
+        viewer.region = self.region.clone();
+        viewer.set_logs(previous_events.into_iter().cloned().collect());
+
+        self.set_status(format!(
+            "Comparing invocation {} with the previous one, {}",
+            id, previous_id
+        ));
+        Some(viewer)
+    }
+
+    /// Copies the messages of the selected event's whole invocation, in time order.
+    pub fn copy_invocation(&mut self) {
+        let Some((id, events)) = self.invocation_events() else {
+            self.set_status("The selected event has no request ID to group by");
+            return;
+        };
+        let text = events
+            .iter()
+            .map(|log| log.message.as_deref().unwrap_or("").trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let lines = text.lines().count();
+
+        match copy_to_clipboard(&text) {
+            Ok(()) => self.set_status(format!(
+                "Copied invocation {} ({} events, {} lines)",
+                id,
+                events.len(),
+                lines
+            )),
+            Err(e) => self.set_status(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Writes the REPORT-line performance summary of the listed events to a file.
+    pub fn export_summary(&mut self) {
+        let summary = ReportSummary::from_events(&self.filtered_logs);
+        if summary.invocations == 0 {
+            self.set_status("No REPORT lines among the listed events");
+            return;
+        }
+
+        let format = self.settings.export_format;
+        let contents = match format {
+            ExportFormat::Csv => summary.to_csv(),
+            ExportFormat::Json => serde_json::to_string_pretty(&summary).unwrap_or_default(),
+            ExportFormat::Markdown => summary.to_markdown(),
+        };
+        let stem = export_stem(
+            &self.settings.export_filename,
+            &self.function_name,
+            "summary",
+            self.from_date,
+            self.to_date,
+        );
+
+        match write_export(&stem, format, &contents) {
+            Ok(path) => self.set_status(format!(
+                "Exported summary of {} invocation(s) to {}",
+                summary.invocations,
+                path.display()
+            )),
+            Err(e) => self.set_status(format!("Export failed: {}", e)),
+        }
+    }
+
+    fn step_selection(&mut self, direction: i32, visible_height: usize) {
+        if self.filtered_logs.is_empty() {
+            return;
+        }
+
+        if let Some(current) = self.selected_log {
+            let current = current.min(self.filtered_logs.len() - 1);
+            let new_index = if direction > 0 {
+                current.saturating_add(1).min(self.filtered_logs.len() - 1)
+            } else {
+                current.saturating_sub(1)
+            };
+            self.selected_log = Some(new_index);
+
+            // Update scroll position for list view
+            if !self.expanded {
+                // Adjust start_index to keep selection visible
+                if new_index >= self.start_index + visible_height {
+                    self.start_index = new_index.saturating_sub(visible_height.saturating_sub(1));
+                } else if new_index < self.start_index {
+                    self.start_index = new_index;
+                }
+            }
+        }
+    }
+}
+
+/// Sort key of the events as loaded: by event time.
+fn event_time(event: &FilteredLogEvent) -> Option<i64> {
+    event.timestamp
+}
+
+/// Sort key of the list ordered by ingestion time.
+fn ingestion_order(event: &FilteredLogEvent) -> Option<i64> {
+    event.ingestion_time.or(event.timestamp)
+}
+
+/// Merges `events` into `into`, both kept in `key` order. Stable: an event already there
+/// stays ahead of a new one with the same key.
+fn merge_by(
+    into: &mut Vec<FilteredLogEvent>,
+    mut events: Vec<FilteredLogEvent>,
+    key: fn(&FilteredLogEvent) -> Option<i64>,
+) {
+    events.sort_by_key(key);
+    match (into.last(), events.first()) {
+        (_, None) => return,
+        (Some(last), Some(first)) if key(last) > key(first) => {}
+        // Everything new comes after what is there, as when paging forward
+        _ => {
+            into.extend(events);
+            return;
+        }
+    }
+
+    let mut merged = Vec::with_capacity(into.len() + events.len());
+    let mut old = std::mem::take(into).into_iter().peekable();
+    let mut new = events.into_iter().peekable();
+    while let (Some(a), Some(b)) = (old.peek(), new.peek()) {
+        let next = if key(b) < key(a) { &mut new } else { &mut old };
+        merged.extend(next.next());
+    }
+    merged.extend(old);
+    merged.extend(new);
+    *into = merged;
+}
+
+/// Histogram bucket `timestamp` falls in, clamped to the last bucket.
+fn bucket_of(timestamp: i64, (start, end): (i64, i64)) -> usize {
+    let offset = (timestamp - start).max(0) as i128;
+    let bucket = offset * HISTOGRAM_BUCKETS as i128 / (end - start) as i128;
+    (bucket as usize).min(HISTOGRAM_BUCKETS - 1)
+}
+
+impl Drop for LogViewer {
+    fn drop(&mut self) {
+        if self.persist && (self.visited || self.filter_input != self.saved_filter) {
+            let (function_name, filter) = (&self.function_name, self.filter_input.trim());
+            let _ = update_state(|state| {
+                if self.visited {
+                    state
+                        .last_visits
+                        .insert(function_name.clone(), Local::now().timestamp_millis());
+                }
+                if filter.is_empty() {
+                    state.filters.remove(function_name);
+                } else {
+                    state
+                        .filters
+                        .insert(function_name.clone(), filter.to_string());
+                }
+            });
+        }
+
+        // Stop fetching pages nobody will look at
+        if let Some(task) = self.load_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.follow_task.take() {
+            task.abort();
+        }
+    }
+}
+
+async fn fetch_retention(
+    client: &CloudWatchLogsClient,
+    log_group_name: &str,
+) -> Result<Option<i32>> {
+    let response = client
+        .describe_log_groups()
+        .log_group_name_prefix(log_group_name)
+        .send()
+        .await?;
+    // The prefix also matches longer names, such as other functions starting the same
+    Ok(response
+        .log_groups()
+        .iter()
+        .find(|group| group.log_group_name() == Some(log_group_name))
+        .and_then(|group| group.retention_in_days()))
+}
+
+async fn fetch_log_events(
+    client: CloudWatchLogsClient,
+    query: LogQuery,
+    tx: UnboundedSender<LogLoadUpdate>,
+) {
+    let result = match (&query.log_stream_name, query.latest_count) {
+        (_, Some(count)) => fetch_latest_events(&client, &query, count, &tx).await,
+        (Some(log_stream_name), None) => {
+            fetch_stream_pages(&client, &query, log_stream_name, &tx).await
+        }
+        (None, None) => fetch_pages(&client, &query, &tx).await,
+    };
+    let update = match result {
+        Ok(()) => LogLoadUpdate::Finished,
+        Err(e) => LogLoadUpdate::Failed(format!("{:#}", e)),
+    };
+    let _ = tx.send(update);
+}
+
+async fn fetch_pages(
+    client: &CloudWatchLogsClient,
+    query: &LogQuery,
+    tx: &UnboundedSender<LogLoadUpdate>,
+) -> Result<()> {
+    let mut next_token = None;
+
+    loop {
+        let mut request = client
+            .filter_log_events()
+            .log_group_name(&query.log_group_name)
+            .start_time(query.start_time)
+            .end_time(query.end_time)
+            .limit(100);
+
+        if let Some(token) = &next_token {
+            request = request.next_token(token);
+        }
+
+        let request_start = Instant::now();
+        let response = request.send().await?;
+        let elapsed = request_start.elapsed();
+        next_token = response.next_token;
+
+        // The receiver is gone once the viewer is closed, so stop paging
+        let update = LogLoadUpdate::Page {
+            events: response.events.unwrap_or_default(),
+            next_token: next_token.clone(),
+            elapsed,
+        };
+        if tx.send(update).is_err() {
+            return Ok(());
+        }
+
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs one profile's share of an aggregated load, tagging its updates with the profile name.
+async fn fetch_profile_events(
+    profile: Profile,
+    query: LogQuery,
+    tx: UnboundedSender<LogLoadUpdate>,
+) {
+    let tag = |update| LogLoadUpdate::FromProfile(profile.name.clone(), Box::new(update));
+    if profile.region.is_empty() {
+        let _ = tx.send(tag(LogLoadUpdate::Failed("No region set".to_string())));
+        return;
+    }
+
+    let aws_config = load_aws_config(&profile.name, &profile.region).await;
+    let client = CloudWatchLogsClient::new(&aws_config);
+    let (profile_tx, mut profile_rx) = unbounded_channel();
+
+    // Dropping the receiver once the viewer is gone makes the fetch stop paging
+    let relay = async move {
+        while let Some(update) = profile_rx.recv().await {
+            if tx.send(tag(update)).is_err() {
+                break;
+            }
+        }
+    };
+    tokio::join!(fetch_log_events(client, query, profile_tx), relay);
+}
+
+/// Polls for events from `since` onwards until aborted or the viewer goes away.
+async fn follow_log_events(
+    client: CloudWatchLogsClient,
+    query: LogQuery,
+    since: i64,
+    tx: UnboundedSender<LogLoadUpdate>,
+) {
+    let mut newest = since;
+    // Event ids already delivered inside the lookback window, with their timestamps
+    let mut seen: HashMap<String, i64> = HashMap::new();
+
+    loop {
+        tokio::time::sleep(FOLLOW_INTERVAL).await;
+
+        let start_time = since.max(newest - FOLLOW_LOOKBACK_MILLIS);
+        let request_start = Instant::now();
+        let events = match fetch_events_since(&client, &query, start_time).await {
+            Ok(events) => events,
+            Err(e) => {
+                let _ = tx.send(LogLoadUpdate::Failed(format!("{:#}", e)));
+                return;
+            }
+        };
+
+        let mut new_events: Vec<FilteredLogEvent> = events
+            .into_iter()
+            .filter(|event| match (&event.event_id, event.timestamp) {
+                (Some(id), Some(timestamp)) => seen.insert(id.clone(), timestamp).is_none(),
+                _ => true,
+            })
+            .collect();
+        new_events.sort_by_key(|e| e.timestamp.unwrap_or(0));
+
+        if let Some(last) = new_events.last().and_then(|e| e.timestamp) {
+            newest = newest.max(last);
+        }
+        seen.retain(|_, timestamp| *timestamp >= newest - FOLLOW_LOOKBACK_MILLIS);
+
+        if new_events.is_empty() {
+            if tx.is_closed() {
+                return;
+            }
+            continue;
+        }
+        let update = LogLoadUpdate::Page {
+            events: new_events,
+            next_token: None,
+            elapsed: request_start.elapsed(),
+        };
+        if tx.send(update).is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads every event from `start_time` up to now, across pages.
+async fn fetch_events_since(
+    client: &CloudWatchLogsClient,
+    query: &LogQuery,
+    start_time: i64,
+) -> Result<Vec<FilteredLogEvent>> {
+    let mut events = Vec::new();
+    let mut next_token: Option<String> = None;
+
+    loop {
+        let mut request = client
+            .filter_log_events()
+            .log_group_name(&query.log_group_name)
+            .start_time(start_time)
+            .set_log_stream_names(query.log_stream_name.clone().map(|name| vec![name]));
+        if let Some(token) = &next_token {
+            request = request.next_token(token);
+        }
+
+        let response = request.send().await?;
+        events.extend(response.events.unwrap_or_default());
+
+        next_token = response.next_token;
+        if next_token.is_none() {
+            return Ok(events);
+        }
+    }
+}
+
+/// `get_log_events` results don't carry their stream, so attach it for display and export.
+fn from_stream_event(event: OutputLogEvent, log_stream_name: &str) -> FilteredLogEvent {
+    FilteredLogEvent::builder()
+        .log_stream_name(log_stream_name)
+        .set_timestamp(event.timestamp)
+        .set_message(event.message)
+        .set_ingestion_time(event.ingestion_time)
+        .build()
+}
+
+/// Reads a single stream front to back with `get_log_events`.
+async fn fetch_stream_pages(
+    client: &CloudWatchLogsClient,
+    query: &LogQuery,
+    log_stream_name: &str,
+    tx: &UnboundedSender<LogLoadUpdate>,
+) -> Result<()> {
+    let mut next_token: Option<String> = None;
+
+    loop {
+        let mut request = client
+            .get_log_events()
+            .log_group_name(&query.log_group_name)
+            .log_stream_name(log_stream_name)
+            .start_time(query.start_time)
+            .end_time(query.end_time)
+            .start_from_head(true);
+
+        if let Some(token) = &next_token {
+            request = request.next_token(token);
+        }
+
+        let request_start = Instant::now();
+        let response = request.send().await?;
+        let elapsed = request_start.elapsed();
+
+        // The forward token stays the same once the end of the stream is reached
+        let forward_token = response.next_forward_token;
+        let is_last_page = forward_token.is_none() || forward_token == next_token;
+
+        let update = LogLoadUpdate::Page {
+            events: response
+                .events
+                .unwrap_or_default()
+                .into_iter()
+                .map(|event| from_stream_event(event, log_stream_name))
+                .collect(),
+            next_token: forward_token.clone().filter(|_| !is_last_page),
+            elapsed,
+        };
+        if tx.send(update).is_err() {
+            return Ok(());
+        }
+
+        if is_last_page {
+            break;
+        }
+        next_token = forward_token;
+    }
+
+    Ok(())
+}
+
+/// Collects the newest `count` events by reading the tail of the most recently written streams.
+async fn fetch_latest_events(
+    client: &CloudWatchLogsClient,
+    query: &LogQuery,
+    count: usize,
+    tx: &UnboundedSender<LogLoadUpdate>,
+) -> Result<()> {
+    let request_start = Instant::now();
+    let mut events: Vec<FilteredLogEvent> = Vec::new();
+    let mut streams_read = 0;
+    let mut next_token: Option<String> = None;
+
+    'pages: loop {
+        let mut request = client
+            .describe_log_streams()
+            .log_group_name(&query.log_group_name)
+            .order_by(OrderBy::LastEventTime)
+            .descending(true);
+        if let Some(token) = &next_token {
+            request = request.next_token(token);
+        }
+        let response = request.send().await?;
+
+        for stream in response.log_streams() {
+            let Some(stream_name) = stream.log_stream_name() else {
+                continue;
+            };
+
+            // Streams are newest first: once we hold enough newer events, older streams can't help
+            let oldest_kept = events.first().and_then(|e| e.timestamp).unwrap_or(i64::MIN);
+            if streams_read >= MAX_LATEST_STREAMS
+                || (events.len() >= count
+                    && stream.last_event_timestamp().unwrap_or(i64::MIN) < oldest_kept)
+            {
+                break 'pages;
+            }
+
+            let stream_events = client
+                .get_log_events()
+                .log_group_name(&query.log_group_name)
+                .log_stream_name(stream_name)
+                .start_from_head(false)
+                .limit(count.clamp(1, MAX_LATEST_EVENTS) as i32)
+                .send()
+                .await?
+                .events
+                .unwrap_or_default();
+            streams_read += 1;
+
+            events.extend(
+                stream_events
+                    .into_iter()
+                    .map(|event| from_stream_event(event, stream_name)),
+            );
+            events.sort_by_key(|e| e.timestamp.unwrap_or(0));
+            let excess = events.len().saturating_sub(count);
+            events.drain(..excess);
+        }
+
+        next_token = response.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    let _ = tx.send(LogLoadUpdate::Page {
+        events,
+        next_token: None,
+        elapsed: request_start.elapsed(),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(timestamp: i64, message: &str) -> FilteredLogEvent {
+        FilteredLogEvent::builder()
+            .timestamp(timestamp)
+            .ingestion_time(timestamp)
+            .message(message)
+            .log_stream_name("stream")
+            .build()
+    }
+
+    /// A viewer over `messages`, one second apart, that never touches AWS or the state file.
+    fn viewer(messages: &[&str]) -> LogViewer {
+        let now = Local::now();
+        let mut viewer =
+            LogViewer::detached("test".to_string(), now, now, LogViewSettings::default());
+        viewer.set_logs(
+            messages
+                .iter()
+                .enumerate()
+                .map(|(i, message)| event(i as i64 * 1_000, message))
+                .collect(),
+        );
+        viewer
+    }
+
+    /// A client that is never called: following waits `FOLLOW_INTERVAL` before its first poll.
+    fn offline_client() -> CloudWatchLogsClient {
+        let config = aws_sdk_cloudwatchlogs::Config::builder()
+            .behavior_version(aws_sdk_cloudwatchlogs::config::BehaviorVersion::latest())
+            .region(aws_sdk_cloudwatchlogs::config::Region::new("us-east-1"))
+            .build();
+        CloudWatchLogsClient::from_conf(config)
+    }
+
+    fn selected_message(viewer: &LogViewer) -> Option<&str> {
+        viewer.get_selected_log()?.message.as_deref()
+    }
+
+    #[test]
+    fn expand_and_collapse_keep_event_and_scroll() {
+        let mut viewer = viewer(&["a", "b", "c"]);
+        viewer.selected_log = Some(1);
+        viewer.toggle_expand();
+        viewer.scroll_position = 3;
+
+        viewer.toggle_expand();
+        assert!(!viewer.expanded);
+        assert_eq!(selected_message(&viewer), Some("b"));
+
+        viewer.toggle_expand();
+        assert!(viewer.expanded);
+        assert_eq!(selected_message(&viewer), Some("b"));
+        assert_eq!(viewer.scroll_position, 3);
+    }
+
+    #[test]
+    fn scrolling_reaches_the_tail_of_a_wrapped_line() {
+        let long = "word ".repeat(40);
+        let mut viewer = viewer(&[long.trim_end()]);
+        viewer.selected_log = Some(0);
+        viewer.toggle_expand();
+        viewer.detail_height = 3;
+        let content = viewer.expanded_content.as_mut().unwrap();
+        assert!(content.wrap(20, 0));
+        assert!(!content.wrap(20, 0));
+        let rows = content.row_count();
+        assert_eq!(content.lines.len(), 1);
+        assert!(rows > 3);
+
+        for _ in 0..rows * 2 {
+            viewer.scroll_down();
+        }
+        assert_eq!(viewer.scroll_position, rows - 3);
+
+        viewer.scroll_position = 0;
+        viewer.page_down();
+        assert_eq!(viewer.scroll_position, 3.min(rows - 3));
+    }
+
+    #[test]
+    fn reformatting_keeps_the_scroll_within_the_wrapped_rows() {
+        let long = "word ".repeat(40);
+        let mut viewer = viewer(&[long.trim_end()]);
+        viewer.selected_log = Some(0);
+        viewer.toggle_expand();
+        viewer.detail_height = 3;
+        viewer.expanded_content.as_mut().unwrap().wrap(20, 0);
+        let rows = viewer.expanded_content.as_ref().unwrap().row_count();
+        viewer.scroll_position = rows - 3;
+
+        viewer.reformat_expanded();
+
+        let content = viewer.expanded_content.as_ref().unwrap();
+        assert_eq!(content.row_count(), rows);
+        assert_eq!(viewer.scroll_position, rows - 3);
+
+        viewer.scroll_position = rows;
+        viewer.reformat_expanded();
+        assert_eq!(viewer.scroll_position, viewer.max_expanded_scroll());
+    }
+
+    #[test]
+    fn pinned_scroll_stops_at_the_last_page() {
+        let message = (0..10).map(|i| format!("line {}", i)).collect::<Vec<_>>();
+        let mut viewer = viewer(&[&message.join("\n")]);
+        viewer.selected_log = Some(0);
+        viewer.toggle_pin();
+        let pinned = viewer.pinned.as_mut().unwrap();
+        pinned.height = 4;
+        let lines = pinned.content.lines.len();
+
+        viewer.scroll_pinned(100);
+        assert_eq!(viewer.pinned.as_ref().unwrap().scroll, lines - 4);
+    }
+
+    #[test]
+    fn histogram_is_counted_again_after_a_filter_change() {
+        let mut viewer = viewer(&["error one", "info", "error two"]);
+        assert_eq!(viewer.histogram().counts.iter().sum::<usize>(), 3);
+
+        viewer.filter_input = "error".to_string();
+        viewer.update_filter();
+        assert_eq!(viewer.histogram().counts.iter().sum::<usize>(), 2);
+
+        let histogram = viewer.histogram().clone();
+        assert_eq!(histogram.bucket_bounds(0).0, histogram.range.0);
+        assert_eq!(
+            histogram.bucket_bounds(HISTOGRAM_BUCKETS - 1).1,
+            histogram.range.1
+        );
+    }
+
+    #[test]
+    fn alerts_keep_the_latest_and_count_the_dropped() {
+        let mut viewer = viewer(&[]);
+        viewer.watch_input = "boom".to_string();
+        viewer.finish_watch_edit();
+        let events: Vec<_> = (0..MAX_ALERTS as i64 + 10)
+            .map(|i| event(i, &format!("boom {}", i)))
+            .collect();
+
+        viewer.check_watch(&events);
+
+        assert_eq!(viewer.alerts.len(), MAX_ALERTS);
+        assert_eq!(viewer.dropped_alerts, 10);
+        assert_eq!(viewer.alerts[0].message.as_deref(), Some("boom 10"));
+    }
+
+    #[test]
+    fn jumping_to_an_alert_while_expanded_shows_its_message() {
+        let mut viewer = viewer(&["boom one", "info", "boom two"]);
+        viewer.alerts = vec![viewer.logs[2].clone()];
+        viewer.selected_log = Some(0);
+        viewer.toggle_expand();
+        viewer.scroll_position = 1;
+
+        viewer.toggle_alerts();
+        viewer.jump_to_alert();
+
+        assert!(viewer.expanded);
+        assert_eq!(selected_message(&viewer), Some("boom two"));
+        let content = viewer.expanded_content.as_ref().unwrap();
+        assert_eq!(content.key, event_key(&viewer.logs[2]));
+        assert_eq!(viewer.scroll_position, 0);
+    }
+
+    #[test]
+    fn merged_pages_land_in_time_order_and_keep_the_selection() {
+        let mut viewer = viewer(&["a", "b", "c"]);
+        viewer.selected_log = Some(1);
+
+        // Another profile's page, overlapping the loaded range
+        viewer.merge_logs(vec![event(2_500, "late"), event(500, "early")]);
+
+        let messages: Vec<_> = viewer
+            .logs
+            .iter()
+            .map(|e| e.message.as_deref().unwrap())
+            .collect();
+        assert_eq!(messages, ["a", "early", "b", "c", "late"]);
+        assert_eq!(viewer.filtered_logs.len(), 5);
+        assert_eq!(selected_message(&viewer), Some("b"));
+    }
+
+    #[test]
+    fn unchanged_nudge_is_not_read_again() {
+        let mut viewer = viewer(&["a"]);
+        let (from, to) = (viewer.from_date, viewer.to_date);
+
+        assert!(!viewer.set_range(from, to + chrono::Duration::milliseconds(500)));
+        assert!(viewer.nudged_at.is_none());
+        assert_eq!(viewer.to_date, to);
+
+        assert!(viewer.set_range(from - chrono::Duration::hours(1), to));
+        assert!(viewer.nudged_at.is_some());
+    }
+
+    #[test]
+    fn filter_change_keeps_listed_event() {
+        let mut viewer = viewer(&["error one", "info", "error two"]);
+        viewer.selected_log = Some(2);
+
+        viewer.filter_input = "error".to_string();
+        viewer.update_filter();
+
+        assert_eq!(viewer.filtered_logs.len(), 2);
+        assert_eq!(viewer.selected_log, Some(1));
+        assert_eq!(selected_message(&viewer), Some("error two"));
+    }
+
+    #[test]
+    fn filter_change_dropping_event_selects_nearest() {
+        let mut viewer = viewer(&["error one", "info", "info", "info", "error two"]);
+        viewer.selected_log = Some(3);
+
+        viewer.filter_input = "error".to_string();
+        viewer.update_filter();
+
+        assert_eq!(selected_message(&viewer), Some("error two"));
+    }
+
+    #[test]
+    fn refresh_keeps_selected_event() {
+        let mut viewer = viewer(&["a", "b", "c"]);
+        viewer.selected_log = Some(1);
+        viewer.toggle_expand();
+        viewer.scroll_position = 2;
+
+        // The refresh found an earlier event, so "b" moved down the list
+        viewer.refresh_events = vec![event(-1_000, "new"), event(0, "a"), event(1_000, "b")];
+        viewer.refreshing = true;
+        viewer.apply_refresh();
+
+        assert_eq!(viewer.selected_log, Some(2));
+        assert_eq!(selected_message(&viewer), Some("b"));
+        assert!(viewer.expanded);
+        assert_eq!(viewer.scroll_position, 2);
+    }
+
+    #[test]
+    fn reorder_by_ingestion_keeps_selected_event() {
+        let mut viewer = viewer(&["a", "b", "c"]);
+        // "a" arrived last
+        viewer.logs[0].ingestion_time = Some(10_000);
+        viewer.update_filter();
+        viewer.selected_log = Some(0);
+
+        viewer.toggle_order_by();
+
+        assert_eq!(viewer.settings.order_by, TimeField::Ingestion);
+        assert_eq!(viewer.selected_log, Some(2));
+        assert_eq!(selected_message(&viewer), Some("a"));
+    }
+
+    #[test]
+    fn shrinking_below_selection_clamps_it() {
+        let mut viewer = viewer(&["a", "b", "c", "d"]);
+        viewer.selected_log = Some(3);
+        viewer.selection_anchor = Some(2);
+
+        viewer.filtered_logs.truncate(2);
+        viewer.clamp_selection();
+
+        assert_eq!(viewer.selected_log, Some(1));
+        assert_eq!(viewer.selection_anchor, Some(1));
+        assert_eq!(selected_message(&viewer), Some("b"));
+    }
+
+    #[test]
+    fn emptied_list_clears_selection() {
+        let mut viewer = viewer(&["a", "b"]);
+        viewer.selected_log = Some(1);
+        viewer.toggle_expand();
+
+        viewer.filter_input = "nothing matches this".to_string();
+        viewer.update_filter();
+
+        assert!(viewer.filtered_logs.is_empty());
+        assert_eq!(viewer.selected_log, None);
+        assert!(!viewer.expanded);
+        assert_eq!(viewer.focus, LogPanel::List);
+        assert!(viewer.get_selected_log().is_none());
+    }
+
+    #[test]
+    fn scroll_past_new_end_is_clamped() {
+        let mut viewer = viewer(&["a", "b", "c", "d", "e", "f"]);
+        viewer.selected_log = Some(5);
+        viewer.start_index = 4;
+
+        viewer.filtered_logs.truncate(3);
+        viewer.clamp_selection();
+
+        assert_eq!(viewer.start_index, 2);
+        assert_eq!(viewer.selected_log, Some(2));
+    }
+
+    #[test]
+    fn overlapping_poll_windows_list_each_event_once() {
+        let mut viewer = viewer(&[]);
+        let first_poll = vec![event(1_000, "a"), event(2_000, "b"), event(3_000, "c")];
+        // The next poll re-reads from before the newest event, and repeats one within itself
+        let second_poll = vec![
+            event(2_000, "b"),
+            event(3_000, "c"),
+            event(3_000, "d"),
+            event(4_000, "e"),
+            event(4_000, "e"),
+        ];
+
+        for poll in [first_poll, second_poll] {
+            let events = viewer.drop_known_events(poll);
+            viewer.append_logs(events);
+        }
+
+        let messages: Vec<&str> = viewer
+            .logs
+            .iter()
+            .filter_map(|log| log.message.as_deref())
+            .collect();
+        assert_eq!(messages, ["a", "b", "c", "d", "e"]);
+    }
+
+    #[tokio::test]
+    async fn follow_toggled_during_a_load_starts_when_it_hits_the_limit() {
+        let mut viewer = viewer(&[]);
+        viewer.cloudwatch_client = Some(offline_client());
+        viewer.settings.max_events = 2;
+        let (tx, rx) = viewer.start_load();
+        viewer.load_rx = Some(rx);
+
+        viewer.toggle_follow();
+        assert!(viewer.follow);
+        assert!(viewer.follow_task.is_none());
+
+        let events = vec![event(1_000, "a"), event(2_000, "b"), event(3_000, "c")];
+        let page = LogLoadUpdate::Page {
+            events,
+            next_token: Some("more".to_string()),
+            elapsed: Duration::ZERO,
+        };
+        tx.send(page).unwrap();
+        viewer.poll_load_updates();
+
+        assert!(!viewer.is_loading);
+        assert_eq!(viewer.truncation, Some(Truncation::EventLimit));
+        assert_eq!(viewer.logs.len(), 2);
+        assert!(viewer.follow);
+        assert!(viewer.follow_task.is_some());
+        viewer.stop_follow();
+    }
+
+    #[test]
+    fn stderr_is_judged_as_events_arrive() {
+        let mut viewer = viewer(&[r#"{"level":"error"}"#, "info"]);
+        viewer.append_logs(vec![event(5_000, "x\tERROR\tboom"), event(6_000, "ok")]);
+
+        let stderr: Vec<bool> = viewer.logs.iter().map(|e| viewer.is_stderr(e)).collect();
+        assert_eq!(stderr, [true, false, true, false]);
+    }
+
+    #[tokio::test]
+    async fn stopped_follow_task_terminates() {
+        let mut viewer = viewer(&["a"]);
+        viewer.cloudwatch_client = Some(offline_client());
+        viewer.start_follow();
+        let task = viewer.follow_task.as_ref().unwrap().abort_handle();
+        assert!(!task.is_finished());
+
+        viewer.stop_follow();
+
+        let finished = tokio::time::timeout(Duration::from_secs(1), async {
+            while !task.is_finished() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await;
+        assert!(finished.is_ok());
+        assert!(!viewer.follow);
+        assert!(viewer.follow_rx.is_none());
     }
 }
@@ -0,0 +1,94 @@
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+use crate::app_state::AppState;
+use crate::toml_parser::Profile;
+use crate::utils::aws_utils::{assume_role_with_mfa, MfaRequirement};
+use crate::utils::error_log;
+use crate::utils::shutdown::spawn;
+
+/// Length of the codes virtual and hardware MFA devices show.
+pub const MFA_CODE_LENGTH: usize = 6;
+
+/// Asks for the MFA code of a profile that assumes a role, then starts the session with it.
+#[derive(Debug)]
+pub struct MfaPrompt {
+    pub profile: Profile,
+    pub requirement: MfaRequirement,
+    pub code: String,
+    pub error: Option<String>,
+    pub is_submitting: bool,
+    /// Screen to go back to once a session that expired mid-use has been renewed.
+    pub resume: Option<AppState>,
+    submit_rx: Option<UnboundedReceiver<Result<(), String>>>,
+}
+
+impl MfaPrompt {
+    pub fn new(profile: Profile, requirement: MfaRequirement) -> Self {
+        Self {
+            profile,
+            requirement,
+            code: String::new(),
+            error: None,
+            is_submitting: false,
+            resume: None,
+            submit_rx: None,
+        }
+    }
+
+    pub fn push_digit(&mut self, digit: char) {
+        if digit.is_ascii_digit() && self.code.len() < MFA_CODE_LENGTH && !self.is_submitting {
+            self.code.push(digit);
+        }
+    }
+
+    pub fn pop_digit(&mut self) {
+        if !self.is_submitting {
+            self.code.pop();
+        }
+    }
+
+    /// Calls `AssumeRole` with the typed code in the background.
+    pub fn submit(&mut self) {
+        if self.is_submitting {
+            return;
+        }
+        if self.code.len() != MFA_CODE_LENGTH {
+            self.error = Some(format!("Enter the {}-digit code", MFA_CODE_LENGTH));
+            return;
+        }
+
+        let (tx, rx) = unbounded_channel();
+        let profile = self.profile.clone();
+        let requirement = self.requirement.clone();
+        let code = self.code.clone();
+
+        self.error = None;
+        self.is_submitting = true;
+        self.submit_rx = Some(rx);
+        spawn(async move {
+            let result =
+                assume_role_with_mfa(&profile.name, &profile.region, &requirement, &code).await;
+            let _ = tx.send(result.map_err(|e| format!("{:#}", e)));
+        });
+    }
+
+    /// Returns true once the session has been started.
+    pub fn poll_updates(&mut self) -> bool {
+        let Some(result) = self.submit_rx.as_mut().and_then(|rx| rx.try_recv().ok()) else {
+            return false;
+        };
+        self.submit_rx = None;
+        self.is_submitting = false;
+
+        match result {
+            Ok(()) => true,
+            Err(error) => {
+                error_log::log_error(&format!("MFA for {}", self.profile.name), &error);
+                // Codes are single-use and short-lived, so start over with a fresh one
+                self.code.clear();
+                self.error = Some(error);
+                false
+            }
+        }
+    }
+}
@@ -1,24 +1,39 @@
 pub mod date_selection;
+pub mod filter_query;
 pub mod function_selection;
 pub mod log_viewer;
+pub mod mfa_prompt;
 pub mod profile_selection;
+pub mod region_selection;
+pub mod stream_selection;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AppState {
     ProfileSelection,
+    RegionSelection,
+    MfaPrompt,
     FunctionList,
     DateSelection,
+    StreamSelection,
     LogViewer,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum FocusedPanel {
+    #[default]
     Left,
     Right,
 }
 
-impl Default for FocusedPanel {
-    fn default() -> Self {
-        Self::Left
-    }
+/// Part of the log viewer that receives keys; Tab moves between them.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LogPanel {
+    /// Typed characters edit the filter.
+    Filter,
+    /// Keys move the selection, with j/k as well as arrows. Focused on opening, so the
+    /// letter commands work straight away.
+    #[default]
+    List,
+    /// Keys scroll the expanded event; takes the list's place while expanded.
+    Detail,
 }
@@ -5,6 +5,8 @@ use ratatui::widgets::ListState;
 pub struct ProfileSelection {
     pub list_state: ListState,
     pub profiles: Vec<Profile>,
+    /// Outcome of the last config reload, shown until the next key press.
+    pub notice: Option<String>,
 }
 
 impl ProfileSelection {
@@ -17,6 +19,30 @@ impl ProfileSelection {
         Self {
             list_state,
             profiles,
+            notice: None,
+        }
+    }
+
+    /// Replaces the profiles after a config reload, keeping the highlighted one if it still exists.
+    pub fn set_profiles(&mut self, profiles: Vec<Profile>) {
+        let selected_name = self.selected_profile().map(|p| p.name);
+        let index = selected_name
+            .and_then(|name| profiles.iter().position(|p| p.name == name))
+            .unwrap_or(0);
+
+        self.profiles = profiles;
+        self.list_state
+            .select((!self.profiles.is_empty()).then_some(index));
+    }
+
+    /// Highlights the profile called `name`. Returns false when there is none.
+    pub fn select_named(&mut self, name: &str) -> bool {
+        match self.profiles.iter().position(|p| p.name == name) {
+            Some(index) => {
+                self.list_state.select(Some(index));
+                true
+            }
+            None => false,
         }
     }
 
@@ -37,6 +63,8 @@ impl ProfileSelection {
     }
 
     pub fn selected_profile(&self) -> Option<Profile> {
-        self.list_state.selected().map(|i| self.profiles[i].clone())
+        self.list_state
+            .selected()
+            .and_then(|i| self.profiles.get(i).cloned())
     }
 }
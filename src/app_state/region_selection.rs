@@ -0,0 +1,73 @@
+use ratatui::widgets::ListState;
+
+use crate::toml_parser::Profile;
+
+/// Regions offered when a profile has none configured anywhere.
+pub const COMMON_REGIONS: &[&str] = &[
+    "us-east-1",
+    "us-east-2",
+    "us-west-1",
+    "us-west-2",
+    "ca-central-1",
+    "sa-east-1",
+    "eu-west-1",
+    "eu-west-2",
+    "eu-west-3",
+    "eu-central-1",
+    "eu-north-1",
+    "ap-northeast-1",
+    "ap-northeast-2",
+    "ap-northeast-3",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-south-1",
+];
+
+#[derive(Debug)]
+pub struct RegionSelection {
+    pub profile: Profile,
+    pub list_state: ListState,
+    /// Set when changing the region of an open session; the choice is not remembered then.
+    pub switching: bool,
+}
+
+impl RegionSelection {
+    pub fn new(profile: Profile) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+
+        Self {
+            profile,
+            list_state,
+            switching: false,
+        }
+    }
+
+    /// Picks another region for a profile already in use, starting at its current one.
+    pub fn switching(profile: Profile) -> Self {
+        let mut selection = Self::new(profile);
+        let current = COMMON_REGIONS
+            .iter()
+            .position(|region| *region == selection.profile.region);
+        selection.list_state.select(Some(current.unwrap_or(0)));
+        selection.switching = true;
+        selection
+    }
+
+    pub fn next(&mut self) {
+        let current = self.list_state.selected().unwrap_or(0);
+        self.list_state
+            .select(Some((current + 1).min(COMMON_REGIONS.len() - 1)));
+    }
+
+    pub fn previous(&mut self) {
+        let current = self.list_state.selected().unwrap_or(0);
+        self.list_state.select(Some(current.saturating_sub(1)));
+    }
+
+    pub fn selected_region(&self) -> Option<&'static str> {
+        self.list_state
+            .selected()
+            .and_then(|i| COMMON_REGIONS.get(i).copied())
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::Result;
+use aws_sdk_cloudwatchlogs::types::OrderBy;
+use aws_sdk_cloudwatchlogs::Client as CloudWatchLogsClient;
+use ratatui::widgets::ListState;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+use crate::toml_parser::Profile;
+use crate::utils::aws_utils::{lambda_log_group, load_aws_config};
+use crate::utils::error_log;
+use crate::utils::shutdown::spawn;
+
+/// Streams are listed newest first, so a few pages cover any recent investigation.
+const MAX_STREAM_PAGES: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct LogStreamInfo {
+    pub name: String,
+    pub last_event_timestamp: Option<i64>,
+}
+
+#[derive(Debug)]
+pub enum StreamLoadUpdate {
+    Loaded(Vec<LogStreamInfo>),
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct StreamSelection {
+    pub profile: Profile,
+    pub function_name: String,
+    pub streams: Vec<LogStreamInfo>,
+    pub filtered_streams: Vec<LogStreamInfo>,
+    pub filter_input: String,
+    pub list_state: ListState,
+    pub is_loading: bool,
+    pub load_error: Option<String>,
+    load_rx: Option<UnboundedReceiver<StreamLoadUpdate>>,
+}
+
+impl StreamSelection {
+    pub fn new(profile: Profile, function_name: String) -> Self {
+        Self {
+            profile,
+            function_name,
+            streams: Vec::new(),
+            filtered_streams: Vec::new(),
+            filter_input: String::new(),
+            list_state: ListState::default(),
+            is_loading: false,
+            load_error: None,
+            load_rx: None,
+        }
+    }
+
+    /// Starts listing the function's log streams in the background.
+    pub fn load_streams(&mut self) {
+        let (tx, rx) = unbounded_channel();
+        self.load_rx = Some(rx);
+        self.is_loading = true;
+        self.load_error = None;
+
+        let profile = self.profile.clone();
+        let log_group_name = lambda_log_group(&self.function_name);
+
+        spawn(async move {
+            let update = match fetch_streams(&profile, &log_group_name).await {
+                Ok(streams) => StreamLoadUpdate::Loaded(streams),
+                Err(e) => StreamLoadUpdate::Failed(format!("{:#}", e)),
+            };
+            let _ = tx.send(update);
+        });
+    }
+
+    pub fn poll_updates(&mut self) {
+        let Some(rx) = self.load_rx.as_mut() else {
+            return;
+        };
+
+        if let Ok(update) = rx.try_recv() {
+            match update {
+                StreamLoadUpdate::Loaded(streams) => {
+                    self.streams = streams;
+                    self.update_filter();
+                }
+                StreamLoadUpdate::Failed(error) => {
+                    error_log::log_error("load log streams", &error);
+                    self.load_error = Some(error);
+                }
+            }
+            self.is_loading = false;
+            self.load_rx = None;
+        }
+    }
+
+    pub fn update_filter(&mut self) {
+        let filter_lower = self.filter_input.to_lowercase();
+        self.filtered_streams = self
+            .streams
+            .iter()
+            .filter(|stream| stream.name.to_lowercase().contains(&filter_lower))
+            .cloned()
+            .collect();
+
+        self.list_state.select(if self.filtered_streams.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    pub fn selected_stream(&self) -> Option<&LogStreamInfo> {
+        self.list_state
+            .selected()
+            .and_then(|i| self.filtered_streams.get(i))
+    }
+
+    pub fn next(&mut self) {
+        if !self.filtered_streams.is_empty() {
+            let current = self.list_state.selected().unwrap_or(0);
+            let next = (current + 1).min(self.filtered_streams.len() - 1);
+            self.list_state.select(Some(next));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.filtered_streams.is_empty() {
+            let current = self.list_state.selected().unwrap_or(0);
+            self.list_state.select(Some(current.saturating_sub(1)));
+        }
+    }
+}
+
+async fn fetch_streams(profile: &Profile, log_group_name: &str) -> Result<Vec<LogStreamInfo>> {
+    let config = load_aws_config(&profile.name, &profile.region).await;
+    let client = CloudWatchLogsClient::new(&config);
+    let mut streams = Vec::new();
+    let mut next_token = None;
+
+    for _ in 0..MAX_STREAM_PAGES {
+        let mut request = client
+            .describe_log_streams()
+            .log_group_name(log_group_name)
+            .order_by(OrderBy::LastEventTime)
+            .descending(true);
+
+        if let Some(token) = next_token {
+            request = request.next_token(token);
+        }
+
+        let response = request.send().await?;
+        for stream in response.log_streams() {
+            if let Some(name) = &stream.log_stream_name {
+                streams.push(LogStreamInfo {
+                    name: name.clone(),
+                    last_event_timestamp: stream.last_event_timestamp,
+                });
+            }
+        }
+
+        next_token = response.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(streams)
+}
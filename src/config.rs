@@ -1,26 +1,192 @@
 use anyhow::Result;
+use aws_config::meta::region::ProvideRegion;
+use aws_config::profile::ProfileFileRegionProvider;
+use std::path::PathBuf;
 
-use crate::toml_parser::{read_aws_profiles, Profile};
+use crate::toml_parser::{read_config_file, FunctionListSettings, LogViewSettings, Profile};
+use crate::utils::aws_utils::find_mfa_requirements;
+use crate::utils::file_utils::load_state;
 
-#[derive(Debug, Clone)]
+/// Minimal working configuration, shown when the config file is missing or malformed.
+pub const EXAMPLE_CONFIG: &str = r#"profiles = [
+    { name = "my-profile", region = "us-east-1" },
+]"#;
+
+#[derive(Debug, Clone, Default)]
 pub struct Config {
     pub aws_profiles: Vec<Profile>,
-}
-
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            aws_profiles: vec![],
-        }
-    }
+    /// Enables hidden diagnostics such as the fetch debug overlay (F12).
+    pub debug: bool,
+    pub error_log: Option<PathBuf>,
+    pub default_profile: Option<String>,
+    pub no_color: bool,
+    pub log_view: LogViewSettings,
+    pub function_list: FunctionListSettings,
+    /// Problems worth telling the user about that didn't stop the config from loading.
+    pub warnings: Vec<String>,
 }
 
 impl Config {
     pub fn new() -> Result<Self> {
-        let aws_profiles = read_aws_profiles()?;
+        let config_file = read_config_file()?;
+        let (aws_profiles, mut warnings) = dedupe_profiles(config_file.profiles);
+        let mut log_view = config_file.log_view;
+        warnings.extend(log_view.clamp_limits());
 
         Ok(Self {
-            aws_profiles: aws_profiles,
+            aws_profiles,
+            warnings,
+            debug: config_file.debug,
+            error_log: config_file.error_log,
+            default_profile: config_file.default_profile,
+            no_color: config_file.no_color,
+            log_view,
+            function_list: config_file.function_list,
         })
     }
+
+    /// Marks profiles that assume a role with an MFA code, so the code can be asked for.
+    /// Ones whose role can't be assumed as configured are added to the warnings.
+    pub async fn detect_mfa(&mut self) {
+        let names: Vec<String> = self.aws_profiles.iter().map(|p| p.name.clone()).collect();
+        let (mut requirements, problems) = find_mfa_requirements(&names).await;
+        self.warnings.extend(problems);
+        for profile in &mut self.aws_profiles {
+            profile.mfa = requirements.remove(&profile.name);
+        }
+    }
+
+    /// Fills in regions config.toml leaves out: first a region picked in an earlier session,
+    /// then the profile's region in the AWS config files, then `AWS_REGION` or
+    /// `AWS_DEFAULT_REGION`. Any still empty are asked for later.
+    pub async fn resolve_regions(&mut self) {
+        let saved_regions = load_state().map(|state| state.regions).unwrap_or_default();
+        let env_region = ["AWS_REGION", "AWS_DEFAULT_REGION"]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|region| !region.trim().is_empty());
+
+        for profile in self.aws_profiles.iter_mut().filter(|p| p.region.is_empty()) {
+            if let Some(region) = saved_regions.get(&profile.name) {
+                profile.region = region.clone();
+                continue;
+            }
+
+            let provider = ProfileFileRegionProvider::builder()
+                .profile_name(&profile.name)
+                .build();
+            if let Some(region) = provider.region().await {
+                profile.region = region.to_string();
+                continue;
+            }
+
+            if let Some(region) = &env_region {
+                profile.region = region.trim().to_string();
+            }
+        }
+    }
+}
+
+/// Drops profiles listed again with the same region, keeping the first. A later copy
+/// without a region is dropped, and one with a region fills it in on an earlier entry that
+/// has none. A name listed with different regions is kept once per region, with a warning,
+/// since each entry then reads from a different place.
+fn dedupe_profiles(profiles: Vec<Profile>) -> (Vec<Profile>, Vec<String>) {
+    let mut kept: Vec<Profile> = Vec::new();
+    let mut warnings = Vec::new();
+
+    for profile in profiles {
+        let mut same_name = kept
+            .iter_mut()
+            .filter(|p| p.name == profile.name)
+            .peekable();
+        if same_name.peek().is_none() {
+            kept.push(profile);
+            continue;
+        }
+        if profile.region.is_empty() {
+            continue;
+        }
+
+        let mut regions = Vec::new();
+        let mut merged = false;
+        for existing in same_name {
+            if existing.region.is_empty() {
+                existing.region = profile.region.clone();
+            }
+            merged |= existing.region == profile.region;
+            regions.push(existing.region.clone());
+        }
+        if !merged {
+            warnings.push(format!(
+                "Profile '{}' is listed with regions {} and {}; each is kept",
+                profile.name,
+                regions.join(", "),
+                profile.region
+            ));
+            kept.push(profile);
+        }
+    }
+
+    (kept, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, region: &str) -> Profile {
+        Profile {
+            name: name.to_string(),
+            region: region.to_string(),
+            mfa: None,
+        }
+    }
+
+    fn names_and_regions(profiles: &[Profile]) -> Vec<(&str, &str)> {
+        profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.region.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn same_region_copies_are_merged() {
+        let (kept, warnings) = dedupe_profiles(vec![
+            profile("dev", "us-east-1"),
+            profile("prod", "eu-west-1"),
+            profile("dev", "us-east-1"),
+            profile("dev", ""),
+        ]);
+
+        assert_eq!(
+            names_and_regions(&kept),
+            [("dev", "us-east-1"), ("prod", "eu-west-1")]
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn later_region_fills_an_empty_one() {
+        let (kept, warnings) =
+            dedupe_profiles(vec![profile("dev", ""), profile("dev", "us-west-2")]);
+
+        assert_eq!(names_and_regions(&kept), [("dev", "us-west-2")]);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn different_regions_are_kept_with_a_warning() {
+        let (kept, warnings) = dedupe_profiles(vec![
+            profile("dev", "us-east-1"),
+            profile("dev", "eu-west-1"),
+        ]);
+
+        assert_eq!(
+            names_and_regions(&kept),
+            [("dev", "us-east-1"), ("dev", "eu-west-1")]
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("us-east-1 and eu-west-1"));
+    }
 }
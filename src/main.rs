@@ -7,93 +7,656 @@ use anyhow::Result;
 use app_state::{
     date_selection::{ActiveColumn, DateSelection},
     function_selection::FunctionSelection,
-    log_viewer::LogViewer,
+    log_viewer::{LogViewer, PAGE_JUMP_STEP},
+    mfa_prompt::MfaPrompt,
     profile_selection::ProfileSelection,
-    AppState, FocusedPanel,
+    region_selection::RegionSelection,
+    stream_selection::StreamSelection,
+    AppState, FocusedPanel, LogPanel,
 };
-use config::Config;
+use aws_sdk_cloudwatchlogs::types::FilteredLogEvent;
+use chrono::{Duration, Local};
+use config::{Config, EXAMPLE_CONFIG};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseEvent,
+        MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
+use std::io::{self, IsTerminal, Read, Stdout};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use toml_parser::{Profile, CONFIG_PATH};
+use utils::aws_utils::{mfa_session, mfa_session_expired};
+use utils::editor::{edit_in_editor, open_in_editor};
+use utils::error_log;
+use utils::file_utils::update_state;
+use utils::import::parse_events;
+use utils::shutdown;
+use utils::time_utils::millis_to_local;
+use utils::ui_utils::{draw_corner_note, draw_too_small, strip_colors};
+
+/// A prefetched quick range is used for a pick made up to this long after it.
+const PREFETCH_MAX_AGE: Duration = Duration::minutes(5);
 
 struct App {
+    config: Config,
     state: AppState,
     profile_selection: ProfileSelection,
+    region_selection: Option<RegionSelection>,
+    mfa_prompt: Option<MfaPrompt>,
     function_selection: Option<FunctionSelection>,
     date_selection: Option<DateSelection>,
+    stream_selection: Option<StreamSelection>,
     log_viewer: Option<LogViewer>,
-    is_loading: bool,
+    /// Second range of the same function, shown beside `log_viewer` in the right pane.
+    comparison_viewer: Option<LogViewer>,
+    /// The same range of the functions next to `log_viewer`'s in the list, loading in the
+    /// background when `log_view.prefetch_adjacent` is on. Dropping one stops its load.
+    prefetched: Vec<LogViewer>,
     focused_panel: FocusedPanel,
+    config_reload_rx: Option<UnboundedReceiver<Result<Config, String>>>,
+    /// Off lets the terminal handle the mouse, for native text selection.
+    mouse_capture: bool,
+    /// Asked for with `--no-color` or `NO_COLOR`, whatever config.toml says.
+    force_no_color: bool,
+    /// The events were piped in on stdin, with no profile or function to go back to.
+    piped_input: bool,
 }
 
 impl App {
-    async fn new() -> Result<Self> {
-        let config = Config::new()?;
-        let profiles = config.aws_profiles;
-        Ok(App {
+    fn new(config: Config) -> Self {
+        let profiles = config.aws_profiles.clone();
+        let mut profile_selection = ProfileSelection::new(profiles);
+        if !config.warnings.is_empty() {
+            profile_selection.notice = Some(config.warnings.join(" | "));
+        }
+        App {
+            config,
             state: AppState::ProfileSelection,
-            profile_selection: ProfileSelection::new(profiles),
+            profile_selection,
+            region_selection: None,
+            mfa_prompt: None,
             function_selection: None,
             date_selection: None,
+            stream_selection: None,
             log_viewer: None,
-            is_loading: false,
+            comparison_viewer: None,
+            prefetched: Vec::new(),
+            mouse_capture: true,
+            force_no_color: std::env::args().skip(1).any(|arg| arg == "--no-color")
+                || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
             focused_panel: FocusedPanel::Left,
-        })
+            config_reload_rx: None,
+            piped_input: false,
+        }
     }
 
-    async fn select_profile(&mut self) -> Result<()> {
+    /// Shows events piped in on stdin over the range they span, without touching AWS.
+    fn open_piped_events(&mut self, mut events: Vec<FilteredLogEvent>) {
+        events.sort_by_key(|event| event.timestamp);
+        let timestamps = || events.iter().filter_map(|event| event.timestamp);
+        let now = Local::now();
+        let from_date = timestamps().min().map(millis_to_local).unwrap_or(now);
+        let to_date = timestamps().max().map(millis_to_local).unwrap_or(now);
+
+        let mut date_selection = DateSelection::new("-".to_string(), PIPED_SOURCE_NAME.to_string());
+        date_selection.from_date = from_date;
+        date_selection.to_date = to_date;
+        date_selection.custom_selection = true;
+        date_selection.selected_quick_range = None;
+
+        // Not a Lambda function, so nothing is restored or saved under its name
+        let mut log_viewer = LogViewer::detached(
+            PIPED_SOURCE_NAME.to_string(),
+            from_date,
+            to_date,
+            self.config.log_view.clone(),
+        );
+        log_viewer.set_status(format!("Read {} events from stdin", events.len()));
+        log_viewer.set_logs(events);
+
+        self.date_selection = Some(date_selection);
+        self.log_viewer = Some(log_viewer);
+        self.piped_input = true;
+        self.state = AppState::LogViewer;
+    }
+
+    fn select_profile(&mut self) {
         if let Some(profile) = self.profile_selection.selected_profile() {
-            let mut function_selection = FunctionSelection::new(profile);
-            function_selection.load_functions().await?;
-            self.function_selection = Some(function_selection);
-            self.state = AppState::FunctionList;
+            // Ask for a region instead of letting every AWS call fail without one
+            if profile.region.is_empty() {
+                self.region_selection = Some(RegionSelection::new(profile));
+                self.state = AppState::RegionSelection;
+                return;
+            }
+
+            // Role profiles guarded by mfa_serial need a code before any AWS call
+            if let Some(requirement) = profile.mfa.clone() {
+                if mfa_session(&profile.name).is_none() {
+                    self.mfa_prompt = Some(MfaPrompt::new(profile, requirement));
+                    self.state = AppState::MfaPrompt;
+                    return;
+                }
+            }
+
+            self.open_function_list(profile);
+        }
+    }
+
+    /// Makes the date selection match the log viewer's range after it was nudged, so going
+    /// back to it, or reopening the viewer from it, stays on the range being read.
+    fn keep_nudged_range(&mut self) {
+        let (Some(log_viewer), Some(date_selection)) = (&self.log_viewer, &mut self.date_selection)
+        else {
+            return;
+        };
+        date_selection.from_date = log_viewer.from_date;
+        date_selection.to_date = log_viewer.to_date;
+        date_selection.custom_selection = true;
+        date_selection.selected_quick_range = None;
+    }
+
+    /// Asks for a new MFA code once the session of a profile in use runs out, then goes back
+    /// to the same screen; the clients already made pick the new session up.
+    fn check_mfa_expiry(&mut self) {
+        if self.mfa_prompt.is_some()
+            || matches!(
+                self.state,
+                AppState::ProfileSelection | AppState::RegionSelection
+            )
+        {
+            return;
+        }
+        let in_use = self
+            .function_selection
+            .iter()
+            .map(|selection| selection.profile.name.as_str())
+            .chain(
+                self.log_viewer
+                    .iter()
+                    .flat_map(|viewer| viewer.aggregate_profiles.iter().map(String::as_str)),
+            );
+        let expired = in_use
+            .filter(|name| mfa_session_expired(name))
+            .find_map(|name| self.config.aws_profiles.iter().find(|p| p.name == name));
+        let Some((profile, requirement)) =
+            expired.and_then(|profile| Some((profile.clone(), profile.mfa.clone()?)))
+        else {
+            return;
+        };
+
+        let mut mfa_prompt = MfaPrompt::new(profile, requirement);
+        mfa_prompt.resume = Some(self.state);
+        self.mfa_prompt = Some(mfa_prompt);
+        self.state = AppState::MfaPrompt;
+    }
+
+    /// Starts in the function list of `default_profile`, or in the profile list when it
+    /// isn't one of the configured profiles.
+    fn open_default_profile(&mut self) {
+        let Some(name) = self.config.default_profile.clone() else {
+            return;
+        };
+        if self.profile_selection.select_named(&name) {
+            self.select_profile();
+        } else {
+            self.profile_selection.notice = Some(format!(
+                "default_profile '{}' is not one of the profiles in {}",
+                name, CONFIG_PATH
+            ));
         }
-        Ok(())
+    }
+
+    fn open_function_list(&mut self, profile: Profile) {
+        // Prefetches belong to the previous profile or region
+        self.prefetched.clear();
+        let mut function_selection = FunctionSelection::new(profile);
+        function_selection.load_functions();
+        self.function_selection = Some(function_selection);
+        self.state = AppState::FunctionList;
+    }
+
+    /// Applies and remembers the region picked for a profile, then continues to its functions.
+    fn select_region(&mut self) {
+        let Some(region_selection) = self.region_selection.take() else {
+            return;
+        };
+        let Some(region) = region_selection.selected_region() else {
+            return;
+        };
+        let profile_name = region_selection.profile.name.clone();
+
+        if region_selection.switching {
+            let filter_input = self
+                .function_selection
+                .take()
+                .map(|selection| selection.filter_input)
+                .unwrap_or_default();
+            let mut profile = region_selection.profile;
+            profile.region = region.to_string();
+            self.open_function_list(profile);
+            // Keep searching for the same function in the new region
+            if let Some(function_selection) = &mut self.function_selection {
+                function_selection.filter_input = filter_input;
+                function_selection.update_filter();
+            }
+            return;
+        }
+
+        let _ = update_state(|state| {
+            state
+                .regions
+                .insert(profile_name.clone(), region.to_string());
+        });
+        for profile in self
+            .profile_selection
+            .profiles
+            .iter_mut()
+            .chain(self.config.aws_profiles.iter_mut())
+            .filter(|p| p.name == profile_name)
+        {
+            profile.region = region.to_string();
+        }
+
+        self.select_profile();
     }
 
     fn enter_date_selection(&mut self) {
         if let Some(function_selection) = &self.function_selection {
-            let profile_name = function_selection.profile.name.clone();
-            let function_name =
-                function_selection.filtered_functions[function_selection.selected_index].clone();
+            if let Some(function_name) = function_selection.selected_function() {
+                let profile_name = function_selection.profile.name.clone();
 
-            self.date_selection = Some(DateSelection::new(profile_name, function_name));
-            self.state = AppState::DateSelection;
+                let mut date_selection = DateSelection::new(profile_name, function_name.clone());
+                date_selection.date_format = self.config.log_view.date_input_format.clone();
+                self.date_selection = Some(date_selection);
+                self.state = AppState::DateSelection;
+            }
         }
     }
 
-    async fn enter_log_viewer(&mut self) -> Result<()> {
+    /// Picks a range to search the selected function's log group in every configured profile.
+    fn enter_all_profiles(&mut self) {
+        self.enter_date_selection();
+        if let Some(date_selection) = &mut self.date_selection {
+            date_selection.all_profiles = true;
+        }
+    }
+
+    fn enter_stream_selection(&mut self) {
         if let (Some(function_selection), Some(date_selection)) =
             (&self.function_selection, &self.date_selection)
         {
-            let function_name =
-                function_selection.filtered_functions[function_selection.selected_index].clone();
+            let mut stream_selection = StreamSelection::new(
+                function_selection.profile.clone(),
+                date_selection.function_name.clone(),
+            );
+            stream_selection.load_streams();
+            self.stream_selection = Some(stream_selection);
+            self.state = AppState::StreamSelection;
+        }
+    }
+
+    /// Skips the date picker and loads the function's most recent events straight away.
+    fn enter_latest_events(&mut self) {
+        self.enter_date_selection();
+        if let Some(date_selection) = &self.date_selection {
             let mut log_viewer = LogViewer::new(
-                function_name,
+                date_selection.function_name.clone(),
                 date_selection.from_date,
                 date_selection.to_date,
+                self.config.log_view.clone(),
             );
+            log_viewer.latest_count = Some(self.config.log_view.latest_events);
 
-            log_viewer
-                .initialize(
+            if let Some(function_selection) = &self.function_selection {
+                log_viewer.initialize(
                     function_selection.profile.name.clone(),
                     function_selection.profile.region.clone(),
-                )
-                .await?;
+                );
+            }
+
+            self.show_log_viewer(log_viewer);
+        }
+    }
+
+    /// Opens the viewer with no history and follows events as they arrive, like `tail -f`.
+    fn enter_live_view(&mut self) {
+        if let (Some(function_selection), Some(date_selection)) =
+            (&self.function_selection, &self.date_selection)
+        {
+            let now = Local::now();
+            let mut log_viewer = LogViewer::new(
+                date_selection.function_name.clone(),
+                now,
+                now,
+                self.config.log_view.clone(),
+            );
+            log_viewer.follow = true;
+
+            log_viewer.initialize(
+                function_selection.profile.name.clone(),
+                function_selection.profile.region.clone(),
+            );
+
+            self.show_log_viewer(log_viewer);
+        }
+    }
+
+    /// Opens the viewer for the selected range, reading a single stream when one is given.
+    fn enter_log_viewer(&mut self, log_stream_name: Option<String>) {
+        let prefetched = match log_stream_name {
+            None => self.take_prefetched(),
+            Some(_) => None,
+        };
+        if let Some(log_viewer) = prefetched.or_else(|| self.load_selected_range(log_stream_name)) {
+            self.show_log_viewer(log_viewer);
+            self.prefetch_adjacent();
+        }
+    }
+
+    fn show_log_viewer(&mut self, mut log_viewer: LogViewer) {
+        log_viewer.visited = true;
+        self.log_viewer = Some(log_viewer);
+        self.state = AppState::LogViewer;
+    }
+
+    /// Hands over the prefetched viewer for the picked function and range, if there is one.
+    /// Quick ranges are picked relative to now, so a range of the same length that ended
+    /// shortly before counts as the same one.
+    fn take_prefetched(&mut self) -> Option<LogViewer> {
+        let date_selection = self.date_selection.as_ref()?;
+        if date_selection.all_profiles {
+            return None;
+        }
+        let length = date_selection.to_date - date_selection.from_date;
+        let index = self.prefetched.iter().position(|viewer| {
+            viewer.function_name == date_selection.function_name
+                && viewer.to_date - viewer.from_date == length
+                && (date_selection.to_date - viewer.to_date).abs() <= PREFETCH_MAX_AGE
+        })?;
+        Some(self.prefetched.swap_remove(index))
+    }
+
+    /// Starts loading the open range for the functions before and after the open one, keeping
+    /// prefetches that still apply and dropping the rest.
+    fn prefetch_adjacent(&mut self) {
+        let (Some(function_selection), Some(log_viewer)) =
+            (&self.function_selection, &self.log_viewer)
+        else {
+            return;
+        };
+        let plain_range = log_viewer.aggregate_profiles.is_empty()
+            && log_viewer.log_stream_name.is_none()
+            && log_viewer.latest_count.is_none();
+        if !self.config.log_view.prefetch_adjacent || !plain_range {
+            self.prefetched.clear();
+            return;
+        }
+
+        let functions = &function_selection.filtered_functions;
+        let neighbours: Vec<&String> = match functions
+            .iter()
+            .position(|name| *name == log_viewer.function_name)
+        {
+            Some(index) => [index.checked_sub(1), Some(index + 1)]
+                .into_iter()
+                .flatten()
+                .filter_map(|i| functions.get(i))
+                .collect(),
+            None => Vec::new(),
+        };
+        let (from_date, to_date) = (log_viewer.from_date, log_viewer.to_date);
+        self.prefetched.retain(|viewer| {
+            neighbours.contains(&&viewer.function_name)
+                && viewer.from_date == from_date
+                && viewer.to_date == to_date
+        });
+
+        for function_name in neighbours {
+            if self
+                .prefetched
+                .iter()
+                .any(|viewer| viewer.function_name == *function_name)
+            {
+                continue;
+            }
+            let mut viewer = LogViewer::new(
+                function_name.clone(),
+                from_date,
+                to_date,
+                self.config.log_view.clone(),
+            );
+            viewer.initialize(
+                function_selection.profile.name.clone(),
+                function_selection.profile.region.clone(),
+            );
+            self.prefetched.push(viewer);
+        }
+    }
+
+    /// Starts loading the range picked in the date selection.
+    fn load_selected_range(&self, log_stream_name: Option<String>) -> Option<LogViewer> {
+        let (function_selection, date_selection) = (
+            self.function_selection.as_ref()?,
+            self.date_selection.as_ref()?,
+        );
+        let mut log_viewer = LogViewer::new(
+            date_selection.function_name.clone(),
+            date_selection.from_date,
+            date_selection.to_date,
+            self.config.log_view.clone(),
+        );
+        log_viewer.log_stream_name = log_stream_name;
+
+        if date_selection.all_profiles {
+            log_viewer.initialize_aggregate(self.config.aws_profiles.clone());
+        } else {
+            log_viewer.initialize(
+                function_selection.profile.name.clone(),
+                function_selection.profile.region.clone(),
+            );
+        }
+        Some(log_viewer)
+    }
 
-            self.log_viewer = Some(log_viewer);
-            self.state = AppState::LogViewer;
+    /// Goes back to the date selection to pick a second range for side-by-side comparison.
+    fn start_comparison(&mut self) {
+        if let Some(date_selection) = &mut self.date_selection {
+            date_selection.for_comparison = true;
+            self.state = AppState::DateSelection;
+        }
+    }
+
+    /// Opens the picked range in the right pane, next to the events already open.
+    fn enter_comparison(&mut self) {
+        if let Some(mut log_viewer) = self.load_selected_range(None) {
+            log_viewer.visited = true;
+            self.comparison_viewer = Some(log_viewer);
+            self.focused_panel = FocusedPanel::Right;
+        }
+        if let Some(date_selection) = &mut self.date_selection {
+            date_selection.for_comparison = false;
+        }
+        self.state = AppState::LogViewer;
+    }
+
+    /// Re-reads config.toml in the background; the result is applied by `poll_background`.
+    fn reload_config(&mut self) {
+        let (tx, rx) = unbounded_channel();
+        self.config_reload_rx = Some(rx);
+        self.profile_selection.notice = Some("Reloading config...".to_string());
+
+        shutdown::spawn(async move {
+            let result = match Config::new() {
+                Ok(mut config) => {
+                    config.resolve_regions().await;
+                    config.detect_mfa().await;
+                    Ok(config)
+                }
+                Err(e) => {
+                    let error = format!("{:#}", e);
+                    error_log::log_error("reload config", &error);
+                    // The notice is a single line; TOML errors span several
+                    Err(error.split_whitespace().collect::<Vec<_>>().join(" "))
+                }
+            };
+            let _ = tx.send(result);
+        });
+    }
+
+    fn apply_reloaded_config(&mut self, result: Result<Config, String>) {
+        match result {
+            Ok(config) => {
+                self.profile_selection
+                    .set_profiles(config.aws_profiles.clone());
+                self.profile_selection.notice = Some(
+                    [format!(
+                        "Reloaded {} ({} profiles)",
+                        CONFIG_PATH,
+                        config.aws_profiles.len()
+                    )]
+                    .into_iter()
+                    .chain(config.warnings.iter().cloned())
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                );
+                self.config = config;
+            }
+            // Keep running with the previous config
+            Err(error) => {
+                self.profile_selection.notice = Some(format!(
+                    "Config not reloaded, keeping the current one: {}",
+                    error
+                ));
+            }
+        }
+    }
+
+    /// Scrolls the focused log pane with the mouse wheel.
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if self.state != AppState::LogViewer {
+            return;
+        }
+        let log_viewer = match self.focused_panel {
+            FocusedPanel::Right if self.comparison_viewer.is_some() => &mut self.comparison_viewer,
+            _ => &mut self.log_viewer,
+        };
+        let Some(log_viewer) = log_viewer else {
+            return;
+        };
+        if log_viewer.reading.is_some() {
+            match mouse.kind {
+                MouseEventKind::ScrollUp => log_viewer.scroll_reading(-1),
+                MouseEventKind::ScrollDown => log_viewer.scroll_reading(1),
+                _ => {}
+            }
+            return;
+        }
+        match (mouse.kind, log_viewer.expanded) {
+            (MouseEventKind::ScrollUp, true) => log_viewer.scroll_up(),
+            (MouseEventKind::ScrollDown, true) => log_viewer.scroll_down(),
+            (MouseEventKind::ScrollUp, false) => {
+                log_viewer.move_selection(-1, log_viewer.page_size)
+            }
+            (MouseEventKind::ScrollDown, false) => {
+                log_viewer.move_selection(1, log_viewer.page_size)
+            }
+            _ => {}
         }
-        Ok(())
     }
+
+    /// Picks up results from background AWS tasks without blocking the render loop.
+    fn poll_background(&mut self) {
+        if let Some(result) = self
+            .config_reload_rx
+            .as_mut()
+            .and_then(|rx| rx.try_recv().ok())
+        {
+            self.config_reload_rx = None;
+            self.apply_reloaded_config(result);
+        }
+        if let Some(mfa_prompt) = &mut self.mfa_prompt {
+            if mfa_prompt.poll_updates() {
+                let profile = mfa_prompt.profile.clone();
+                match mfa_prompt.resume {
+                    Some(state) => self.state = state,
+                    None => self.open_function_list(profile),
+                }
+                self.mfa_prompt = None;
+            }
+        }
+        self.check_mfa_expiry();
+        if let Some(function_selection) = &mut self.function_selection {
+            function_selection.poll_updates();
+        }
+        if let Some(stream_selection) = &mut self.stream_selection {
+            stream_selection.poll_updates();
+        }
+        if let Some(log_viewer) = &mut self.log_viewer {
+            log_viewer.poll_updates();
+        }
+        if let Some(comparison_viewer) = &mut self.comparison_viewer {
+            comparison_viewer.poll_updates();
+        }
+        for viewer in &mut self.prefetched {
+            viewer.poll_updates();
+        }
+    }
+}
+
+/// Stands in for the function name when the events were piped in.
+const PIPED_SOURCE_NAME: &str = "stdin";
+
+/// Reads the events piped in, if stdin isn't the terminal. Keys are still read from the
+/// terminal, which crossterm opens directly when stdin is a pipe. An empty stdin, as under
+/// cron, CI or `< /dev/null`, counts as nothing piped.
+fn read_piped_events() -> Result<Option<Vec<FilteredLogEvent>>> {
+    let mut stdin = io::stdin();
+    if stdin.is_terminal() {
+        return Ok(None);
+    }
+    let mut text = String::new();
+    stdin.read_to_string(&mut text)?;
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    parse_events(&text).map(Some)
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Load the config before touching the terminal so errors print normally
+    let mut config = match Config::new() {
+        Ok(config) => config,
+        Err(e) => {
+            print_config_error(&e);
+            std::process::exit(1);
+        }
+    };
+
+    let piped_events = match read_piped_events() {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("Could not read the events piped in: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Piped events need no profile, so skip the lookups that go through AWS config
+    if piped_events.is_none() {
+        config.resolve_regions().await;
+        config.detect_mfa().await;
+    }
+
+    if let Some(path) = error_log::resolve_path(config.error_log.as_deref()) {
+        if let Err(e) = error_log::init(&path) {
+            eprintln!("Error log disabled: {:#}", e);
+        }
+    }
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -102,80 +665,265 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create app state
-    let mut app = App::new().await?;
+    let mut app = App::new(config);
+    match piped_events {
+        Some(events) => app.open_piped_events(events),
+        None => app.open_default_profile(),
+    }
+
+    let result = run_app(&mut terminal, &mut app);
+    if let Err(e) = &result {
+        error_log::log_error("main loop", &format!("{:#}", e));
+    }
+
+    // Stop loads, follows and prefetches before handing the terminal back
+    shutdown::shutdown().await;
+    drop(app);
 
+    // Restore terminal
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) -> Result<()> {
     // Main loop
     loop {
-        terminal.draw(|f| match app.state {
-            AppState::ProfileSelection => {
-                ui::profile_list_view::draw_profile_selection(f, &mut app.profile_selection)
+        app.poll_background();
+
+        terminal.draw(|f| {
+            if draw_too_small(f) {
+                return;
             }
-            AppState::FunctionList => {
-                if let Some(ref mut function_selection) = app.function_selection {
-                    ui::function_list_view::draw_function_selection(f, function_selection)
+            match app.state {
+                AppState::ProfileSelection => {
+                    ui::profile_list_view::draw_profile_selection(f, &mut app.profile_selection)
                 }
-            }
-            AppState::DateSelection => {
-                if let Some(ref mut date_selection) = app.date_selection {
-                    ui::date_selection::draw_date_selection_panel(f, date_selection);
+                AppState::RegionSelection => {
+                    if let Some(ref mut region_selection) = app.region_selection {
+                        ui::region_list_view::draw_region_selection(f, region_selection);
+                    }
                 }
-            }
-            AppState::LogViewer => {
-                if let Some(ref mut log_viewer) = app.log_viewer {
-                    ui::log_view::draw_log_view(
-                        f,
-                        app.date_selection.as_ref().unwrap(),
-                        Some(log_viewer),
-                        false,
-                        app.focused_panel,
-                    )
+                AppState::MfaPrompt => {
+                    if let Some(ref mfa_prompt) = app.mfa_prompt {
+                        ui::mfa_prompt_view::draw_mfa_prompt(f, mfa_prompt);
+                    }
+                }
+                AppState::FunctionList => {
+                    if let Some(ref mut function_selection) = app.function_selection {
+                        ui::function_list_view::draw_function_selection(f, function_selection)
+                    }
+                }
+                AppState::DateSelection => {
+                    if let Some(ref mut date_selection) = app.date_selection {
+                        ui::date_selection::draw_date_selection_panel(f, date_selection);
+                    }
                 }
+                AppState::StreamSelection => {
+                    if let Some(ref mut stream_selection) = app.stream_selection {
+                        ui::stream_list_view::draw_stream_selection(f, stream_selection);
+                    }
+                }
+                AppState::LogViewer => {
+                    if let Some(ref mut log_viewer) = app.log_viewer {
+                        let is_loading = log_viewer.is_loading && log_viewer.logs.is_empty();
+                        ui::log_view::draw_log_view(
+                            f,
+                            app.date_selection.as_ref().unwrap(),
+                            Some(log_viewer),
+                            app.comparison_viewer.as_mut(),
+                            is_loading,
+                            app.focused_panel,
+                        )
+                    }
+                }
+            }
+            if !app.mouse_capture {
+                draw_corner_note(f, " Mouse capture off (F2) ");
+            }
+            if app.force_no_color || app.config.no_color {
+                strip_colors(f.buffer_mut());
             }
         })?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            if let Event::Mouse(mouse) = event {
+                app.handle_mouse(mouse);
+            }
+            if let Event::Key(key) = event {
+                // Works on every screen, so native selection is one key away
+                if key.code == KeyCode::F(2) {
+                    app.mouse_capture = !app.mouse_capture;
+                    if app.mouse_capture {
+                        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+                    } else {
+                        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+                    }
+                    continue;
+                }
                 match app.state {
-                    AppState::ProfileSelection => match key.code {
-                        KeyCode::Char('q') => break,
-                        KeyCode::Up | KeyCode::Char('k') => app.profile_selection.previous(),
-                        KeyCode::Down | KeyCode::Char('j') => app.profile_selection.next(),
-                        KeyCode::Enter => {
-                            app.select_profile().await?;
+                    AppState::ProfileSelection => {
+                        // A reload notice stays up until the next key press
+                        if app.config_reload_rx.is_none() {
+                            app.profile_selection.notice = None;
+                        }
+                        match key.code {
+                            KeyCode::Char('q') => break,
+                            KeyCode::Up | KeyCode::Char('k') => app.profile_selection.previous(),
+                            KeyCode::Down | KeyCode::Char('j') => app.profile_selection.next(),
+                            KeyCode::Enter => app.select_profile(),
+                            KeyCode::Char('r') if app.config_reload_rx.is_none() => {
+                                app.reload_config()
+                            }
+                            _ => {}
                         }
-                        _ => {}
-                    },
+                    }
+                    AppState::RegionSelection => {
+                        if let Some(ref mut region_selection) = app.region_selection {
+                            match key.code {
+                                KeyCode::Char('q') => break,
+                                KeyCode::Esc => {
+                                    app.state = if region_selection.switching {
+                                        AppState::FunctionList
+                                    } else {
+                                        AppState::ProfileSelection
+                                    };
+                                    app.region_selection = None;
+                                }
+                                KeyCode::Up | KeyCode::Char('k') => region_selection.previous(),
+                                KeyCode::Down | KeyCode::Char('j') => region_selection.next(),
+                                KeyCode::Enter => app.select_region(),
+                                _ => {}
+                            }
+                        }
+                    }
+                    AppState::MfaPrompt => {
+                        if let Some(ref mut mfa_prompt) = app.mfa_prompt {
+                            match key.code {
+                                KeyCode::Char('q') => break,
+                                KeyCode::Esc => {
+                                    app.profile_selection.notice = Some(format!(
+                                        "Profile {} needs an MFA code from {}",
+                                        mfa_prompt.profile.name, mfa_prompt.requirement.mfa_serial
+                                    ));
+                                    app.state = AppState::ProfileSelection;
+                                    app.mfa_prompt = None;
+                                }
+                                KeyCode::Char(c) => mfa_prompt.push_digit(c),
+                                KeyCode::Backspace => mfa_prompt.pop_digit(),
+                                KeyCode::Enter => mfa_prompt.submit(),
+                                _ => {}
+                            }
+                        }
+                    }
                     AppState::FunctionList => {
                         if let Some(ref mut function_selection) = app.function_selection {
                             match key.code {
+                                // The tag filter takes every key while it is being typed
+                                KeyCode::Char(c) if function_selection.editing_tag_filter => {
+                                    function_selection.edit_tag_filter(Some(c));
+                                }
+                                KeyCode::Backspace if function_selection.editing_tag_filter => {
+                                    function_selection.edit_tag_filter(None);
+                                }
+                                KeyCode::Enter if function_selection.editing_tag_filter => {
+                                    function_selection.editing_tag_filter = false;
+                                }
+                                KeyCode::Esc if function_selection.editing_tag_filter => {
+                                    function_selection.clear_tag_filter();
+                                }
+                                _ if function_selection.editing_tag_filter => {}
                                 KeyCode::Char('q') => break,
                                 KeyCode::Esc => {
                                     app.state = AppState::ProfileSelection;
                                     app.function_selection = None;
+                                    app.prefetched.clear();
                                 }
                                 KeyCode::Enter => {
+                                    if app.config.function_list.prefer_exact_match {
+                                        function_selection.select_exact_match();
+                                    }
                                     app.enter_date_selection();
                                 }
+                                KeyCode::Char('l')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    if app.config.function_list.prefer_exact_match {
+                                        function_selection.select_exact_match();
+                                    }
+                                    app.enter_latest_events();
+                                }
+                                KeyCode::Char('r')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    app.region_selection = Some(RegionSelection::switching(
+                                        function_selection.profile.clone(),
+                                    ));
+                                    app.state = AppState::RegionSelection;
+                                }
+                                KeyCode::Char('a')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    if app.config.function_list.prefer_exact_match {
+                                        function_selection.select_exact_match();
+                                    }
+                                    app.enter_all_profiles();
+                                }
+                                KeyCode::Char('s')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    function_selection.cycle_sort();
+                                }
+                                KeyCode::Char('t')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    function_selection.start_tag_filter();
+                                }
+                                KeyCode::Char('g')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    function_selection.copy_log_group();
+                                }
+                                KeyCode::Char('n')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    if let Some((function_name, note)) =
+                                        function_selection.selected_note()
+                                    {
+                                        let edited = with_terminal_suspended(
+                                            terminal,
+                                            app.mouse_capture,
+                                            || edit_in_editor(&note, "md"),
+                                        )?;
+                                        match edited {
+                                            Ok(text) => {
+                                                function_selection.save_note(function_name, &text)
+                                            }
+                                            Err(e) => function_selection
+                                                .set_status(format!("Editor failed: {:#}", e)),
+                                        }
+                                    }
+                                }
+                                KeyCode::F(5) => function_selection.reload_functions(),
                                 KeyCode::Up => function_selection.previous(),
                                 KeyCode::Down => function_selection.next(),
                                 KeyCode::Char(c) => {
                                     function_selection.filter_input.push(c);
-                                    function_selection.update_filter().await?;
+                                    function_selection.update_filter();
                                 }
                                 KeyCode::Backspace => {
                                     function_selection.filter_input.pop();
-                                    function_selection.update_filter().await?;
-                                }
-                                KeyCode::PageUp => {
-                                    for _ in 0..10 {
-                                        function_selection.previous();
-                                    }
-                                }
-                                KeyCode::PageDown => {
-                                    for _ in 0..10 {
-                                        function_selection.next();
-                                    }
+                                    function_selection.update_filter();
                                 }
+                                KeyCode::PageUp => function_selection.page_up(),
+                                KeyCode::PageDown => function_selection.page_down(),
                                 _ => {}
                             }
                         }
@@ -183,16 +931,61 @@ async fn main() -> Result<()> {
                     AppState::DateSelection => {
                         if let Some(ref mut date_selection) = app.date_selection {
                             match key.code {
+                                // While a date-time is being typed every character goes into it
+                                KeyCode::Char(c) if date_selection.typed_input.is_some() => {
+                                    date_selection.edit_typed_input(Some(c))
+                                }
+                                KeyCode::Backspace if date_selection.typed_input.is_some() => {
+                                    date_selection.edit_typed_input(None)
+                                }
+                                KeyCode::Enter if date_selection.typed_input.is_some() => {
+                                    date_selection.finish_typed_input()
+                                }
+                                KeyCode::Esc if date_selection.typed_input.is_some() => {
+                                    date_selection.cancel_typed_input()
+                                }
+                                _ if date_selection.typed_input.is_some() => {}
                                 KeyCode::Char('q') => break,
+                                KeyCode::Esc if date_selection.for_comparison => {
+                                    date_selection.for_comparison = false;
+                                    app.state = AppState::LogViewer;
+                                }
                                 KeyCode::Esc => {
                                     app.state = AppState::FunctionList;
                                     app.date_selection = None;
                                 }
                                 KeyCode::Char('c') => date_selection.toggle_custom(),
-                                KeyCode::Tab => {
-                                    if date_selection.active_column == ActiveColumn::CustomRange {
-                                        date_selection.toggle_selection()
-                                    }
+                                KeyCode::Char('t') if date_selection.custom_selection => {
+                                    date_selection.start_typed_input()
+                                }
+                                // Streams and live tail read through a single profile's client
+                                KeyCode::Char('s')
+                                    if !date_selection.for_comparison
+                                        && !date_selection.all_profiles =>
+                                {
+                                    app.enter_stream_selection()
+                                }
+                                KeyCode::Char('l')
+                                    if !date_selection.for_comparison
+                                        && !date_selection.all_profiles =>
+                                {
+                                    app.enter_live_view()
+                                }
+                                // While editing the custom range, digits type into the focused field
+                                KeyCode::Char(digit)
+                                    if date_selection.custom_selection
+                                        && digit.is_ascii_digit() =>
+                                {
+                                    date_selection.type_digit(digit)
+                                }
+                                KeyCode::Backspace if date_selection.custom_selection => {
+                                    date_selection.clear_pending_digits()
+                                }
+                                KeyCode::Tab
+                                    if date_selection.active_column
+                                        == ActiveColumn::CustomRange =>
+                                {
+                                    date_selection.toggle_selection()
                                 }
                                 KeyCode::Char('1') => {
                                     date_selection.select_column(ActiveColumn::QuickRanges)
@@ -228,87 +1021,491 @@ async fn main() -> Result<()> {
                                         date_selection.next_quick_range();
                                     }
                                 }
+                                KeyCode::Enter if date_selection.for_comparison => {
+                                    app.enter_comparison()
+                                }
                                 KeyCode::Enter => {
                                     // Handle final selection
-                                    app.enter_log_viewer().await?;
+                                    app.enter_log_viewer(None);
                                 }
                                 _ => {}
                             }
                         }
                     }
-                    AppState::LogViewer => {
-                        if let Some(ref mut log_viewer) = app.log_viewer {
+                    AppState::StreamSelection => {
+                        if let Some(ref mut stream_selection) = app.stream_selection {
                             match key.code {
-                                KeyCode::Char('q') => break,
+                                KeyCode::Char('q') if stream_selection.filter_input.is_empty() => {
+                                    break
+                                }
                                 KeyCode::Esc => {
                                     app.state = AppState::DateSelection;
+                                    app.stream_selection = None;
+                                }
+                                KeyCode::Up => stream_selection.previous(),
+                                KeyCode::Down => stream_selection.next(),
+                                KeyCode::Enter => {
+                                    let stream_name =
+                                        stream_selection.selected_stream().map(|s| s.name.clone());
+                                    if stream_name.is_some() {
+                                        app.enter_log_viewer(stream_name);
+                                    }
+                                }
+                                KeyCode::Char(c) => {
+                                    stream_selection.filter_input.push(c);
+                                    stream_selection.update_filter();
+                                }
+                                KeyCode::Backspace => {
+                                    stream_selection.filter_input.pop();
+                                    stream_selection.update_filter();
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    AppState::LogViewer => {
+                        // Keys go to the focused pane when two ranges are compared
+                        let comparing = app.comparison_viewer.is_some();
+                        let focused_viewer = match app.focused_panel {
+                            FocusedPanel::Right if comparing => &mut app.comparison_viewer,
+                            _ => &mut app.log_viewer,
+                        };
+                        let mut switched_pane = false;
+                        let mut nudged = false;
+                        if let Some(ref mut log_viewer) = focused_viewer {
+                            match key.code {
+                                // The find term takes every key while it is being typed
+                                KeyCode::Char(c) if log_viewer.finding => {
+                                    log_viewer.edit_find(Some(c));
+                                }
+                                KeyCode::Backspace if log_viewer.finding => {
+                                    log_viewer.edit_find(None);
+                                }
+                                KeyCode::Enter if log_viewer.finding => log_viewer.finish_find(),
+                                KeyCode::Esc if log_viewer.finding => log_viewer.cancel_find(),
+                                _ if log_viewer.finding => {}
+                                // As does the watch pattern
+                                KeyCode::Char(c) if log_viewer.editing_watch => {
+                                    log_viewer.edit_watch(Some(c));
+                                }
+                                KeyCode::Backspace if log_viewer.editing_watch => {
+                                    log_viewer.edit_watch(None);
+                                }
+                                KeyCode::Enter if log_viewer.editing_watch => {
+                                    log_viewer.finish_watch_edit();
+                                }
+                                KeyCode::Esc if log_viewer.editing_watch => {
+                                    log_viewer.cancel_watch_edit();
+                                }
+                                _ if log_viewer.editing_watch => {}
+                                // So does the field table while it is open
+                                KeyCode::Esc | KeyCode::Char('F')
+                                    if log_viewer.fields.is_some() =>
+                                {
+                                    log_viewer.toggle_fields();
+                                }
+                                KeyCode::Up | KeyCode::Char('k') if log_viewer.fields.is_some() => {
+                                    log_viewer.scroll_fields(-1);
+                                }
+                                KeyCode::Down | KeyCode::Char('j')
+                                    if log_viewer.fields.is_some() =>
+                                {
+                                    log_viewer.scroll_fields(1);
+                                }
+                                KeyCode::Char('c') if log_viewer.fields.is_some() => {
+                                    log_viewer.copy_fields();
+                                }
+                                _ if log_viewer.fields.is_some() => {}
+                                // And the alerts list
+                                KeyCode::Esc | KeyCode::Char('A')
+                                    if log_viewer.alerts_cursor.is_some() =>
+                                {
+                                    log_viewer.toggle_alerts();
+                                }
+                                KeyCode::Up | KeyCode::Char('k')
+                                    if log_viewer.alerts_cursor.is_some() =>
+                                {
+                                    log_viewer.move_alerts_cursor(-1);
+                                }
+                                KeyCode::Down | KeyCode::Char('j')
+                                    if log_viewer.alerts_cursor.is_some() =>
+                                {
+                                    log_viewer.move_alerts_cursor(1);
+                                }
+                                KeyCode::Enter if log_viewer.alerts_cursor.is_some() => {
+                                    log_viewer.jump_to_alert();
+                                }
+                                _ if log_viewer.alerts_cursor.is_some() => {}
+                                // And the events per stream
+                                KeyCode::Esc | KeyCode::Char('S')
+                                    if log_viewer.stream_counts.is_some() =>
+                                {
+                                    log_viewer.toggle_stream_counts();
+                                }
+                                KeyCode::Up | KeyCode::Char('k')
+                                    if log_viewer.stream_counts.is_some() =>
+                                {
+                                    log_viewer.move_stream_cursor(-1);
+                                }
+                                KeyCode::Down | KeyCode::Char('j')
+                                    if log_viewer.stream_counts.is_some() =>
+                                {
+                                    log_viewer.move_stream_cursor(1);
+                                }
+                                KeyCode::Enter if log_viewer.stream_counts.is_some() => {
+                                    log_viewer.show_only_stream();
+                                }
+                                _ if log_viewer.stream_counts.is_some() => {}
+                                KeyCode::Char('q')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.close_filter_tab();
+                                }
+                                // In the filter, q is just another character
+                                KeyCode::Char('q') if log_viewer.focus != LogPanel::Filter => break,
+                                KeyCode::Char('d')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.toggle_reading_view();
+                                }
+                                // The reading view takes the list's keys while open
+                                KeyCode::Esc if log_viewer.reading.is_some() => {
+                                    log_viewer.toggle_reading_view();
+                                }
+                                KeyCode::Enter if log_viewer.reading.is_some() => {
+                                    log_viewer.toggle_reading_view();
+                                    log_viewer.toggle_expand();
+                                }
+                                KeyCode::Up if log_viewer.reading.is_some() => {
+                                    log_viewer.scroll_reading(-1);
+                                }
+                                KeyCode::Down if log_viewer.reading.is_some() => {
+                                    log_viewer.scroll_reading(1);
+                                }
+                                KeyCode::PageUp | KeyCode::PageDown
+                                    if log_viewer.reading.is_some() =>
+                                {
+                                    let page = log_viewer.reading.as_ref().map_or(1, |r| r.height)
+                                        as isize;
+                                    log_viewer.scroll_reading(if key.code == KeyCode::PageUp {
+                                        -page
+                                    } else {
+                                        page
+                                    });
+                                }
+                                KeyCode::Char(c @ ('j' | 'k' | '[' | ']'))
+                                    if log_viewer.reading.is_some()
+                                        && log_viewer.focus != LogPanel::Filter =>
+                                {
+                                    match c {
+                                        'k' => log_viewer.scroll_reading(-1),
+                                        'j' => log_viewer.scroll_reading(1),
+                                        '[' => log_viewer.jump_reading_event(-1),
+                                        _ => log_viewer.jump_reading_event(1),
+                                    }
+                                }
+                                KeyCode::Esc if log_viewer.narrow_hint.is_some() => {
+                                    log_viewer.narrow_hint = None;
+                                }
+                                // A brushed histogram window is cleared before leaving the viewer
+                                KeyCode::Esc if log_viewer.time_window.is_some() => {
+                                    log_viewer.clear_time_window();
+                                }
+                                KeyCode::Esc if log_viewer.only_stream.is_some() => {
+                                    log_viewer.clear_only_stream();
+                                }
+                                KeyCode::Esc if comparing => {
+                                    app.comparison_viewer = None;
+                                    app.focused_panel = FocusedPanel::Left;
+                                }
+                                // Tab walks the panels of a pane, then those of the other pane
+                                KeyCode::Tab => {
+                                    let wrapped = log_viewer.focus_next();
+                                    if wrapped && comparing {
+                                        app.focused_panel = match app.focused_panel {
+                                            FocusedPanel::Left => FocusedPanel::Right,
+                                            FocusedPanel::Right => FocusedPanel::Left,
+                                        };
+                                        switched_pane = true;
+                                    }
+                                }
+                                KeyCode::Char('k')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    app.start_comparison();
+                                }
+                                KeyCode::Esc if app.piped_input => break,
+                                KeyCode::Esc => {
+                                    // Return to wherever the viewer was opened from
+                                    app.state = if log_viewer.latest_count.is_some() {
+                                        app.date_selection = None;
+                                        AppState::FunctionList
+                                    } else if log_viewer.log_stream_name.is_some()
+                                        && app.stream_selection.is_some()
+                                    {
+                                        AppState::StreamSelection
+                                    } else {
+                                        AppState::DateSelection
+                                    };
                                     app.log_viewer = None;
                                 }
+                                KeyCode::Up
+                                    if key.modifiers.contains(KeyModifiers::SHIFT)
+                                        && !log_viewer.expanded =>
+                                {
+                                    log_viewer.extend_selection(-1, log_viewer.page_size);
+                                }
+                                KeyCode::Down
+                                    if key.modifiers.contains(KeyModifiers::SHIFT)
+                                        && !log_viewer.expanded =>
+                                {
+                                    log_viewer.extend_selection(1, log_viewer.page_size);
+                                }
+                                KeyCode::Char('t')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.copy_time_range();
+                                }
+                                KeyCode::Char('e')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.copy_event_json();
+                                }
+                                KeyCode::Char('o')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.toggle_stderr_marks();
+                                }
+                                KeyCode::Char('p')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.toggle_strip_timestamps();
+                                }
+                                KeyCode::Char('s')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.toggle_collapse_whitespace();
+                                }
+                                KeyCode::Char('x')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.cancel_load();
+                                }
+                                KeyCode::Char('r')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.export_summary();
+                                }
+                                KeyCode::Char('u')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.cycle_timestamp_mode();
+                                }
+                                KeyCode::Char('a')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.copy_visible();
+                                }
+                                KeyCode::Char('g')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.toggle_histogram();
+                                }
+                                KeyCode::Char('l')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.clear_filter();
+                                }
+                                KeyCode::Char('y')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.copy_invocation();
+                                }
+                                KeyCode::Char('n')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.apply_narrow_hint();
+                                }
+                                KeyCode::Char('w')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.toggle_time_field();
+                                }
+                                KeyCode::Char('f')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.new_filter_tab();
+                                }
+                                KeyCode::Char('b')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    log_viewer.brush_histogram_bucket();
+                                }
+                                KeyCode::Left
+                                    if log_viewer.settings.show_histogram
+                                        && !log_viewer.expanded =>
+                                {
+                                    log_viewer.move_histogram_cursor(-1);
+                                }
+                                KeyCode::Right
+                                    if log_viewer.settings.show_histogram
+                                        && !log_viewer.expanded =>
+                                {
+                                    log_viewer.move_histogram_cursor(1);
+                                }
                                 KeyCode::Up => {
                                     if log_viewer.expanded {
                                         log_viewer.scroll_up();
                                     } else {
-                                        log_viewer.move_selection(
-                                            -1,
-                                            terminal.size().unwrap().height as usize - 8,
-                                        );
+                                        log_viewer.move_selection(-1, log_viewer.page_size);
                                     }
                                 }
                                 KeyCode::Down => {
                                     if log_viewer.expanded {
-                                        // Get the content height from the current log message
-                                        if let Some(log) = log_viewer.get_selected_log() {
-                                            let message = log.message.as_deref().unwrap_or("");
-                                            let content_height = message.lines().count();
-                                            let visible_height =
-                                                terminal.size().unwrap().height as usize - 8;
-                                            log_viewer.scroll_down();
-                                        }
+                                        log_viewer.scroll_down();
                                     } else {
-                                        log_viewer.move_selection(
-                                            1,
-                                            terminal.size().unwrap().height as usize - 8,
-                                        );
+                                        log_viewer.move_selection(1, log_viewer.page_size);
                                     }
                                 }
+                                KeyCode::F(12) if app.config.debug => log_viewer.toggle_debug(),
                                 KeyCode::Enter => {
                                     log_viewer.toggle_expand();
-                                    log_viewer.scroll_position = 0; // Reset scroll position when toggling
                                 }
-                                KeyCode::Char(c) if !log_viewer.expanded => {
+                                KeyCode::Char(c) if log_viewer.focus == LogPanel::Filter => {
                                     log_viewer.filter_input.push(c);
                                     log_viewer.update_filter();
                                 }
-                                KeyCode::Backspace if !log_viewer.expanded => {
+                                KeyCode::Backspace if log_viewer.focus == LogPanel::Filter => {
                                     log_viewer.filter_input.pop();
                                     log_viewer.update_filter();
                                 }
-                                KeyCode::PageUp => {
-                                    if log_viewer.expanded {
-                                        log_viewer.scroll_position =
-                                            log_viewer.scroll_position.saturating_sub(10);
-                                    } else {
-                                        log_viewer.page_up();
+                                KeyCode::Char('/') => log_viewer.focus = LogPanel::Filter,
+                                KeyCode::Char('f') => log_viewer.toggle_full_width(),
+                                KeyCode::Char('p') => log_viewer.toggle_pin(),
+                                KeyCode::Char('F') => log_viewer.toggle_fields(),
+                                KeyCode::Char('L') => log_viewer.copy_console_link(),
+                                KeyCode::Char('G') => log_viewer.copy_log_group(),
+                                KeyCode::Char('D') => log_viewer.copy_markdown(),
+                                KeyCode::Char('M') => log_viewer.toggle_timestamp_millis(),
+                                KeyCode::Char('z') => log_viewer.toggle_center_selection(),
+                                KeyCode::Char('O') => log_viewer.toggle_order_by(),
+                                KeyCode::Char('x') => log_viewer.mute_selected(),
+                                KeyCode::Char('C') => log_viewer.follow_correlation_id(),
+                                KeyCode::Char('S') => log_viewer.toggle_stream_counts(),
+                                KeyCode::Char('W') => log_viewer.start_watch_edit(),
+                                KeyCode::Char('A') => log_viewer.toggle_alerts(),
+                                KeyCode::Char('{') => log_viewer.switch_filter_tab(-1),
+                                KeyCode::Char('}') => log_viewer.switch_filter_tab(1),
+                                KeyCode::Char('I') if !comparing => {
+                                    if let Some(previous) = log_viewer.previous_invocation() {
+                                        app.comparison_viewer = Some(previous);
                                     }
                                 }
-                                KeyCode::PageDown => {
-                                    if log_viewer.expanded {
-                                        log_viewer.scroll_position =
-                                            log_viewer.scroll_position.saturating_add(10);
-                                    } else {
-                                        log_viewer.page_down();
+                                KeyCode::Char('J') if log_viewer.pinned.is_some() => {
+                                    log_viewer.scroll_pinned(1);
+                                }
+                                KeyCode::Char('K') if log_viewer.pinned.is_some() => {
+                                    log_viewer.scroll_pinned(-1);
+                                }
+                                KeyCode::Char('e') => {
+                                    if let Some((contents, extension)) =
+                                        log_viewer.editor_contents()
+                                    {
+                                        let edited = with_terminal_suspended(
+                                            terminal,
+                                            app.mouse_capture,
+                                            || open_in_editor(&contents, extension),
+                                        )?;
+                                        if let Err(e) = edited {
+                                            log_viewer
+                                                .set_status(format!("Editor failed: {:#}", e));
+                                        }
                                     }
                                 }
+                                KeyCode::Char('?') if !log_viewer.expanded => {
+                                    log_viewer.start_find();
+                                }
+                                KeyCode::Char('n') if !log_viewer.expanded => {
+                                    log_viewer.find_next(1);
+                                }
+                                KeyCode::Char('N') if !log_viewer.expanded => {
+                                    log_viewer.find_next(-1);
+                                }
+                                KeyCode::Char('m') if log_viewer.expanded => {
+                                    log_viewer.load_full_message();
+                                }
+                                KeyCode::Char('v') if log_viewer.expanded => {
+                                    log_viewer.cycle_json_view();
+                                }
+                                KeyCode::Char('t') if log_viewer.expanded => {
+                                    log_viewer.toggle_stack_traces();
+                                }
+                                KeyCode::Char('t') => log_viewer.toggle_follow(),
+                                KeyCode::Char('+') if log_viewer.expanded => {
+                                    log_viewer.adjust_page_jump(PAGE_JUMP_STEP);
+                                }
+                                KeyCode::Char('-') if log_viewer.expanded => {
+                                    log_viewer.adjust_page_jump(-PAGE_JUMP_STEP);
+                                }
+                                KeyCode::Char('=') if log_viewer.expanded => {
+                                    log_viewer.reset_page_jump();
+                                }
+                                KeyCode::Char('[') if log_viewer.expanded => {
+                                    log_viewer.jump_section(-1);
+                                }
+                                KeyCode::Char(']') if log_viewer.expanded => {
+                                    log_viewer.jump_section(1);
+                                }
+                                KeyCode::Char('<') => nudged = log_viewer.shift_range(-1),
+                                KeyCode::Char('>') => nudged = log_viewer.shift_range(1),
+                                KeyCode::Char('+') => nudged = log_viewer.zoom_range(true),
+                                KeyCode::Char('-') => nudged = log_viewer.zoom_range(false),
+                                KeyCode::Char('k') if log_viewer.focus == LogPanel::Detail => {
+                                    log_viewer.scroll_up();
+                                }
+                                KeyCode::Char('j') if log_viewer.focus == LogPanel::Detail => {
+                                    log_viewer.scroll_down();
+                                }
+                                KeyCode::Char('k') => {
+                                    log_viewer.move_selection(-1, log_viewer.page_size);
+                                }
+                                KeyCode::Char('j') => {
+                                    log_viewer.move_selection(1, log_viewer.page_size);
+                                }
+                                KeyCode::PageUp => log_viewer.page_up(),
+                                KeyCode::PageDown => log_viewer.page_down(),
                                 _ => {}
                             }
                         }
+                        // The comparison's range is its own; the main one is kept for going back
+                        if nudged && !(comparing && app.focused_panel == FocusedPanel::Right) {
+                            app.keep_nudged_range();
+                        }
+                        // A pane is entered at its filter, the first panel in the Tab order
+                        if switched_pane {
+                            let entered = match app.focused_panel {
+                                FocusedPanel::Right => app.comparison_viewer.as_mut(),
+                                FocusedPanel::Left => app.log_viewer.as_mut(),
+                            };
+                            if let Some(viewer) = entered {
+                                viewer.focus = LogPanel::Filter;
+                            }
+                        }
                     }
                 }
             }
         }
     }
 
-    // Restore terminal
+    Ok(())
+}
+
+/// Hands the terminal to `run`, e.g. an external editor, and takes it back afterwards.
+fn with_terminal_suspended<T>(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    mouse_capture: bool,
+    run: impl FnOnce() -> T,
+) -> Result<T> {
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
@@ -317,5 +1514,29 @@ async fn main() -> Result<()> {
     )?;
     terminal.show_cursor()?;
 
-    Ok(())
+    let result = run();
+
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    if mouse_capture {
+        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    }
+    // Whatever the editor left on screen is gone, so draw everything again
+    terminal.clear()?;
+    Ok(result)
+}
+
+fn print_config_error(error: &anyhow::Error) {
+    let config_path = std::env::current_dir()
+        .map(|dir| dir.join(CONFIG_PATH).display().to_string())
+        .unwrap_or_else(|_| CONFIG_PATH.to_string());
+
+    eprintln!("Failed to load configuration: {:#}", error);
+    eprintln!();
+    eprintln!("The config file is expected at {}", config_path);
+    eprintln!("(it is read from the directory the app is started in).");
+    eprintln!();
+    eprintln!("Example config.toml:");
+    eprintln!();
+    eprintln!("{}", EXAMPLE_CONFIG);
 }
@@ -1,28 +1,288 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use serde::Deserialize;
 use std::fs;
-use toml;
+use std::path::PathBuf;
 
-#[derive(Debug, Deserialize)]
+use crate::app_state::date_selection::DEFAULT_DATE_INPUT_FORMAT;
+use crate::utils::aws_utils::MfaRequirement;
+use crate::utils::export::{ExportFormat, DEFAULT_EXPORT_FILENAME};
+
+#[derive(Debug, Deserialize, Default)]
 pub struct AwsConfig {
     pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub debug: bool,
+    /// File the app appends its own errors to, for diagnosing a session afterwards.
+    #[serde(default)]
+    pub error_log: Option<PathBuf>,
+    /// Profile opened at startup, skipping the profile list.
+    #[serde(default)]
+    pub default_profile: Option<String>,
+    /// Draws with text attributes only, for terminals with poor color support.
+    #[serde(default)]
+    pub no_color: bool,
+    #[serde(default)]
+    pub log_view: LogViewSettings,
+    #[serde(default)]
+    pub function_list: FunctionListSettings,
+}
+
+/// Most events `GetLogEvents` returns per request, and so the most `latest_events` can ask for.
+pub const MAX_LATEST_EVENTS: usize = 10_000;
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LogViewSettings {
+    /// Maximum number of message lines shown per row in the log list.
+    pub preview_lines: usize,
+    /// Maximum number of characters of each message line shown in the log list.
+    pub preview_chars: Option<usize>,
+    /// Loading stops once this many events have been fetched.
+    pub max_events: usize,
+    /// Number of events loaded by the "latest events" shortcut in the function list.
+    pub latest_events: usize,
+    /// Re-reads the whole range this often, to pick up late-ingested events. Off when unset.
+    pub auto_refresh_secs: Option<u64>,
+    /// Marks events that look like stderr output (errors, tracebacks) in the log list.
+    pub mark_stderr: bool,
+    /// Hides ISO-8601 timestamps that messages repeat at their start, in the log list only.
+    pub strip_timestamps: bool,
+    /// Halves leading indentation and squeezes runs of spaces in displayed messages.
+    pub collapse_whitespace: bool,
+    /// Shows the event-volume histogram above the log list.
+    pub show_histogram: bool,
+    /// Moving past the last event selects the first one, and the other way round.
+    pub wrap_navigation: bool,
+    /// Keeps the selected event in the middle of the list; off, the list only scrolls once
+    /// the selection reaches its top or bottom.
+    pub center_selection: bool,
+    /// How event timestamps are written in the list and the detail view.
+    pub timestamp_mode: TimestampMode,
+    /// Shows milliseconds in the log list's timestamps too; the detail view always does.
+    pub timestamp_millis: bool,
+    /// Format of exported summaries.
+    pub export_format: ExportFormat,
+    /// Name of exported files, without the extension; see `export_stem` for the placeholders.
+    pub export_filename: String,
+    /// How JSON messages are laid out in the detail view.
+    pub json_view: JsonView,
+    /// Shows only the top frames of long stack traces in the detail view.
+    pub collapse_stack_traces: bool,
+    /// Which time the histogram and its narrowed windows go by.
+    pub time_field: TimeField,
+    /// Which time the log list is ordered by; events as loaded are in event-time order.
+    pub order_by: TimeField,
+    /// Expands the event when a filter leaves just one.
+    pub expand_single_result: bool,
+    /// Fields (JSON keys, or `key=value` in text) holding the ID that ties one transaction's
+    /// lines together, tried in order.
+    pub correlation_keys: Vec<String>,
+    /// Regex finding the correlation ID instead, from its first group or the whole match.
+    pub correlation_pattern: Option<String>,
+    /// Filter a log viewer opens with when the function has no filter of its own saved.
+    pub default_filter: String,
+    /// Columns the detail view indents wrapped continuation rows by; 0 leaves them flush.
+    pub wrap_indent: usize,
+    /// chrono format of date-times typed into the custom range with `t`.
+    pub date_input_format: String,
+    /// Lines PageUp/PageDown scroll the detail view by. A screenful when unset.
+    pub page_jump: Option<usize>,
+    /// Loads the same range of the functions before and after the open one in the background.
+    pub prefetch_adjacent: bool,
+    /// Appended to message lines cut to fit the log list, and shown below rows with more lines.
+    pub truncation_marker: String,
+    /// Color of the truncation marker: a name such as "darkgray", an index such as "8", or "#rrggbb".
+    pub truncation_color: String,
+    /// Rings the terminal bell when a followed event matches the watch pattern.
+    pub alert_bell: bool,
+}
+
+impl Default for LogViewSettings {
+    fn default() -> Self {
+        Self {
+            preview_lines: 3,
+            preview_chars: None,
+            max_events: 10_000,
+            latest_events: 100,
+            auto_refresh_secs: None,
+            mark_stderr: true,
+            strip_timestamps: false,
+            collapse_whitespace: false,
+            show_histogram: true,
+            wrap_navigation: false,
+            center_selection: true,
+            timestamp_mode: TimestampMode::Local,
+            timestamp_millis: false,
+            export_format: ExportFormat::Csv,
+            export_filename: DEFAULT_EXPORT_FILENAME.to_string(),
+            json_view: JsonView::Pretty,
+            collapse_stack_traces: true,
+            time_field: TimeField::Event,
+            order_by: TimeField::Event,
+            expand_single_result: false,
+            default_filter: String::new(),
+            correlation_keys: [
+                "traceId",
+                "correlationId",
+                "X-Amzn-Trace-Id",
+                "x-correlation-id",
+            ]
+            .map(String::from)
+            .to_vec(),
+            correlation_pattern: None,
+            wrap_indent: 0,
+            date_input_format: DEFAULT_DATE_INPUT_FORMAT.to_string(),
+            page_jump: None,
+            prefetch_adjacent: false,
+            truncation_marker: "…".to_string(),
+            truncation_color: "darkgray".to_string(),
+            alert_bell: false,
+        }
+    }
+}
+
+impl LogViewSettings {
+    /// Brings values AWS would reject into range, returning a warning for each one changed.
+    pub fn clamp_limits(&mut self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let latest_events = self.latest_events.clamp(1, MAX_LATEST_EVENTS);
+        if latest_events != self.latest_events {
+            warnings.push(format!(
+                "log_view.latest_events must be between 1 and {}; using {}",
+                MAX_LATEST_EVENTS, latest_events
+            ));
+            self.latest_events = latest_events;
+        }
+        warnings
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampMode {
+    #[default]
+    Local,
+    Utc,
+    /// The raw CloudWatch value, milliseconds since the Unix epoch.
+    Epoch,
+    /// Time since the event above in the log list; local time everywhere else.
+    Delta,
+}
+
+impl TimestampMode {
+    pub fn next(self) -> Self {
+        match self {
+            TimestampMode::Local => TimestampMode::Utc,
+            TimestampMode::Utc => TimestampMode::Epoch,
+            TimestampMode::Epoch => TimestampMode::Delta,
+            TimestampMode::Delta => TimestampMode::Local,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            TimestampMode::Local => "local time",
+            TimestampMode::Utc => "UTC",
+            TimestampMode::Epoch => "epoch milliseconds",
+            TimestampMode::Delta => "time since previous event",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeField {
+    /// When the function logged the event.
+    #[default]
+    Event,
+    /// When CloudWatch received it; later than the event time by the delivery lag.
+    Ingestion,
+}
+
+impl TimeField {
+    pub fn toggle(self) -> Self {
+        match self {
+            TimeField::Event => TimeField::Ingestion,
+            TimeField::Ingestion => TimeField::Event,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            TimeField::Event => "event time",
+            TimeField::Ingestion => "ingestion time",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonView {
+    /// One key per line, indented by depth.
+    #[default]
+    Pretty,
+    /// Like pretty, but objects and arrays short enough stay on one line.
+    Compact,
+    /// The message exactly as logged.
+    Raw,
+}
+
+impl JsonView {
+    pub fn next(self) -> Self {
+        match self {
+            JsonView::Raw => JsonView::Pretty,
+            JsonView::Pretty => JsonView::Compact,
+            JsonView::Compact => JsonView::Raw,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            JsonView::Pretty => "pretty",
+            JsonView::Compact => "compact",
+            JsonView::Raw => "raw",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct FunctionListSettings {
+    /// Enter opens the function whose name the filter matches exactly (or the only match),
+    /// even when another row is highlighted.
+    pub prefer_exact_match: bool,
+}
+
+impl Default for FunctionListSettings {
+    fn default() -> Self {
+        Self {
+            prefer_exact_match: true,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Profile {
     pub name: String,
+    /// Empty when config.toml leaves it out; resolved at startup or picked by the user.
+    #[serde(default)]
     pub region: String,
+    /// Set at startup when the AWS config makes this profile assume a role with an MFA code.
+    #[serde(skip)]
+    pub mfa: Option<MfaRequirement>,
 }
 
-pub fn read_aws_profiles() -> Result<Vec<Profile>> {
-    let config_path = "config.toml";
+pub const CONFIG_PATH: &str = "config.toml";
 
-    if !std::path::Path::new(config_path).exists() {
-        return Ok(Vec::new());
+pub fn read_config_file() -> Result<AwsConfig> {
+    if !std::path::Path::new(CONFIG_PATH).exists() {
+        bail!("{} was not found", CONFIG_PATH);
     }
 
-    let content = fs::read_to_string(config_path)?;
-    let config: AwsConfig = toml::from_str(&content)?;
+    let content = fs::read_to_string(CONFIG_PATH)
+        .with_context(|| format!("Could not read {}", CONFIG_PATH))?;
+    let config: AwsConfig =
+        toml::from_str(&content).with_context(|| format!("{} is not valid", CONFIG_PATH))?;
 
-    Ok(config.profiles)
+    Ok(config)
 }
@@ -1,6 +1,6 @@
-use crate::app_state::{date_selection::DateSelection, FocusedPanel};
+use crate::app_state::date_selection::DateSelection;
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, Borders, List, ListItem, Paragraph},
@@ -8,6 +8,7 @@ use ratatui::{
 };
 
 use crate::app_state::date_selection::{ActiveColumn, DateField};
+use crate::ui::shortcuts::{self, footer};
 use chrono::{DateTime, Local};
 
 pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection) {
@@ -24,8 +25,18 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
 
     // Title bar at the top
     let title = Paragraph::new(format!(
-        "Log Viewer | Profile: {} | Function: {}",
-        date_selection.profile_name, date_selection.function_name
+        "Log Viewer | Profile: {} | Function: {}{}",
+        if date_selection.all_profiles {
+            "all"
+        } else {
+            &date_selection.profile_name
+        },
+        date_selection.function_name,
+        if date_selection.for_comparison {
+            " | Pick a range to compare"
+        } else {
+            ""
+        }
     ))
     .style(Style::default().fg(Color::Cyan))
     .block(Block::default().borders(Borders::ALL))
@@ -115,18 +126,10 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
     let from_label = Paragraph::new("From").style(from_style);
     f.render_widget(from_label, date_fields[0]);
 
-    let from_text = format_date_with_highlight(
-        date_selection.from_date,
-        date_selection.is_selecting_from
-            && date_selection.active_column == ActiveColumn::CustomRange,
-        &date_selection.current_field,
-    );
-    let from_input = Paragraph::new(from_text)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(from_style),
-        )
+    let from_focused = date_selection.is_selecting_from
+        && date_selection.active_column == ActiveColumn::CustomRange;
+    let from_input = date_input(date_selection, date_selection.from_date, from_focused)
+        .block(typed_input_block(date_selection, from_focused).border_style(from_style))
         .alignment(Alignment::Left);
     f.render_widget(from_input, date_fields[1]);
 
@@ -141,34 +144,26 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
     let to_label = Paragraph::new("To").style(to_style);
     f.render_widget(to_label, date_fields[3]);
 
-    let to_text = format_date_with_highlight(
-        date_selection.to_date,
-        !date_selection.is_selecting_from
-            && date_selection.active_column == ActiveColumn::CustomRange,
-        &date_selection.current_field,
-    );
-    let to_input = Paragraph::new(to_text)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(to_style),
-        )
+    let to_focused = !date_selection.is_selecting_from
+        && date_selection.active_column == ActiveColumn::CustomRange;
+    let to_input = date_input(date_selection, date_selection.to_date, to_focused)
+        .block(typed_input_block(date_selection, to_focused).border_style(to_style))
         .alignment(Alignment::Left);
     f.render_widget(to_input, date_fields[4]);
 
     // Helper text at the bottom with border
-    let help_text = match date_selection.active_column {
-        ActiveColumn::QuickRanges => {
-            "1/2: Switch Columns | ↑↓: Select Range | Enter: Confirm | Esc: Back | q: Quit"
-        }
-        ActiveColumn::CustomRange => {
-            if date_selection.is_selecting_from {
-                "1/2: Switch Columns | Tab: Switch to To | ←→: Select Field | ↑↓: Adjust Value | Enter: Confirm | Esc: Back | q: Quit"
-            } else {
-                "1/2: Switch Columns | Tab: Switch to From | ←→: Select Field | ↑↓: Adjust Value | Enter: Confirm | Esc: Back | q: Quit"
-            }
+    // In the custom range digits edit the focused field, so only `c` leaves it
+    let help_shortcuts = match date_selection.active_column {
+        _ if date_selection.typed_input.is_some() => shortcuts::DATE_TYPED_INPUT,
+        ActiveColumn::QuickRanges if date_selection.all_profiles => shortcuts::DATE_ALL_PROFILES,
+        ActiveColumn::QuickRanges if date_selection.for_comparison => shortcuts::DATE_COMPARISON,
+        ActiveColumn::QuickRanges => shortcuts::DATE_QUICK_RANGES,
+        ActiveColumn::CustomRange if date_selection.is_selecting_from => {
+            shortcuts::DATE_CUSTOM_FROM
         }
+        ActiveColumn::CustomRange => shortcuts::DATE_CUSTOM_TO,
     };
+    let help_text = footer(help_shortcuts, main_area[2].width);
 
     let help = Paragraph::new(help_text)
         .style(Style::default().fg(Color::Green))
@@ -178,10 +173,45 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
     f.render_widget(help, main_area[2]);
 }
 
+/// The focused field shows the date-time being typed, if any, with a cursor after it.
+fn date_input(
+    date_selection: &DateSelection,
+    date: DateTime<Local>,
+    is_focused: bool,
+) -> Paragraph<'static> {
+    match &date_selection.typed_input {
+        Some(input) if is_focused => Paragraph::new(Line::from(vec![
+            Span::styled(input.clone(), Style::default().fg(Color::Yellow)),
+            Span::styled(" ", Style::default().bg(Color::Yellow)),
+        ])),
+        _ => Paragraph::new(format_date_with_highlight(
+            date,
+            is_focused,
+            &date_selection.current_field,
+            &date_selection.pending_digits,
+        )),
+    }
+}
+
+/// While typing, the box title gives the expected format, or why the last attempt failed.
+fn typed_input_block(date_selection: &DateSelection, is_focused: bool) -> Block<'static> {
+    let block = Block::default().borders(Borders::ALL);
+    if !is_focused || date_selection.typed_input.is_none() {
+        return block;
+    }
+    match &date_selection.typed_error {
+        Some(error) => block
+            .title(error.clone())
+            .title_style(Style::default().fg(Color::Red)),
+        None => block.title(format!("Format: {}", date_selection.date_format)),
+    }
+}
+
 fn format_date_with_highlight(
     date: DateTime<Local>,
     is_selected: bool,
     current_field: &DateField,
+    pending_digits: &str,
 ) -> Text<'static> {
     let date_str = date.format("%Y-%m-%d %H:%M").to_string();
     let mut spans = Vec::new();
@@ -190,7 +220,7 @@ fn format_date_with_highlight(
         spans.push(Span::raw(date_str));
     } else {
         // Create owned strings first
-        let date_parts = (
+        let mut date_parts = (
             date_str[0..4].to_string(),   // Year
             date_str[5..7].to_string(),   // Month
             date_str[8..10].to_string(),  // Day
@@ -198,6 +228,18 @@ fn format_date_with_highlight(
             date_str[14..16].to_string(), // Minute
         );
 
+        // Show a partially typed value in place of the focused field
+        if !pending_digits.is_empty() {
+            let typed = format!("{:_<width$}", pending_digits, width = current_field.width());
+            match current_field {
+                DateField::Year => date_parts.0 = typed,
+                DateField::Month => date_parts.1 = typed,
+                DateField::Day => date_parts.2 = typed,
+                DateField::Hour => date_parts.3 = typed,
+                DateField::Minute => date_parts.4 = typed,
+            }
+        }
+
         // Styles for different states
         let highlight_style = Style::default()
             .fg(Color::Black)
@@ -1,11 +1,18 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
 
-use crate::app_state::function_selection::FunctionSelection;
+use crate::app_state::function_selection::{FunctionSelection, FunctionSort, FunctionTags};
+use crate::ui::shortcuts::{self, footer};
+use crate::utils::time_utils::millis_to_local;
+use crate::utils::ui_utils::{error_paragraph, error_summary};
+
+/// Note lines shown under the highlighted function's details; the rest is in the editor.
+const NOTE_PREVIEW_LINES: usize = 5;
 
 pub fn draw_function_selection(f: &mut Frame, state: &mut FunctionSelection) {
     let chunks = Layout::default()
@@ -28,58 +35,109 @@ pub fn draw_function_selection(f: &mut Frame, state: &mut FunctionSelection) {
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, chunks[0]);
 
-    // Function list layout
+    // Function list layout, with room below for the highlighted function's note
+    let note_rows = state
+        .selected_function()
+        .and_then(|name| state.notes.get(name))
+        .map_or(0, |note| {
+            1 + note.text.lines().count().min(NOTE_PREVIEW_LINES)
+        });
     let inner_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .constraints([
+            Constraint::Length(3),                    // Filter
+            Constraint::Min(0),                       // Functions
+            Constraint::Length(3 + note_rows as u16), // Highlighted function's details
+        ])
         .split(chunks[1]);
 
-    // Filter input
+    // Filter input, and the tag filter next to it once there is one
+    let show_tag_filter = state.editing_tag_filter || !state.tag_filter_input.trim().is_empty();
+    let filter_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(if show_tag_filter {
+            [Constraint::Percentage(60), Constraint::Percentage(40)]
+        } else {
+            [Constraint::Percentage(100), Constraint::Percentage(0)]
+        })
+        .split(inner_chunks[0]);
     let filter_input = Paragraph::new(state.filter_input.as_str())
         .block(Block::default().title("Filter").borders(Borders::ALL));
-    f.render_widget(filter_input, inner_chunks[0]);
+    f.render_widget(filter_input, filter_chunks[0]);
 
-    // Functions list with scroll state
+    if show_tag_filter {
+        let pending = state.pending_tag_lookups();
+        let title = match (state.editing_tag_filter, pending) {
+            (true, _) => "Tags (Enter: Done | Esc: Clear)".to_string(),
+            (false, 0) => "Tags (Ctrl+T: Edit)".to_string(),
+            (false, pending) => format!("Tags (checking {} functions)", pending),
+        };
+        let cursor = if state.editing_tag_filter { "▏" } else { "" };
+        let tag_input = Paragraph::new(format!("{}{}", state.tag_filter_input, cursor))
+            .style(Style::default().fg(Color::Magenta))
+            .block(Block::default().title(title).borders(Borders::ALL));
+        f.render_widget(tag_input, filter_chunks[1]);
+    }
+
+    // Only the visible window of functions is turned into list items
     let total_functions = state.filtered_functions.len();
-    let items_per_page = inner_chunks[1].height as usize - 2; // Subtract 2 for borders
+    let items_per_page = (inner_chunks[1].height as usize).saturating_sub(2); // Subtract 2 for borders
+    state.page_size = items_per_page;
 
-    // Calculate scroll position
-    let selected_index = state.list_state.selected().unwrap_or(0);
-    let scroll_threshold = items_per_page / 2;
-    let scroll_offset = if selected_index > scroll_threshold {
-        selected_index - scroll_threshold
-    } else {
-        0
-    };
+    let selected_index = state.selected_index;
+    let (start, end) = state.get_visible_range(items_per_page);
+    let max_name_width = (inner_chunks[1].width as usize).saturating_sub(4);
 
-    // Create visible items
-    let visible_items: Vec<ListItem> = state
-        .filtered_functions
+    let visible_items: Vec<ListItem> = state.filtered_functions[start..end]
         .iter()
-        .skip(scroll_offset)
-        .take(items_per_page)
         .enumerate()
         .map(|(i, name)| {
-            let display_text = if name.len() > inner_chunks[1].width as usize - 4 {
-                format!("{}...", &name[..inner_chunks[1].width as usize - 7])
+            // The field the list is sorted by follows the name, when it isn't the name
+            let detail = state.summary(name).and_then(|summary| match state.sort {
+                FunctionSort::Name => None,
+                FunctionSort::LastModified => summary
+                    .last_modified
+                    .as_deref()
+                    .map(|date| date.get(..16).unwrap_or(date).replace('T', " ")),
+                FunctionSort::Runtime => summary.runtime.clone(),
+            });
+            // Listed whatever the tag filter says, since their tags can't be checked
+            let tags_unknown =
+                !state.tag_filter_input.trim().is_empty() && state.tags_unknown(name);
+            let detail = detail
+                .into_iter()
+                .chain(tags_unknown.then(|| "tags unknown".to_string()))
+                .map(|detail| format!("  {}", detail))
+                .collect::<String>();
+            let max_name_width = max_name_width.saturating_sub(detail.chars().count());
+
+            let display_text = if name.chars().count() > max_name_width {
+                let truncated: String = name
+                    .chars()
+                    .take(max_name_width.saturating_sub(3))
+                    .collect();
+                format!("{}...", truncated)
             } else {
                 name.clone()
             };
 
-            let style = if i + scroll_offset == selected_index {
+            let style = if start + i == selected_index {
                 Style::default().fg(Color::Yellow).bg(Color::DarkGray)
             } else {
                 Style::default()
             };
 
-            ListItem::new(display_text).style(style)
+            ListItem::new(Line::from(vec![
+                Span::raw(display_text),
+                Span::styled(detail, Style::default().fg(Color::DarkGray)),
+            ]))
+            .style(style)
         })
         .collect();
 
     // Create scroll indicator
     let scroll_indicator = if total_functions > items_per_page {
-        let progress =
-            (scroll_offset as f64 / (total_functions - items_per_page) as f64 * 100.0) as u16;
+        let progress = (start as f64 / (total_functions - items_per_page) as f64 * 100.0) as u16;
         format!(
             " ({}/{}) {}%",
             selected_index + 1,
@@ -90,22 +148,113 @@ pub fn draw_function_selection(f: &mut Frame, state: &mut FunctionSelection) {
         format!(" ({}/{})", selected_index + 1, total_functions)
     };
 
-    let functions_list = List::new(visible_items).block(
-        Block::default()
-            .title(format!("Lambda Functions{}", scroll_indicator))
-            .borders(Borders::ALL),
-    ); // Removed highlight_style
-    f.render_stateful_widget(functions_list, inner_chunks[1], &mut state.list_state);
-
-    // Controls
-    let controls = if total_functions > items_per_page {
-        "↑↓: Navigate | PgUp/PgDn: Scroll | Enter: Select | Esc: Back to profiles | q: Quit"
+    let load_status = if let Some(error) = &state.load_error {
+        format!(" | Error: {}", error_summary(error))
+    } else if state.is_loading {
+        " | Refreshing...".to_string()
     } else {
-        "↑↓: Navigate | Enter: Select | Esc: Back to profiles | q: Quit"
+        String::new()
     };
 
+    let functions_block = Block::default()
+        .title(format!(
+            "Lambda Functions{} | Sorted by {}{}",
+            scroll_indicator,
+            state.sort.label(),
+            load_status
+        ))
+        .borders(Borders::ALL);
+
+    if state.is_loading && state.lambda_functions.is_empty() {
+        let loading_text = Paragraph::new("Loading functions...")
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center)
+            .block(functions_block);
+        f.render_widget(loading_text, inner_chunks[1]);
+    } else if let (Some(error), true) = (&state.load_error, state.lambda_functions.is_empty()) {
+        f.render_widget(error_paragraph(error, functions_block), inner_chunks[1]);
+    } else {
+        let functions_list = List::new(visible_items).block(functions_block);
+        f.render_widget(functions_list, inner_chunks[1]);
+    }
+
+    draw_function_details(f, state, inner_chunks[2]);
+
+    // Controls
+    let controls = footer(shortcuts::FUNCTION_LIST, chunks[2].width);
+
     let controls_widget = Paragraph::new(controls)
         .style(Style::default().fg(Color::Green))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(controls_widget, chunks[2]);
 }
+
+/// Runtime, last deploy and tags of the highlighted function, to confirm it's the right one,
+/// and the note kept on it.
+fn draw_function_details(f: &mut Frame, state: &FunctionSelection, area: Rect) {
+    let Some(summary) = state
+        .selected_function()
+        .and_then(|name| state.summary(name))
+    else {
+        f.render_widget(
+            Block::default().title("Details").borders(Borders::ALL),
+            area,
+        );
+        return;
+    };
+
+    let label = |text: &str| Span::styled(text.to_string(), Style::default().fg(Color::Cyan));
+    let mut spans = vec![
+        label("Runtime: "),
+        Span::raw(summary.runtime.clone().unwrap_or_else(|| "-".to_string())),
+        label("  Modified: "),
+        Span::raw(summary.last_modified.as_deref().map_or_else(
+            || "-".to_string(),
+            |date| date.get(..16).unwrap_or(date).replace('T', " "),
+        )),
+        label("  Tags: "),
+    ];
+    spans.push(match state.tags.get(&summary.name) {
+        Some(FunctionTags::Loaded(tags)) if tags.is_empty() => Span::raw("none"),
+        Some(FunctionTags::Loaded(tags)) => Span::raw(
+            tags.iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        Some(FunctionTags::Failed(error)) => Span::styled(
+            error_summary(error).to_string(),
+            Style::default().fg(Color::Red),
+        ),
+        Some(FunctionTags::Loading) => {
+            Span::styled("loading...", Style::default().fg(Color::Yellow))
+        }
+        // Cached before ARNs were kept; known once the list has been fetched again
+        None => Span::styled("-", Style::default().fg(Color::DarkGray)),
+    });
+
+    let mut lines = vec![Line::from(spans)];
+    if let Some(note) = state.notes.get(&summary.name) {
+        let edited = millis_to_local(note.edited_at).format("%Y-%m-%d %H:%M");
+        lines.push(Line::from(label(&format!("Note (edited {}):", edited))));
+        let note_lines: Vec<&str> = note.text.lines().collect();
+        for (i, line) in note_lines.iter().take(NOTE_PREVIEW_LINES).enumerate() {
+            let more = i + 1 == NOTE_PREVIEW_LINES && note_lines.len() > NOTE_PREVIEW_LINES;
+            lines.push(Line::from(if more {
+                format!("{} …", line)
+            } else {
+                line.to_string()
+            }));
+        }
+    }
+
+    let details = Paragraph::new(lines).block(
+        Block::default()
+            .title(match state.status_message() {
+                Some(message) => format!("Details: {} | {}", summary.name, message),
+                None => format!("Details: {}", summary.name),
+            })
+            .borders(Borders::ALL),
+    );
+    f.render_widget(details, area);
+}
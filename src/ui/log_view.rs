@@ -1,12 +1,19 @@
+use crate::ui::shortcuts::{self, footer};
 use crate::{
     app_state::{
-        date_selection::{DateField, DateSelection},
-        log_viewer::LogViewer,
-        FocusedPanel,
+        date_selection::DateSelection,
+        filter_query::{FilterQuery, TermKind},
+        log_viewer::{LogViewer, HISTOGRAM_BUCKETS},
+        FocusedPanel, LogPanel,
+    },
+    toml_parser::{TimeField, TimestampMode},
+    utils::{
+        log_utils::{collapse_whitespace, strip_timestamp_prefix},
+        time_utils::{format_delta, format_timestamp, millis_to_local},
+        ui_utils::{centered_rect, error_paragraph, error_summary, wrap_with_indent},
     },
-    utils::ui_utils::format_json,
 };
-use chrono::{DateTime, Local};
+use aws_sdk_cloudwatchlogs::types::FilteredLogEvent;
 use ratatui::{
     layout::{Alignment, Constraint, Corner, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
@@ -17,11 +24,13 @@ use ratatui::{
     },
     Frame,
 };
+use std::str::FromStr;
 
 pub fn draw_log_view(
     f: &mut Frame,
     date_selection: &DateSelection,
-    log_viewer: Option<&LogViewer>,
+    mut log_viewer: Option<&mut LogViewer>,
+    comparison_viewer: Option<&mut LogViewer>,
     is_loading: bool,
     focused_panel: FocusedPanel,
 ) {
@@ -35,16 +44,44 @@ pub fn draw_log_view(
         .margin(1)
         .split(f.size());
 
+    let stream_suffix = match log_viewer.as_deref() {
+        Some(viewer) if !viewer.aggregate_profiles.is_empty() => {
+            format!(" | All profiles ({})", viewer.aggregate_profiles.len())
+        }
+        Some(LogViewer {
+            latest_count: Some(count),
+            ..
+        }) => format!(" | Latest {} events", count),
+        Some(LogViewer {
+            log_stream_name: Some(stream),
+            ..
+        }) => format!(" | Stream: {}", stream),
+        _ => String::new(),
+    };
+    let retention = match log_viewer
+        .as_deref()
+        .and_then(|viewer| viewer.retention_days)
+    {
+        Some(days) => format!(" | Retention: {} days", days),
+        None => String::new(),
+    };
+
     let title = Paragraph::new(format!(
-        "Step {}: {} | Profile: {} | Function: {}",
+        "Step {}: {} | Profile: {} | Function: {}{}{}",
         if log_viewer.is_some() { "2" } else { "1" },
         if log_viewer.is_some() {
             "Log Viewer"
         } else {
             "Date Selection"
         },
-        date_selection.profile_name,
-        date_selection.function_name
+        if date_selection.all_profiles {
+            "all"
+        } else {
+            &date_selection.profile_name
+        },
+        date_selection.function_name,
+        stream_suffix,
+        retention
     ))
     .style(Style::default().fg(Color::Cyan))
     .block(Block::default().borders(Borders::ALL))
@@ -52,33 +89,339 @@ pub fn draw_log_view(
 
     f.render_widget(title, layout_chunks[0]);
 
-    draw_logs_panel(f, log_viewer, is_loading, layout_chunks[1], focused_panel);
+    if let Some(comparison_viewer) = comparison_viewer {
+        // Side by side: the range opened first on the left, the compared range on the right
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(layout_chunks[1]);
+
+        let pane_title = |label: &str, viewer: &LogViewer| match &viewer.invocation_id {
+            Some(id) => format!(
+                "{}: Previous invocation {} at {}",
+                label,
+                id,
+                viewer.from_date.format("%Y-%m-%d %H:%M:%S")
+            ),
+            None => format!(
+                "{}: {} - {}",
+                label,
+                viewer.from_date.format("%Y-%m-%d %H:%M"),
+                viewer.to_date.format("%Y-%m-%d %H:%M")
+            ),
+        };
+        if let Some(log_viewer) = log_viewer.as_deref_mut() {
+            let title = pane_title("A", log_viewer);
+            draw_logs_panel(
+                f,
+                Some(log_viewer),
+                is_loading,
+                panes[0],
+                title,
+                focused_panel == FocusedPanel::Left,
+                focused_panel == FocusedPanel::Left,
+            );
+        }
+        let title = pane_title("B", comparison_viewer);
+        let comparison_loading = comparison_viewer.is_loading && comparison_viewer.logs.is_empty();
+        draw_logs_panel(
+            f,
+            Some(comparison_viewer),
+            comparison_loading,
+            panes[1],
+            title,
+            focused_panel == FocusedPanel::Right,
+            focused_panel == FocusedPanel::Right,
+        );
+        draw_fields_overlay(f, comparison_viewer);
+        draw_alerts_overlay(f, comparison_viewer);
+        draw_stream_counts_overlay(f, comparison_viewer);
+    } else {
+        draw_logs_panel(
+            f,
+            log_viewer.as_deref_mut(),
+            is_loading,
+            layout_chunks[1],
+            "2. Logs".to_string(),
+            focused_panel == FocusedPanel::Right,
+            true,
+        );
+    }
+
+    if let Some(log_viewer) = log_viewer.as_deref().filter(|viewer| viewer.show_debug) {
+        draw_debug_overlay(f, log_viewer);
+    }
+    if let Some(log_viewer) = log_viewer {
+        draw_fields_overlay(f, log_viewer);
+        draw_alerts_overlay(f, log_viewer);
+        draw_stream_counts_overlay(f, log_viewer);
+    }
+}
+
+fn draw_stream_counts_overlay(f: &mut Frame, log_viewer: &LogViewer) {
+    let Some(counts) = &log_viewer.stream_counts else {
+        return;
+    };
+    let area = centered_rect(80, 60, f.size());
+    let height = area.height.saturating_sub(2) as usize;
+    // Keeps the highlighted stream in view
+    let first = counts.cursor.saturating_sub(height.saturating_sub(1));
+    let total: usize = counts.rows.iter().map(|(_, count)| count).sum();
+    let count_width = counts
+        .rows
+        .first()
+        .map_or(1, |(_, most)| most.to_string().len());
+
+    let lines: Vec<Line> = counts
+        .rows
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(height)
+        .map(|(i, (stream, count))| {
+            let mut line = Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{:>width$} {:>3}%  ",
+                        count,
+                        count * 100 / total.max(1),
+                        width = count_width
+                    ),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(stream.clone()),
+            ]);
+            if i == counts.cursor {
+                line.patch_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
+            }
+            line
+        })
+        .collect();
+
+    let overlay = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!(
+                "Events per stream ({} streams, {} events) | Enter: Show only this stream | Esc: Close",
+                counts.rows.len(),
+                total
+            ))
+            .borders(Borders::ALL),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(overlay, area);
+}
+
+/// Alerts kept, with how many older ones were dropped to make room.
+fn alert_count(log_viewer: &LogViewer) -> String {
+    match log_viewer.dropped_alerts {
+        0 => log_viewer.alerts.len().to_string(),
+        dropped => format!("{}, {} older dropped", log_viewer.alerts.len(), dropped),
+    }
+}
+
+fn draw_alerts_overlay(f: &mut Frame, log_viewer: &LogViewer) {
+    let Some(cursor) = log_viewer.alerts_cursor else {
+        return;
+    };
+    let area = centered_rect(80, 60, f.size());
+    let height = area.height.saturating_sub(2) as usize;
+    // Keeps the highlighted alert in view
+    let first = cursor.saturating_sub(height.saturating_sub(1));
+
+    let lines: Vec<Line> = log_viewer
+        .alerts
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(height)
+        .map(|(i, alert)| {
+            let message = alert.message.as_deref().unwrap_or("");
+            let mut line = Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{} ",
+                        format_timestamp(
+                            alert.timestamp.unwrap_or(0),
+                            log_viewer.settings.timestamp_mode,
+                            true
+                        )
+                    ),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::raw(message.lines().next().unwrap_or("").to_string()),
+            ]);
+            if i == cursor {
+                line.patch_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
+            }
+            line
+        })
+        .collect();
+
+    let overlay = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!(
+                "Alerts for '{}' ({}) | Enter: Go to event | Esc: Close",
+                log_viewer.watch_pattern().unwrap_or_default(),
+                alert_count(log_viewer)
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(overlay, area);
+}
+
+fn draw_fields_overlay(f: &mut Frame, log_viewer: &mut LogViewer) {
+    let Some(fields) = log_viewer.fields.as_mut() else {
+        return;
+    };
+    let area = centered_rect(70, 60, f.size());
+    let height = area.height.saturating_sub(2) as usize;
+    fields.scroll = fields.scroll.min(fields.rows.len().saturating_sub(height));
+
+    let key_width = fields
+        .rows
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = fields
+        .rows
+        .iter()
+        .skip(fields.scroll)
+        .take(height)
+        .map(|(key, value)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<width$}", key, width = key_width),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(" = "),
+                Span::raw(value.clone()),
+            ])
+        })
+        .collect();
+
+    let overlay = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!(
+                "Fields ({}) | c: Copy | Esc: Close",
+                fields.rows.len()
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(overlay, area);
+}
+
+fn draw_debug_overlay(f: &mut Frame, log_viewer: &LogViewer) {
+    let area = centered_rect(70, 60, f.size());
+    let stats = &log_viewer.fetch_stats;
+
+    let label = |text: &str| Span::styled(text.to_string(), Style::default().fg(Color::Cyan));
+    let slowest_page = stats.page_times.iter().max().copied().unwrap_or_default();
+    let request_time: std::time::Duration = stats.page_times.iter().sum();
+
+    let mut lines = vec![
+        Line::from(vec![
+            label("Log group: "),
+            Span::raw(log_viewer.log_group_name()),
+        ]),
+        Line::from(vec![
+            label("Range: "),
+            Span::raw(format!(
+                "{} - {} ({} - {})",
+                log_viewer.from_date.format("%Y-%m-%d %H:%M:%S"),
+                log_viewer.to_date.format("%Y-%m-%d %H:%M:%S"),
+                log_viewer.from_date.timestamp_millis(),
+                log_viewer.to_date.timestamp_millis()
+            )),
+        ]),
+        Line::from(vec![
+            label("Status: "),
+            Span::raw(if log_viewer.is_loading {
+                "loading"
+            } else if log_viewer.load_error.is_some() {
+                "failed"
+            } else {
+                "finished"
+            }),
+        ]),
+        Line::from(vec![label("Pages: "), Span::raw(stats.pages.to_string())]),
+        Line::from(vec![label("Events: "), Span::raw(stats.events.to_string())]),
+        Line::from(vec![
+            label("Request time: "),
+            Span::raw(format!(
+                "{:.2?} total, {:.2?} slowest page",
+                request_time, slowest_page
+            )),
+        ]),
+        Line::from(vec![
+            label("Wall time: "),
+            Span::raw(match (stats.total_time, stats.started_at) {
+                (Some(total), _) => format!("{:.2?}", total),
+                (None, Some(start)) => format!("{:.2?} (running)", start.elapsed()),
+                (None, None) => "-".to_string(),
+            }),
+        ]),
+    ];
+
+    if let Some(error) = &log_viewer.load_error {
+        lines.push(Line::from(vec![
+            label("Error: "),
+            Span::styled(error.clone(), Style::default().fg(Color::Red)),
+        ]));
+    }
+
+    lines.push(Line::from(label(&format!(
+        "Next tokens seen ({}):",
+        stats.next_tokens.len()
+    ))));
+    lines.extend(
+        stats
+            .next_tokens
+            .iter()
+            .map(|token| Line::from(format!("  {}", token))),
+    );
+
+    let overlay = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Debug: last filter_log_events call (F12 to close)")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Magenta)),
+        )
+        .wrap(ratatui::widgets::Wrap { trim: false });
+
+    f.render_widget(Clear, area);
+    f.render_widget(overlay, area);
 }
 
 fn draw_logs_panel(
     f: &mut Frame,
-    log_viewer: Option<&LogViewer>,
+    log_viewer: Option<&mut LogViewer>,
     is_loading: bool,
     area: ratatui::layout::Rect,
-    focused_panel: FocusedPanel,
+    title: String,
+    is_active: bool,
+    receives_keys: bool,
 ) {
     let right_panel = Block::default()
         .title(format!(
-            "2. Logs{}",
-            if focused_panel == FocusedPanel::Right {
-                " [Active]"
-            } else {
-                ""
-            }
+            "{}{}",
+            title,
+            if is_active { " [Active]" } else { "" }
         ))
         .borders(Borders::ALL)
-        .border_style(
-            Style::default().fg(if focused_panel == FocusedPanel::Right {
-                Color::Yellow
-            } else {
-                Color::White
-            }),
-        );
+        .border_style(Style::default().fg(if is_active {
+            Color::Yellow
+        } else {
+            Color::White
+        }));
     f.render_widget(right_panel.clone(), area);
 
     let inner_area = right_panel.inner(area);
@@ -92,43 +435,130 @@ fn draw_logs_panel(
     }
 
     if let Some(log_viewer) = log_viewer {
+        // Full-width mode keeps only the logs, and the filter while it is being typed in
+        let show_panels = !log_viewer.full_width;
+        let filter_height = if show_panels || log_viewer.focus == LogPanel::Filter {
+            3
+        } else {
+            0
+        };
+        let chips_height = if log_viewer.filter_query.is_empty() || !show_panels {
+            0
+        } else {
+            1
+        };
+        let coverage = log_viewer.coverage().filter(|_| show_panels);
+        let coverage_height = if coverage.is_some() { 1 } else { 0 };
+        let histogram_height =
+            if log_viewer.settings.show_histogram && !log_viewer.expanded && show_panels {
+                3
+            } else {
+                0
+            };
+        let controls_height = if show_panels || log_viewer.finding || log_viewer.editing_watch {
+            3
+        } else {
+            0
+        };
         let log_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Filter
-                Constraint::Min(1),    // Logs
-                Constraint::Length(3), // Helper text
+                Constraint::Length(filter_height),    // Filter
+                Constraint::Length(chips_height),     // Parsed filter terms
+                Constraint::Length(coverage_height),  // Share of the range loaded
+                Constraint::Length(histogram_height), // Event volume
+                Constraint::Min(1),                   // Logs
+                Constraint::Length(controls_height),  // Helper text
             ])
             .margin(1)
             .split(inner_area);
 
+        // Only the pane keys go to shows which of its panels has focus
+        let focus = receives_keys.then_some(log_viewer.focus);
+
         // Filter input
-        let filter_input = Paragraph::new(log_viewer.filter_input.as_str())
-            .block(Block::default().title("Filter").borders(Borders::ALL));
+        let filter_input = Paragraph::new(log_viewer.filter_input.as_str()).block(
+            Block::default()
+                .title(filter_title(log_viewer))
+                .borders(Borders::ALL)
+                .border_style(focus_style(focus == Some(LogPanel::Filter))),
+        );
         f.render_widget(filter_input, log_layout[0]);
 
+        if chips_height > 0 {
+            draw_filter_chips(f, &log_viewer.filter_query, log_layout[1]);
+        }
+
+        if let Some((start, end)) = coverage {
+            draw_coverage(f, log_viewer, start, end, log_layout[2]);
+        }
+
+        if histogram_height > 0 {
+            draw_histogram(f, log_viewer, log_layout[3]);
+        }
+
         // Clear the area before rendering new content
         let clear_widget = ratatui::widgets::Clear;
-        f.render_widget(clear_widget, log_layout[1]);
+        f.render_widget(clear_widget, log_layout[4]);
 
         // Logs content
         if log_viewer.expanded {
-            draw_expanded_log(f, log_viewer, log_layout[1]);
+            draw_expanded_log(
+                f,
+                log_viewer,
+                log_layout[4],
+                focus == Some(LogPanel::Detail),
+            );
+        } else if log_viewer.reading.is_some() {
+            draw_reading_view(f, log_viewer, log_layout[4], focus == Some(LogPanel::List));
+        } else if log_viewer.pinned.is_some() {
+            // The pinned event stays below while the list scrolls above it
+            let split = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(log_layout[4]);
+            draw_log_list(f, log_viewer, split[0], focus == Some(LogPanel::List));
+            draw_pinned_event(f, log_viewer, split[1]);
         } else {
-            draw_log_list(f, log_viewer, log_layout[1]);
+            draw_log_list(f, log_viewer, log_layout[4], focus == Some(LogPanel::List));
         }
 
-        // Controls
-        let controls = if log_viewer.expanded {
-            "Enter: Collapse | Esc: Back | q: Quit"
-        } else {
-            "↑↓: Navigate | Enter: Expand | Filter: Type to filter | Esc: Back | q: Quit"
-        };
+        // Controls for the focused panel, temporarily replaced by status messages such as
+        // copy confirmations
+        let controls = footer(
+            match log_viewer.focus {
+                LogPanel::List if log_viewer.reading.is_some() => shortcuts::LOG_READING,
+                LogPanel::Filter => shortcuts::LOG_FILTER,
+                LogPanel::List => shortcuts::LOG_LIST,
+                LogPanel::Detail => shortcuts::LOG_DETAIL,
+            },
+            log_layout[5].width,
+        );
 
-        let controls_widget = Paragraph::new(controls)
-            .style(Style::default().fg(Color::Green))
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(controls_widget, log_layout[2]);
+        let controls_widget = match (log_viewer.status_message(), log_viewer.narrow_hint) {
+            _ if log_viewer.finding => Paragraph::new(format!(
+                "Find: {}▏ | Enter: Done | Esc: Cancel",
+                log_viewer.find_input
+            ))
+            .style(Style::default().fg(Color::Yellow)),
+            _ if log_viewer.editing_watch => Paragraph::new(format!(
+                "Watch new events for: {}▏ | Enter: Set (empty stops) | Esc: Cancel",
+                log_viewer.watch_input
+            ))
+            .style(Style::default().fg(Color::Yellow)),
+            (Some(status), _) => Paragraph::new(status).style(Style::default().fg(Color::Cyan)),
+            (None, Some((start, end, count))) => Paragraph::new(format!(
+                "{} of {} events fall between {} and {} | Ctrl+N: Narrow to it | Esc: Dismiss",
+                count,
+                log_viewer.filtered_logs.len(),
+                millis_to_local(start).format("%H:%M:%S"),
+                millis_to_local(end).format("%H:%M:%S")
+            ))
+            .style(Style::default().fg(Color::Magenta)),
+            (None, None) => Paragraph::new(controls).style(Style::default().fg(Color::Green)),
+        }
+        .block(Block::default().borders(Borders::ALL));
+        f.render_widget(controls_widget, log_layout[5]);
     } else {
         let placeholder = Paragraph::new("Select date range and press Enter to load logs")
             .style(Style::default().fg(Color::DarkGray))
@@ -137,73 +567,321 @@ fn draw_logs_panel(
     }
 }
 
-fn draw_expanded_log(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::Rect) {
+/// Longest filter shown in a tab label; the full text is in the box once the tab is active.
+const FILTER_TAB_LABEL_WIDTH: usize = 16;
+
+/// "Filter", followed by one label per tab once there are several, the active one highlighted.
+fn filter_title(log_viewer: &LogViewer) -> Line<'static> {
+    let mut spans = vec![Span::raw("Filter")];
+    if log_viewer.filter_tabs.len() < 2 {
+        return Line::from(spans);
+    }
+    for (i, tab) in log_viewer.filter_tabs.iter().enumerate() {
+        let active = i == log_viewer.active_tab;
+        let input = if active {
+            &log_viewer.filter_input
+        } else {
+            &tab.input
+        };
+        let text = if input.is_empty() {
+            "(all)".to_string()
+        } else if input.chars().count() > FILTER_TAB_LABEL_WIDTH {
+            let shortened: String = input.chars().take(FILTER_TAB_LABEL_WIDTH - 1).collect();
+            format!("{}…", shortened)
+        } else {
+            input.clone()
+        };
+        let style = if active {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(format!(" {}: {} ", i + 1, text), style));
+    }
+    Line::from(spans)
+}
+
+/// Shows how the filter input was parsed, one colored token per term.
+fn draw_filter_chips(f: &mut Frame, filter_query: &FilterQuery, area: Rect) {
+    let mut spans = Vec::new();
+    for term in &filter_query.terms {
+        let color = match (&term.kind, term.negated) {
+            (_, true) => Color::Red,
+            (TermKind::Keyword(_), false) => Color::Green,
+            (TermKind::Phrase(_), false) => Color::Yellow,
+            (TermKind::Field { .. }, false) => Color::Cyan,
+        };
+        spans.push(Span::styled(
+            format!(" {} ", term.label()),
+            Style::default().fg(Color::Black).bg(color),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// A bar of the range with the stretch the loaded events span filled in, and the times it
+/// covers, so an early stop shows how much of the range is missing.
+fn draw_coverage(f: &mut Frame, log_viewer: &LogViewer, start: f64, end: f64, area: Rect) {
+    let span = log_viewer.loaded_span();
+    let time = |t: Option<i64>| {
+        t.map(|t| millis_to_local(t).format("%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    };
+    let label = format!(
+        " Loaded {} - {} ({:.0}% of the range)",
+        time(span.map(|(first, _)| first)),
+        time(span.map(|(_, last)| last)),
+        (end - start) * 100.0
+    );
+    let width = (area.width as usize)
+        .saturating_sub(label.chars().count())
+        .max(10);
+    let (first, last) = (
+        (start * width as f64).floor() as usize,
+        ((end * width as f64).ceil() as usize).clamp(1, width),
+    );
+    let bar: String = (0..width)
+        .map(|i| {
+            if (first..last).contains(&i) {
+                '█'
+            } else {
+                '░'
+            }
+        })
+        .collect();
+
+    let color = if log_viewer.is_loading {
+        Color::Cyan
+    } else {
+        Color::Yellow
+    };
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(bar, Style::default().fg(color)),
+            Span::styled(label, Style::default().fg(color)),
+        ])),
+        area,
+    );
+}
+
+/// One bar per bucket of filter matches; the cursor bucket and the brushed window stand out.
+fn draw_histogram(f: &mut Frame, log_viewer: &mut LogViewer, area: Rect) {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let histogram = log_viewer.histogram().clone();
+    let counts = &histogram.counts;
+    let peak = counts.iter().copied().max().unwrap_or(0);
+    let bucket_width = (area.width.saturating_sub(2) as usize / HISTOGRAM_BUCKETS).max(1);
+
+    let spans: Vec<Span> = counts
+        .iter()
+        .enumerate()
+        .map(|(bucket, &count)| {
+            let bar = match count {
+                0 => ' ',
+                _ => BARS[(count * BARS.len()).div_ceil(peak) - 1],
+            };
+            let (start, end) = histogram.bucket_bounds(bucket);
+            let brushed = log_viewer
+                .time_window
+                .is_some_and(|(from, to)| start >= from && end <= to);
+            let style = if Some(bucket) == log_viewer.histogram_cursor {
+                Style::default().fg(Color::Yellow).bg(Color::DarkGray)
+            } else if brushed {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            Span::styled(bar.to_string().repeat(bucket_width), style)
+        })
+        .collect();
+
+    let cursor_note = log_viewer
+        .histogram_cursor
+        .map_or_else(String::new, |bucket| {
+            let (start, end) = histogram.bucket_bounds(bucket);
+            format!(
+                " | {} - {}: {} events",
+                millis_to_local(start).format("%m-%d %H:%M:%S"),
+                millis_to_local(end).format("%H:%M:%S"),
+                counts[bucket]
+            )
+        });
+    let window_note = if log_viewer.time_window.is_some() {
+        " | Narrowed (Esc: full range)"
+    } else {
+        ""
+    };
+
+    let histogram = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .title(format!(
+                "Events by {} (peak {}){}{}",
+                log_viewer.settings.time_field.label(),
+                peak,
+                cursor_note,
+                window_note
+            ))
+            .borders(Borders::ALL),
+    );
+    f.render_widget(histogram, area);
+}
+
+/// Width of the delta column: the first row's time of day, with milliseconds.
+const DELTA_COLUMN_WIDTH: usize = 12;
+
+/// Time since the event listed above, or the time of day for the first one.
+fn delta_from_previous(logs: &[FilteredLogEvent], index: usize) -> String {
+    let timestamp = logs[index].timestamp.unwrap_or(0);
+    match index.checked_sub(1).and_then(|i| logs[i].timestamp) {
+        Some(previous) => format_delta(timestamp - previous),
+        None => millis_to_local(timestamp)
+            .format("%H:%M:%S%.3f")
+            .to_string(),
+    }
+}
+
+/// Border of a panel, highlighted while it has focus.
+fn focus_style(focused: bool) -> Style {
+    if focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    }
+}
+
+fn draw_expanded_log(
+    f: &mut Frame,
+    log_viewer: &mut LogViewer,
+    area: ratatui::layout::Rect,
+    focused: bool,
+) {
     f.render_widget(Clear, area);
     if let Some(log) = log_viewer.get_selected_log() {
-        let message = log.message.as_deref().unwrap_or("");
-        let timestamp = DateTime::<Local>::from(
-            std::time::UNIX_EPOCH
-                + std::time::Duration::from_millis(log.timestamp.unwrap_or(0) as u64),
+        let timestamp = format_timestamp(
+            log.timestamp.unwrap_or(0),
+            log_viewer.settings.timestamp_mode,
+            true,
         );
 
+        let header_height = if log_viewer.full_width { 0 } else { 3 };
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(1),    // Content
+                Constraint::Length(header_height), // Header
+                Constraint::Min(1),                // Content
             ])
             .split(area);
 
         // Header with timestamp
-        let header = Paragraph::new(vec![Line::from(vec![
+        let mut header_spans = vec![
             Span::styled("Timestamp: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::styled(
-                timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
-                Style::default().fg(Color::Cyan),
-            ),
-        ])])
-        .block(Block::default().borders(Borders::ALL).title("Log Details"));
+            Span::styled(timestamp, Style::default().fg(Color::Cyan)),
+        ];
+        if let Some(profile) = log_viewer.event_profile(log) {
+            header_spans.push(Span::styled(
+                "  Profile: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            header_spans.push(Span::styled(
+                profile.to_string(),
+                Style::default().fg(Color::Magenta),
+            ));
+        }
+        let header = Paragraph::new(vec![Line::from(header_spans)])
+            .block(Block::default().borders(Borders::ALL).title("Log Details"));
         f.render_widget(header, layout[0]);
 
-        // Format message content
-        let formatted_content =
-            if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(message) {
-                // If it's valid JSON, format it nicely
-                let formatted_lines = format_json(&json_value, 0);
-                Text::from(formatted_lines)
-            } else {
-                // If it's not JSON, format as regular log message
-                Text::from(format_log_message(message))
-            };
-
         // Content area with scrollbar
         let content_area = layout[1];
         let inner_area = content_area.inner(&Margin {
             vertical: 1,
             horizontal: 1,
         });
-
-        // Count actual lines after formatting
-        let line_count = formatted_content.lines.len();
         let viewport_height = inner_area.height as usize;
+        log_viewer.detail_height = viewport_height;
+
+        // Formatted once on expand and laid out once per width; scrolling counts wrapped
+        // rows, and only the lines on screen are highlighted and wrapped again to draw
+        let width = inner_area.width as usize;
+        let wrap_indent = log_viewer.settings.wrap_indent;
+        if let Some(content) = log_viewer.expanded_content.as_mut() {
+            let top_line = content.line_at(log_viewer.scroll_position);
+            if content.wrap(width, wrap_indent) {
+                // Resized: keep the line that was at the top there
+                log_viewer.scroll_position = content.row_of(top_line);
+            }
+        }
+        log_viewer.scroll_position = log_viewer
+            .scroll_position
+            .min(log_viewer.max_expanded_scroll());
+        let scroll = log_viewer.scroll_position;
+
+        let (formatted_content, row_count, line_count, top_line, is_partial) =
+            match &log_viewer.expanded_content {
+                Some(content) if !content.lines.is_empty() => {
+                    let first_line = content.line_at(scroll);
+                    let last_line = content.line_at(scroll + viewport_height.saturating_sub(1)) + 1;
+                    let highlighted = highlight_lines(
+                        content.lines[first_line..last_line].to_vec(),
+                        &log_viewer.filter_query.highlight_terms(),
+                    );
+                    let rows: Vec<_> = wrap_with_indent(highlighted, width, wrap_indent)
+                        .into_iter()
+                        .skip(scroll - content.row_of(first_line))
+                        .take(viewport_height)
+                        .collect();
+                    (
+                        Text::from(rows),
+                        content.row_count(),
+                        content.lines.len(),
+                        first_line,
+                        content.is_partial(),
+                    )
+                }
+                _ => (Text::default(), 0, 0, 0, false),
+            };
+
+        let partial_note = match &log_viewer.expanded_content {
+            Some(content) if is_partial => format!(
+                " | first {} of {} lines (m: load rest)",
+                content.lines.len(),
+                content.total_lines
+            ),
+            _ => String::new(),
+        };
+
+        let field_note = log_viewer
+            .expanded_content
+            .as_ref()
+            .and_then(|content| content.section_at(top_line))
+            .map_or_else(String::new, |field| format!(" | Field: {}", field));
 
-        // Create content paragraph with scroll
-        let content = Paragraph::new(formatted_content)
-            .block(Block::default().borders(Borders::ALL).title(format!(
-                "Message (Line {} of {})",
-                log_viewer.scroll_position + 1,
-                line_count
-            )))
-            .wrap(ratatui::widgets::Wrap { trim: false })
-            .scroll((log_viewer.scroll_position as u16, 0));
+        let content = Paragraph::new(formatted_content).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(focus_style(focused))
+                .title(format!(
+                    "Message (Line {} of {}){}{}",
+                    top_line + 1,
+                    line_count,
+                    field_note,
+                    partial_note
+                )),
+        );
 
         f.render_widget(content, content_area);
 
         // Only render scrollbar if content is scrollable
-        if line_count > viewport_height {
+        if row_count > viewport_height {
             let mut scrollbar_state = ScrollbarState::default()
-                .content_length(line_count)
-                .position(log_viewer.scroll_position);
+                .content_length(row_count.saturating_sub(viewport_height) + 1)
+                .position(scroll);
 
             f.render_stateful_widget(
                 Scrollbar::new(ScrollbarOrientation::VerticalRight)
@@ -219,7 +897,94 @@ fn draw_expanded_log(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layou
     }
 }
 
-fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::Rect) {
+fn draw_pinned_event(f: &mut Frame, log_viewer: &mut LogViewer, area: Rect) {
+    let height = area.height.saturating_sub(2) as usize;
+    if let Some(pinned) = log_viewer.pinned.as_mut() {
+        pinned.height = height;
+    }
+    let Some(pinned) = &log_viewer.pinned else {
+        return;
+    };
+    let lines = &pinned.content.lines;
+    let first_line = pinned.scroll.min(lines.len());
+    let last_line = (first_line + height).min(lines.len());
+
+    let content = Paragraph::new(Text::from(highlight_lines(
+        lines[first_line..last_line].to_vec(),
+        &log_viewer.filter_query.highlight_terms(),
+    )))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta))
+            .title(format!(
+                "Pinned: {} (line {} of {}) | J/K: Scroll | p: Unpin",
+                format_timestamp(
+                    pinned.event.timestamp.unwrap_or(0),
+                    log_viewer.settings.timestamp_mode,
+                    true
+                ),
+                first_line + 1,
+                lines.len()
+            )),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(content, area);
+}
+
+/// Every listed event's full message, one after another, scrolled as one document.
+fn draw_reading_view(f: &mut Frame, log_viewer: &mut LogViewer, area: Rect, focused: bool) {
+    let terms = log_viewer.filter_query.highlight_terms();
+    let total = log_viewer.filtered_logs.len();
+    let Some(reading) = log_viewer.reading.as_mut() else {
+        return;
+    };
+    reading.height = area.height.saturating_sub(2) as usize;
+
+    let first_line = reading.scroll.min(reading.lines.len());
+    let last_line = (first_line + reading.height).min(reading.lines.len());
+    let content = Paragraph::new(Text::from(highlight_lines(
+        reading.lines[first_line..last_line].to_vec(),
+        &terms,
+    )))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(focus_style(focused))
+            .title(format!(
+                "Reading all matches (event {} of {}, line {} of {})",
+                reading.current_event() + 1,
+                total,
+                first_line + 1,
+                reading.lines.len()
+            )),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(content, area);
+
+    if reading.lines.len() > reading.height {
+        let mut scrollbar_state = ScrollbarState::default()
+            .content_length(reading.lines.len())
+            .position(reading.scroll);
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some("↑"))
+                .end_symbol(Some("↓")),
+            area.inner(&Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+}
+
+fn draw_log_list(
+    f: &mut Frame,
+    log_viewer: &mut LogViewer,
+    area: ratatui::layout::Rect,
+    focused: bool,
+) {
     // Clear the area first
     let clear_text = " ".repeat(area.width as usize);
     for y in 0..area.height {
@@ -237,13 +1002,48 @@ fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::R
     }
 
     let available_width = area.width.saturating_sub(4) as usize; // Subtract 4 for borders and scrollbar
-    let timestamp_width = "YYYY-MM-DD HH:MM:SS ".len();
+    let timestamp_mode = log_viewer.settings.timestamp_mode;
+    // Every mode has a fixed width for current dates, so a sample sizes the column
+    let timestamp_width = match timestamp_mode {
+        TimestampMode::Delta => DELTA_COLUMN_WIDTH,
+        mode => format_timestamp(
+            1_700_000_000_000,
+            mode,
+            log_viewer.settings.timestamp_millis,
+        )
+        .len(),
+    } + 1;
+    // Aggregated views tag each row with its profile, padded to the longest name
+    let profile_width = log_viewer
+        .aggregate_profiles
+        .iter()
+        .map(|name| name.chars().count() + 1)
+        .max()
+        .unwrap_or(0);
+    let timestamp_width = timestamp_width + profile_width;
+    let preview_lines = log_viewer.settings.preview_lines.max(1);
     let message_width = available_width.saturating_sub(timestamp_width);
+    let message_width = log_viewer
+        .settings
+        .preview_chars
+        .map_or(message_width, |chars| message_width.min(chars));
 
     // Calculate visible range
     let visible_height = area.height.saturating_sub(2) as usize; // Subtract 2 for borders
+    log_viewer.page_size = visible_height;
     let total_logs = log_viewer.filtered_logs.len();
     let (start_idx, end_idx) = log_viewer.get_visible_range(visible_height);
+    // Edge scrolling goes on from the window drawn, however the selection got there
+    log_viewer.start_index = start_idx;
+    let selected_range = log_viewer.selected_range();
+    let highlight_terms = log_viewer.filter_query.highlight_terms();
+    let first_new = log_viewer.first_new_event();
+    let marker = log_viewer.settings.truncation_marker.as_str();
+    let marker_span = Span::styled(
+        marker.to_string(),
+        Style::default()
+            .fg(Color::from_str(&log_viewer.settings.truncation_color).unwrap_or(Color::DarkGray)),
+    );
 
     // Get visible logs
     let visible_logs = log_viewer
@@ -256,74 +1056,134 @@ fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::R
     let logs: Vec<ListItem> = visible_logs
         .map(|(i, log)| {
             let message = log.message.as_deref().unwrap_or("");
-            let timestamp = DateTime::<Local>::from(
-                std::time::UNIX_EPOCH
-                    + std::time::Duration::from_millis(log.timestamp.unwrap_or(0) as u64),
-            );
+            let message = if log_viewer.settings.strip_timestamps {
+                strip_timestamp_prefix(message)
+            } else {
+                message
+            };
+            let collapsed;
+            let message = if log_viewer.settings.collapse_whitespace {
+                collapsed = collapse_whitespace(message);
+                collapsed.as_str()
+            } else {
+                message
+            };
+            let timestamp = match timestamp_mode {
+                TimestampMode::Delta => format!(
+                    "{:>width$}",
+                    delta_from_previous(&log_viewer.filtered_logs, i),
+                    width = DELTA_COLUMN_WIDTH
+                ),
+                mode => format_timestamp(
+                    log.timestamp.unwrap_or(0),
+                    mode,
+                    log_viewer.settings.timestamp_millis,
+                ),
+            };
 
             let timestamp_prefix = if Some(i) == log_viewer.selected_log {
-                "→ "
+                "→"
             } else {
-                "  "
+                " "
             };
+            let is_stderr = log_viewer.settings.mark_stderr && log_viewer.is_stderr(log);
 
+            // The column after the selection arrow marks stderr-like events
+            let stream_marker = if is_stderr {
+                Span::styled("▌", Style::default().fg(Color::LightRed))
+            } else {
+                Span::raw(" ")
+            };
             let timestamp_span = Span::styled(
+                format!("{} ", timestamp),
+                Style::default().fg(if is_stderr {
+                    Color::LightRed
+                } else if log_viewer.is_new_since_last_visit(log) {
+                    Color::LightGreen
+                } else {
+                    Color::Gray
+                }),
+            );
+
+            let profile_span = Span::styled(
                 format!(
-                    "{}{} ",
-                    timestamp_prefix,
-                    timestamp.format("%Y-%m-%d %H:%M:%S")
+                    "{:<width$}",
+                    log_viewer.event_profile(log).unwrap_or(""),
+                    width = profile_width
                 ),
-                Style::default().fg(Color::Gray),
+                Style::default().fg(Color::Magenta),
             );
 
             let mut lines = Vec::new();
             let message_lines: Vec<&str> = message.lines().collect();
 
+            if Some(i) == first_new {
+                lines.push(Line::styled(
+                    "─── new since last visit ───",
+                    Style::default().fg(Color::LightGreen),
+                ));
+            }
+
             // Process first line with timestamp
             if let Some(first_msg) = message_lines.first() {
-                let mut first_line_spans = vec![timestamp_span];
-                let truncated_msg = truncate_to_width(first_msg, message_width);
+                let mut first_line_spans = vec![
+                    Span::styled(timestamp_prefix, Style::default().fg(Color::Gray)),
+                    stream_marker,
+                    profile_span,
+                    timestamp_span,
+                ];
+                let (truncated_msg, cut) = truncate_to_width(first_msg, message_width, marker);
 
-                if log_viewer.filter_input.is_empty() {
+                if highlight_terms.is_empty() {
                     first_line_spans.push(Span::raw(truncated_msg));
                 } else {
                     add_highlighted_message_spans(
                         &mut first_line_spans,
                         &truncated_msg,
-                        &log_viewer.filter_input,
+                        &highlight_terms,
                     );
                 }
+                if cut {
+                    first_line_spans.push(marker_span.clone());
+                }
                 lines.push(Line::from(first_line_spans));
             }
 
             // Process remaining lines with indentation
-            for msg in message_lines.iter().skip(1).take(2) {
-                // Show max 3 lines per log
+            for msg in message_lines.iter().skip(1).take(preview_lines - 1) {
+                // Show at most `preview_lines` lines per log
                 let mut line_spans = vec![Span::raw(" ".repeat(timestamp_width + 2))];
-                let truncated_msg = truncate_to_width(msg, message_width);
+                let (truncated_msg, cut) = truncate_to_width(msg, message_width, marker);
 
-                if log_viewer.filter_input.is_empty() {
+                if highlight_terms.is_empty() {
                     line_spans.push(Span::raw(truncated_msg));
                 } else {
                     add_highlighted_message_spans(
                         &mut line_spans,
                         &truncated_msg,
-                        &log_viewer.filter_input,
+                        &highlight_terms,
                     );
                 }
+                if cut {
+                    line_spans.push(marker_span.clone());
+                }
                 lines.push(Line::from(line_spans));
             }
 
             // Add ellipsis if there are more lines
-            if message_lines.len() > 3 {
+            if message_lines.len() > preview_lines {
                 lines.push(Line::from(vec![
                     Span::raw(" ".repeat(timestamp_width + 2)),
-                    Span::styled("...", Style::default().fg(Color::DarkGray)),
+                    marker_span.clone(),
                 ]));
             }
 
+            let in_range = log_viewer.selection_anchor.is_some()
+                && selected_range.is_some_and(|(start, end)| (start..=end).contains(&i));
             let style = if Some(i) == log_viewer.selected_log {
                 Style::default().fg(Color::Yellow).bg(Color::DarkGray)
+            } else if in_range {
+                Style::default().bg(Color::DarkGray)
             } else {
                 Style::default()
             };
@@ -339,22 +1199,144 @@ fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::R
         100
     };
 
-    let logs_list = List::new(logs)
-        .block(
-            Block::default()
-                .title(format!(
-                    "Logs ({}/{}) {}%",
-                    log_viewer.selected_log.map_or(0, |i| i + 1),
-                    total_logs,
-                    scroll_percentage
-                ))
-                .borders(Borders::ALL),
+    let load_status = if let Some(error) = &log_viewer.load_error {
+        format!(" | Error: {}", error_summary(error))
+    } else if log_viewer.refreshing {
+        " | Refreshing... (Ctrl+X: stop)".to_string()
+    } else if log_viewer.is_loading {
+        " | Loading... (Ctrl+X: stop)".to_string()
+    } else if let Some(rate) = log_viewer.follow_rate() {
+        // Per minute reads better than fractions of an event per second
+        let rate = if rate >= 1.0 {
+            format!("{:.1}/s", rate)
+        } else {
+            format!("{:.0}/min", rate * 60.0)
+        };
+        format!(" | Following new events, {} (Ctrl+X: stop)", rate)
+    } else if let Some(truncation) = log_viewer.truncation {
+        format!(
+            " | Incomplete: showing {} of possibly more events ({})",
+            log_viewer.logs.len(),
+            truncation.description()
         )
-        .start_corner(Corner::TopLeft);
+    } else {
+        String::new()
+    };
+
+    // Profiles that failed while others loaded; when all fail the error view lists them
+    let profile_failures =
+        if log_viewer.profile_errors.is_empty() || log_viewer.load_error.is_some() {
+            String::new()
+        } else {
+            format!(
+                " | Failed: {}",
+                log_viewer
+                    .profile_errors
+                    .iter()
+                    .map(|(profile, error)| format!("{} ({})", profile, error_summary(error)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+
+    let refresh_countdown = log_viewer
+        .next_refresh_in()
+        .map_or_else(String::new, |left| {
+            format!(" | Refresh in {}s", left.as_secs())
+        });
+
+    // Older events were deleted by the log group's retention, not missed by the search
+    let retention_note = log_viewer
+        .range_beyond_retention()
+        .map_or_else(String::new, |days| {
+            format!(" | Events older than {} days have been deleted", days)
+        });
+
+    let order_note = if log_viewer.settings.order_by == TimeField::Ingestion {
+        " | By ingestion time"
+    } else {
+        ""
+    };
+
+    let stream_note = log_viewer
+        .only_stream
+        .as_ref()
+        .map_or_else(String::new, |stream| format!(" | Stream: {}", stream));
+
+    let watch_note = log_viewer
+        .watch_pattern()
+        .map_or_else(String::new, |pattern| {
+            format!(
+                " | Watching '{}' ({} alerts)",
+                pattern,
+                alert_count(log_viewer)
+            )
+        });
+
+    let title_style = if (log_viewer.truncation.is_some() || !retention_note.is_empty())
+        && log_viewer.load_error.is_none()
+    {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+
+    let logs_block = Block::default()
+        .title_style(title_style)
+        .title(format!(
+            "Logs ({}/{}) {}%{}{}{}{}{}{}{}",
+            log_viewer.selected_log.map_or(0, |i| i + 1),
+            total_logs,
+            scroll_percentage,
+            order_note,
+            stream_note,
+            load_status,
+            watch_note,
+            profile_failures,
+            retention_note,
+            refresh_countdown
+        ))
+        .borders(Borders::ALL)
+        // Flashes red when a watched pattern has just matched
+        .border_style(if log_viewer.is_alerting() {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            focus_style(focused)
+        });
 
     f.render_widget(Clear, area);
+
+    if let (Some(error), true) = (&log_viewer.load_error, log_viewer.logs.is_empty()) {
+        f.render_widget(error_paragraph(error, logs_block), area);
+        return;
+    }
+
+    let logs_list = List::new(logs)
+        .block(logs_block)
+        .start_corner(Corner::TopLeft);
+
     f.render_widget(logs_list, area);
 
+    // Mark where the loaded events end while more pages are still arriving
+    if log_viewer.is_loading && !log_viewer.refreshing && end_idx == total_logs && area.height > 2 {
+        let marker_area = Rect {
+            x: area.x + 1,
+            y: area.y + area.height - 2,
+            width: area.width.saturating_sub(2),
+            height: 1,
+        };
+        let label = " loading more ";
+        let side = (marker_area.width as usize).saturating_sub(label.len()) / 2;
+        let marker = Paragraph::new(format!("{}{}{}", "─".repeat(side), label, "─".repeat(side)))
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::ITALIC),
+            );
+        f.render_widget(Clear, marker_area);
+        f.render_widget(marker, marker_area);
+    }
+
     // Add scrollbar if there are more logs than visible space
     if total_logs > visible_height {
         // Update scrollbar position to follow selected item
@@ -386,137 +1368,100 @@ fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::R
     }
 }
 
-fn add_highlighted_message_spans(spans: &mut Vec<Span<'static>>, text: &str, filter: &str) {
-    let keywords: Vec<&str> = filter.split_whitespace().collect();
+/// Colors cycled through per filter term, so each term's matches can be told apart.
+const HIGHLIGHT_COLORS: &[Color] = &[
+    Color::Yellow,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Green,
+    Color::LightRed,
+    Color::LightBlue,
+];
+
+fn add_highlighted_message_spans(spans: &mut Vec<Span<'static>>, text: &str, keywords: &[String]) {
+    spans.extend(highlighted_spans(text, Style::default(), keywords));
+}
+
+/// Highlights filter terms inside already styled lines, such as a formatted JSON message.
+fn highlight_lines(lines: Vec<Line<'static>>, keywords: &[String]) -> Vec<Line<'static>> {
+    if keywords.is_empty() {
+        return lines;
+    }
+    lines
+        .into_iter()
+        .map(|line| {
+            let spans = line
+                .spans
+                .iter()
+                .flat_map(|span| highlighted_spans(&span.content, span.style, keywords))
+                .collect::<Vec<_>>();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Splits `text` into spans, coloring each keyword's matches by its position in `keywords`.
+fn highlighted_spans(text: &str, base_style: Style, keywords: &[String]) -> Vec<Span<'static>> {
+    let text_lower = text.to_lowercase();
+    let mut spans = Vec::new();
     let mut last_pos = 0;
-    let mut positions: Vec<(usize, usize)> = Vec::new();
+    let mut positions: Vec<(usize, usize, usize)> = Vec::new();
 
-    // Find all keyword positions
-    for keyword in keywords {
-        let text_lower = text.to_lowercase();
-        let keyword_lower = keyword.to_lowercase();
+    // Lowercasing can change byte lengths for some scripts; offsets would no longer line up
+    if text_lower.len() != text.len() {
+        spans.push(Span::styled(text.to_string(), base_style));
+        return spans;
+    }
 
+    // Find all keyword positions
+    for (index, keyword) in keywords.iter().enumerate().filter(|(_, k)| !k.is_empty()) {
         let mut start = 0;
-        while let Some(pos) = text_lower[start..].find(&keyword_lower) {
+        while let Some(pos) = text_lower[start..].find(keyword.as_str()) {
             let abs_pos = start + pos;
-            positions.push((abs_pos, abs_pos + keyword.len()));
-            start = abs_pos + 1;
+            positions.push((abs_pos, abs_pos + keyword.len(), index));
+            start = abs_pos + keyword.len();
         }
     }
 
     // Sort and deduplicate positions
-    positions.sort_by_key(|k| k.0);
-    positions.dedup();
+    positions.sort_by_key(|k| (k.0, k.2));
+    positions.dedup_by_key(|k| (k.0, k.1));
 
-    // Build spans with highlighting
-    for (start, end) in positions {
+    // Build spans with highlighting, merging overlapping matches
+    for (start, end, index) in positions {
+        let start = start.max(last_pos);
+        if end <= start {
+            continue;
+        }
         if start > last_pos {
-            spans.push(Span::raw(text[last_pos..start].to_string()));
+            spans.push(Span::styled(text[last_pos..start].to_string(), base_style));
         }
         spans.push(Span::styled(
             text[start..end].to_string(),
-            Style::default()
-                .fg(Color::Yellow)
+            base_style
+                .fg(Color::Black)
+                .bg(HIGHLIGHT_COLORS[index % HIGHLIGHT_COLORS.len()])
                 .add_modifier(Modifier::BOLD),
         ));
         last_pos = end;
     }
 
     if last_pos < text.len() {
-        spans.push(Span::raw(text[last_pos..].to_string()));
+        spans.push(Span::styled(text[last_pos..].to_string(), base_style));
     }
+    spans
 }
 
-// Add this new function to format log messages
-fn format_log_message(message: &str) -> Vec<Line<'static>> {
-    let mut lines = Vec::new();
-
-    // Try to parse as JSON first
-    if let Ok(json) = serde_json::from_str::<serde_json::Value>(message) {
-        // Format JSON with pretty print
-        let formatted = format_json(&json, 0);
-        lines.extend(formatted);
-    } else {
-        // Handle non-JSON log messages
-        for line in message.lines() {
-            let line_string = line.to_string(); // Convert to owned String
-            if line.contains("ERROR") || line.contains("error") {
-                lines.push(Line::from(Span::styled(
-                    line_string,
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                )));
-            } else if line.contains("WARN") || line.contains("warn") {
-                lines.push(Line::from(Span::styled(
-                    line_string,
-                    Style::default().fg(Color::Yellow),
-                )));
-            } else if line.contains("DEBUG") || line.contains("debug") {
-                lines.push(Line::from(Span::styled(
-                    line_string,
-                    Style::default().fg(Color::Blue),
-                )));
-            } else if line.contains("INFO") || line.contains("info") {
-                lines.push(Line::from(Span::styled(
-                    line_string,
-                    Style::default().fg(Color::Green),
-                )));
-            } else {
-                lines.push(Line::from(line_string));
-            }
-        }
-    }
-
-    lines
-}
-
-// Add this function to format JSON content
-
-// Add this helper function at the end of the file
-fn wrap_text(text: &str, width: usize) -> Vec<String> {
-    let mut wrapped = Vec::new();
-    let mut line = String::new();
-    let mut line_length = 0;
-
-    for word in text.split_whitespace() {
-        let word_length = word.len();
-
-        if line_length + word_length + 1 <= width {
-            // Add word to current line
-            if !line.is_empty() {
-                line.push(' ');
-                line_length += 1;
-            }
-            line.push_str(word);
-            line_length += word_length;
-        } else {
-            // Start new line
-            if !line.is_empty() {
-                wrapped.push(line);
-            }
-            line = word.to_string();
-            line_length = word_length;
-        }
-    }
-
-    if !line.is_empty() {
-        wrapped.push(line);
-    }
-
-    // Handle empty input or single long words
-    if wrapped.is_empty() {
-        wrapped.push(text.to_string());
-    }
-
-    wrapped
-}
-
-// Add this helper function to truncate text
-fn truncate_to_width(text: &str, width: usize) -> String {
-    if text.len() <= width {
-        text.to_string()
+/// Cuts `text` to leave room for `marker` within `width` columns when it doesn't fit, and
+/// says whether it did. The marker is left to the caller so it can be styled on its own.
+fn truncate_to_width(text: &str, width: usize, marker: &str) -> (String, bool) {
+    if text.chars().count() <= width {
+        (text.to_string(), false)
     } else {
-        let mut truncated = text.chars().take(width - 3).collect::<String>();
-        truncated.push_str("...");
-        truncated
+        let truncated = text
+            .chars()
+            .take(width.saturating_sub(marker.chars().count()))
+            .collect::<String>();
+        (truncated, true)
     }
 }
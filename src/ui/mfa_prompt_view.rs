@@ -0,0 +1,65 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app_state::mfa_prompt::{MfaPrompt, MFA_CODE_LENGTH};
+use crate::ui::shortcuts::{self, footer};
+use crate::utils::ui_utils::error_paragraph;
+
+pub fn draw_mfa_prompt(f: &mut Frame, state: &MfaPrompt) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(5), // Title
+            Constraint::Length(3), // Code input
+            Constraint::Min(0),    // Status
+            Constraint::Length(3), // Controls
+        ])
+        .split(f.size());
+
+    // Title
+    let action = if state.resume.is_some() {
+        "The session expired; enter a new code from your device to carry on."
+    } else {
+        "Enter the current code from your device to start a session."
+    };
+    let title = Paragraph::new(format!(
+        "Profile '{}' assumes {} and requires an MFA code from {}. {}",
+        state.profile.name, state.requirement.role_arn, state.requirement.mfa_serial, action
+    ))
+    .style(Style::default().fg(Color::Cyan))
+    .wrap(Wrap { trim: true })
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(title, chunks[0]);
+
+    // Code input, with the remaining digits as placeholders
+    let code = format!("{:_<width$}", state.code, width = MFA_CODE_LENGTH);
+    let input = Paragraph::new(code)
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().title("MFA code").borders(Borders::ALL));
+    f.render_widget(input, chunks[1]);
+
+    let status_block = Block::default().borders(Borders::ALL);
+    if let Some(error) = &state.error {
+        f.render_widget(error_paragraph(error, status_block), chunks[2]);
+    } else if state.is_submitting {
+        let status = Paragraph::new("Assuming role...")
+            .style(Style::default().fg(Color::Yellow))
+            .block(status_block);
+        f.render_widget(status, chunks[2]);
+    }
+
+    // Controls
+    let controls = Paragraph::new(footer(shortcuts::MFA_PROMPT, chunks[3].width))
+        .style(Style::default().fg(Color::Green))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(controls, chunks[3]);
+}
@@ -1,4 +1,8 @@
 pub mod date_selection;
 pub mod function_list_view;
 pub mod log_view;
+pub mod mfa_prompt_view;
 pub mod profile_list_view;
+pub mod region_list_view;
+pub mod shortcuts;
+pub mod stream_list_view;
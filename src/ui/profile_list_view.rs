@@ -6,6 +6,7 @@ use ratatui::{
 };
 
 use crate::app_state::profile_selection::ProfileSelection;
+use crate::ui::shortcuts::{self, footer};
 
 pub fn draw_profile_selection(f: &mut Frame, state: &mut ProfileSelection) {
     let chunks = Layout::default()
@@ -28,7 +29,15 @@ pub fn draw_profile_selection(f: &mut Frame, state: &mut ProfileSelection) {
     let profiles: Vec<ListItem> = state
         .profiles
         .iter()
-        .map(|profile| ListItem::new(format!("{} ({})", profile.name, profile.region)))
+        .map(|profile| {
+            let region = if profile.region.is_empty() {
+                "region not set"
+            } else {
+                profile.region.as_str()
+            };
+            let mfa = if profile.mfa.is_some() { " [MFA]" } else { "" };
+            ListItem::new(format!("{} ({}){}", profile.name, region, mfa))
+        })
         .collect();
 
     let profiles_list = List::new(profiles)
@@ -37,8 +46,15 @@ pub fn draw_profile_selection(f: &mut Frame, state: &mut ProfileSelection) {
     f.render_stateful_widget(profiles_list, chunks[1], &mut state.list_state);
 
     // Controls
-    let controls = Paragraph::new("↑↓ or j/k: Navigate profiles | Enter: Select | q: Quit")
-        .style(Style::default().fg(Color::Green))
+    let (controls_text, controls_color) = match &state.notice {
+        Some(notice) => (notice.clone(), Color::Yellow),
+        None => (
+            footer(shortcuts::PROFILE_SELECTION, chunks[2].width),
+            Color::Green,
+        ),
+    };
+    let controls = Paragraph::new(controls_text)
+        .style(Style::default().fg(controls_color))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(controls, chunks[2]);
 }
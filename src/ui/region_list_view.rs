@@ -0,0 +1,56 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app_state::region_selection::{RegionSelection, COMMON_REGIONS};
+use crate::ui::shortcuts::{self, footer};
+
+pub fn draw_region_selection(f: &mut Frame, state: &mut RegionSelection) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(4), // Title
+            Constraint::Min(0),    // Main content
+            Constraint::Length(3), // Controls
+        ])
+        .split(f.size());
+
+    // Title
+    let title = Paragraph::new(if state.switching {
+        format!(
+            "Switch profile '{}' from {} to another region for this session. \
+             Its functions are loaded again from the new region.",
+            state.profile.name, state.profile.region
+        )
+    } else {
+        format!(
+            "No region is configured for profile '{}' in config.toml or the AWS config files. \
+             Pick one to use; it will be remembered for this profile.",
+            state.profile.name
+        )
+    })
+    .style(Style::default().fg(Color::Cyan))
+    .wrap(Wrap { trim: true })
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(title, chunks[0]);
+
+    let regions: Vec<ListItem> = COMMON_REGIONS
+        .iter()
+        .map(|region| ListItem::new(*region))
+        .collect();
+
+    let regions_list = List::new(regions)
+        .block(Block::default().title("Regions").borders(Borders::ALL))
+        .highlight_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
+    f.render_stateful_widget(regions_list, chunks[1], &mut state.list_state);
+
+    // Controls
+    let controls = Paragraph::new(footer(shortcuts::REGION_SELECTION, chunks[2].width))
+        .style(Style::default().fg(Color::Green))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(controls, chunks[2]);
+}
@@ -0,0 +1,140 @@
+/// A key and what it does on the screen it is listed for.
+/// Each screen's footer lists at most five of its most used keys, most used first, since the
+/// footer drops entries from the end on narrow terminals. Every key is in the README's
+/// Keyboard Shortcuts section.
+pub type Shortcut = (&'static str, &'static str);
+
+pub const PROFILE_SELECTION: &[Shortcut] = &[
+    ("↑↓ or j/k", "Navigate profiles"),
+    ("Enter", "Select"),
+    ("r", "Reload config"),
+    ("q", "Quit"),
+];
+
+pub const REGION_SELECTION: &[Shortcut] = &[
+    ("↑↓ or j/k", "Navigate regions"),
+    ("Enter", "Select"),
+    ("Esc", "Back"),
+    ("q", "Quit"),
+];
+
+pub const MFA_PROMPT: &[Shortcut] = &[
+    ("0-9", "Type code"),
+    ("Enter", "Submit"),
+    ("Backspace", "Delete"),
+    ("Esc", "Back"),
+    ("q", "Quit"),
+];
+
+pub const FUNCTION_LIST: &[Shortcut] = &[
+    ("↑↓", "Navigate"),
+    ("Enter", "Select"),
+    ("Ctrl+L", "Latest events"),
+    ("Esc", "Back to profiles"),
+    ("q", "Quit"),
+];
+
+pub const DATE_QUICK_RANGES: &[Shortcut] = &[
+    ("↑↓", "Select Range"),
+    ("Enter", "Confirm"),
+    ("1/2 or c", "Switch Columns"),
+    ("l", "Live Tail"),
+    ("Esc", "Back"),
+];
+
+pub const DATE_ALL_PROFILES: &[Shortcut] = &[
+    ("↑↓", "Select Range"),
+    ("Enter", "Search all profiles"),
+    ("1/2 or c", "Switch Columns"),
+    ("Esc", "Back"),
+    ("q", "Quit"),
+];
+
+pub const DATE_COMPARISON: &[Shortcut] = &[
+    ("↑↓", "Select Range"),
+    ("Enter", "Compare"),
+    ("1/2 or c", "Switch Columns"),
+    ("Esc", "Back to logs"),
+    ("q", "Quit"),
+];
+
+pub const DATE_CUSTOM_FROM: &[Shortcut] = &[
+    ("0-9", "Type Value"),
+    ("←→", "Select Field"),
+    ("Tab", "Switch to To"),
+    ("t", "Type date"),
+    ("Enter", "Confirm"),
+];
+
+pub const DATE_CUSTOM_TO: &[Shortcut] = &[
+    ("0-9", "Type Value"),
+    ("←→", "Select Field"),
+    ("Tab", "Switch to From"),
+    ("t", "Type date"),
+    ("Enter", "Confirm"),
+];
+
+pub const DATE_TYPED_INPUT: &[Shortcut] = &[
+    ("Type", "Date and time"),
+    ("Enter", "Apply"),
+    ("Backspace", "Delete"),
+    ("Esc", "Cancel"),
+];
+
+pub const STREAM_SELECTION: &[Shortcut] = &[
+    ("↑↓", "Navigate"),
+    ("Enter", "View stream in selected range"),
+    ("Type", "Filter"),
+    ("Esc", "Back"),
+    ("q", "Quit"),
+];
+
+/// Log viewer with the filter focused, where q is typed rather than quitting.
+pub const LOG_FILTER: &[Shortcut] = &[
+    ("Type", "Filter"),
+    ("↑↓", "Navigate"),
+    ("Enter", "Expand"),
+    ("Tab", "Next panel"),
+    ("Esc", "Back"),
+];
+
+pub const LOG_LIST: &[Shortcut] = &[
+    ("↑↓ or j/k", "Navigate"),
+    ("Enter", "Expand"),
+    ("/", "Filter"),
+    ("Esc", "Back"),
+    ("q", "Quit"),
+];
+
+pub const LOG_READING: &[Shortcut] = &[
+    ("↑↓ or j/k", "Scroll"),
+    ("[ ]", "Previous/next event"),
+    ("PgUp/PgDn", "Page"),
+    ("Enter", "Expand event"),
+    ("Esc", "Back to list"),
+];
+
+pub const LOG_DETAIL: &[Shortcut] = &[
+    ("↑↓ or j/k", "Scroll"),
+    ("Enter", "Collapse"),
+    ("[ ]", "Previous/next field"),
+    ("Esc", "Back"),
+    ("q", "Quit"),
+];
+
+/// Joins as many shortcuts as fit on one line of a bordered footer `width` columns wide.
+/// Whole entries are dropped from the end rather than cutting one off mid-word.
+pub fn footer(shortcuts: &[Shortcut], width: u16) -> String {
+    let available = width.saturating_sub(2) as usize;
+    let mut line = String::new();
+    for (key, action) in shortcuts {
+        let entry = format!("{}: {}", key, action);
+        let separator = if line.is_empty() { "" } else { " | " };
+        if line.chars().count() + separator.len() + entry.chars().count() > available {
+            break;
+        }
+        line.push_str(separator);
+        line.push_str(&entry);
+    }
+    line
+}
@@ -0,0 +1,86 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::app_state::stream_selection::StreamSelection;
+use crate::ui::shortcuts::{self, footer};
+use crate::utils::time_utils::millis_to_local;
+use crate::utils::ui_utils::{error_paragraph, error_summary};
+
+pub fn draw_stream_selection(f: &mut Frame, state: &mut StreamSelection) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Filter
+            Constraint::Min(0),    // Main content
+            Constraint::Length(3), // Controls
+        ])
+        .split(f.size());
+
+    // Title
+    let title = Paragraph::new(format!(
+        "Log Streams | Profile: {} | Function: {}",
+        state.profile.name, state.function_name
+    ))
+    .style(Style::default().fg(Color::Cyan))
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(title, chunks[0]);
+
+    // Filter input
+    let filter_input = Paragraph::new(state.filter_input.as_str())
+        .block(Block::default().title("Filter").borders(Borders::ALL));
+    f.render_widget(filter_input, chunks[1]);
+
+    let list_title = if let Some(error) = &state.load_error {
+        format!("Streams | Error: {}", error_summary(error))
+    } else {
+        format!(
+            "Streams ({}/{}) - most recent first",
+            state.filtered_streams.len(),
+            state.streams.len()
+        )
+    };
+    let list_block = Block::default().title(list_title).borders(Borders::ALL);
+
+    if state.is_loading {
+        let loading_text = Paragraph::new("Loading log streams...")
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center)
+            .block(list_block);
+        f.render_widget(loading_text, chunks[2]);
+    } else if let (Some(error), true) = (&state.load_error, state.streams.is_empty()) {
+        f.render_widget(error_paragraph(error, list_block), chunks[2]);
+    } else {
+        let streams: Vec<ListItem> = state
+            .filtered_streams
+            .iter()
+            .map(|stream| {
+                let last_event = stream.last_event_timestamp.map_or_else(
+                    || "no events".to_string(),
+                    |millis| {
+                        millis_to_local(millis)
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string()
+                    },
+                );
+                ListItem::new(format!("{}  {}", last_event, stream.name))
+            })
+            .collect();
+
+        let streams_list = List::new(streams)
+            .block(list_block)
+            .highlight_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
+        f.render_stateful_widget(streams_list, chunks[2], &mut state.list_state);
+    }
+
+    // Controls
+    let controls = Paragraph::new(footer(shortcuts::STREAM_SELECTION, chunks[3].width))
+        .style(Style::default().fg(Color::Green))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(controls, chunks[3]);
+}
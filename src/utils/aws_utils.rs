@@ -0,0 +1,379 @@
+use anyhow::{anyhow, Context, Result};
+use aws_config::ecs::EcsCredentialsProvider;
+use aws_config::environment::EnvironmentVariableCredentialsProvider;
+use aws_config::imds::credentials::ImdsCredentialsProvider;
+use aws_config::{BehaviorVersion, Region, SdkConfig};
+use aws_credential_types::provider::error::CredentialsError;
+use aws_credential_types::provider::{future, ProvideCredentials};
+use aws_sdk_sts::config::Credentials;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+/// Session credentials are refreshed this long before they expire.
+const SESSION_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// Role a profile assumes with an MFA code, from `role_arn` and `mfa_serial` in ~/.aws/config.
+#[derive(Debug, Clone)]
+pub struct MfaRequirement {
+    pub role_arn: String,
+    pub mfa_serial: String,
+    /// Where the credentials that sign the `AssumeRole` call come from.
+    pub source: RoleSource,
+    pub duration_seconds: Option<i32>,
+    pub role_session_name: Option<String>,
+}
+
+/// A role profile's `source_profile`, or the `credential_source` it names instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoleSource {
+    Profile(String),
+    Environment,
+    Ec2InstanceMetadata,
+    EcsContainer,
+}
+
+/// Credentials obtained with an MFA code, per profile, kept until they expire.
+static MFA_SESSIONS: OnceLock<Mutex<HashMap<String, Credentials>>> = OnceLock::new();
+
+fn mfa_sessions() -> &'static Mutex<HashMap<String, Credentials>> {
+    MFA_SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Hands out a profile's MFA session while it lasts. Once it runs out, calls fail until a
+/// new code is entered, instead of falling back to whatever other credentials are around.
+#[derive(Debug)]
+struct MfaSessionProvider {
+    profile_name: String,
+}
+
+impl ProvideCredentials for MfaSessionProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::ready(mfa_session(&self.profile_name).ok_or_else(|| {
+            CredentialsError::not_loaded(format!(
+                "the MFA session of profile {} has expired; enter a new code",
+                self.profile_name
+            ))
+        }))
+    }
+}
+
+/// Loads the shared SDK configuration for a profile and region. Profiles unlocked with an
+/// MFA code keep using that session, and ask for a new code once it expires.
+pub async fn load_aws_config(profile_name: &str, region: &str) -> SdkConfig {
+    let loader =
+        aws_config::defaults(BehaviorVersion::latest()).region(Region::new(region.to_string()));
+    if has_mfa_session(profile_name) {
+        let provider = MfaSessionProvider {
+            profile_name: profile_name.to_string(),
+        };
+        loader.credentials_provider(provider).load().await
+    } else {
+        loader.profile_name(profile_name).load().await
+    }
+}
+
+/// Unexpired session credentials for a profile that was unlocked with an MFA code.
+pub fn mfa_session(profile_name: &str) -> Option<Credentials> {
+    let sessions = mfa_sessions().lock().ok()?;
+    sessions.get(profile_name).cloned().filter(|credentials| {
+        credentials
+            .expiry()
+            .is_none_or(|expiry| expiry > SystemTime::now() + SESSION_EXPIRY_MARGIN)
+    })
+}
+
+/// Whether a profile was unlocked with an MFA code earlier, expired or not.
+fn has_mfa_session(profile_name: &str) -> bool {
+    mfa_sessions()
+        .lock()
+        .is_ok_and(|sessions| sessions.contains_key(profile_name))
+}
+
+/// Whether a profile's MFA session has run out, so a new code is needed to carry on.
+pub fn mfa_session_expired(profile_name: &str) -> bool {
+    has_mfa_session(profile_name) && mfa_session(profile_name).is_none()
+}
+
+/// Looks up which of the given profiles assume a role that needs an MFA code.
+/// The SDK can't prompt for the code itself, so those calls would otherwise fail.
+/// Role profiles whose source credentials can't be worked out are reported instead.
+pub async fn find_mfa_requirements(
+    profile_names: &[String],
+) -> (HashMap<String, MfaRequirement>, Vec<String>) {
+    let profiles = read_config_profiles().await;
+    let mut requirements = HashMap::new();
+    let mut problems = Vec::new();
+
+    for name in profile_names {
+        let Some(profile) = profiles.get(name) else {
+            continue;
+        };
+        let (Some(role_arn), Some(mfa_serial)) =
+            (profile.get("role_arn"), profile.get("mfa_serial"))
+        else {
+            continue;
+        };
+        match role_source(profile) {
+            Ok(source) => {
+                let requirement = MfaRequirement {
+                    role_arn: role_arn.clone(),
+                    mfa_serial: mfa_serial.clone(),
+                    source,
+                    duration_seconds: profile.get("duration_seconds").and_then(|d| d.parse().ok()),
+                    role_session_name: profile.get("role_session_name").cloned(),
+                };
+                requirements.insert(name.clone(), requirement);
+            }
+            Err(problem) => problems.push(format!("Profile {}: {}", name, problem)),
+        }
+    }
+    (requirements, problems)
+}
+
+/// Where a role profile's `AssumeRole` credentials come from. The AWS CLI would quietly
+/// use the `default` profile when neither key is set, which is rarely what was meant.
+fn role_source(profile: &HashMap<String, String>) -> Result<RoleSource, String> {
+    match (profile.get("source_profile"), profile.get("credential_source")) {
+        (Some(_), Some(_)) => Err("set source_profile or credential_source, not both".to_string()),
+        (Some(source_profile), None) => Ok(RoleSource::Profile(source_profile.clone())),
+        (None, Some(source)) => match source.as_str() {
+            "Environment" => Ok(RoleSource::Environment),
+            "Ec2InstanceMetadata" => Ok(RoleSource::Ec2InstanceMetadata),
+            "EcsContainer" => Ok(RoleSource::EcsContainer),
+            other => Err(format!(
+                "credential_source {} is not one of Environment, Ec2InstanceMetadata or EcsContainer",
+                other
+            )),
+        },
+        (None, None) => Err(
+            "role_arn with mfa_serial needs a source_profile or credential_source to assume it with"
+                .to_string(),
+        ),
+    }
+}
+
+/// The shared AWS config file (`AWS_CONFIG_FILE`, or ~/.aws/config), as each profile's
+/// top-level keys.
+async fn read_config_profiles() -> HashMap<String, HashMap<String, String>> {
+    let path = std::env::var_os("AWS_CONFIG_FILE")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".aws").join("config")));
+    let Some(path) = path else {
+        return HashMap::new();
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => parse_config_profiles(&contents),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// Reads `[profile name]` and `[default]` sections of an AWS config file. Other sections,
+/// such as `[sso-session ...]`, and indented sub-properties are skipped.
+fn parse_config_profiles(contents: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut profiles: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current = None;
+    for line in contents.lines() {
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some((header, _)) = line.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+            let header = header.trim();
+            current = match header.strip_prefix("profile ") {
+                Some(name) => Some(name.trim().to_string()),
+                None if header == "default" => Some(header.to_string()),
+                None => None,
+            };
+            continue;
+        }
+        if let (Some(name), Some((key, value))) = (&current, line.split_once('=')) {
+            profiles
+                .entry(name.clone())
+                .or_default()
+                .insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    profiles
+}
+
+/// SDK configuration signing a role profile's `AssumeRole` call.
+async fn source_config(source: &RoleSource, region: &str) -> SdkConfig {
+    let loader =
+        aws_config::defaults(BehaviorVersion::latest()).region(Region::new(region.to_string()));
+    match source {
+        RoleSource::Profile(name) => load_aws_config(name, region).await,
+        RoleSource::Environment => {
+            let provider = EnvironmentVariableCredentialsProvider::new();
+            loader.credentials_provider(provider).load().await
+        }
+        RoleSource::Ec2InstanceMetadata => {
+            let provider = ImdsCredentialsProvider::builder().build();
+            loader.credentials_provider(provider).load().await
+        }
+        RoleSource::EcsContainer => {
+            let provider = EcsCredentialsProvider::builder().build();
+            loader.credentials_provider(provider).load().await
+        }
+    }
+}
+
+/// Assumes the profile's role with an MFA code and caches the session for `load_aws_config`.
+pub async fn assume_role_with_mfa(
+    profile_name: &str,
+    region: &str,
+    requirement: &MfaRequirement,
+    token_code: &str,
+) -> Result<()> {
+    let source_config = source_config(&requirement.source, region).await;
+    let response = aws_sdk_sts::Client::new(&source_config)
+        .assume_role()
+        .role_arn(&requirement.role_arn)
+        .role_session_name(
+            requirement
+                .role_session_name
+                .clone()
+                .unwrap_or_else(|| format!("aws-logs-viewer-{}", profile_name)),
+        )
+        .serial_number(&requirement.mfa_serial)
+        .token_code(token_code)
+        .set_duration_seconds(requirement.duration_seconds)
+        .send()
+        .await
+        .with_context(|| format!("AssumeRole {} failed", requirement.role_arn))?;
+
+    let session = response
+        .credentials()
+        .ok_or_else(|| anyhow!("AssumeRole returned no credentials"))?;
+    let credentials = Credentials::new(
+        session.access_key_id(),
+        session.secret_access_key(),
+        Some(session.session_token().to_string()),
+        SystemTime::try_from(*session.expiration()).ok(),
+        "mfa-assume-role",
+    );
+
+    mfa_sessions()
+        .lock()
+        .map_err(|_| anyhow!("MFA session cache is poisoned"))?
+        .insert(profile_name.to_string(), credentials);
+    Ok(())
+}
+
+/// Log group Lambda writes a function's logs to.
+pub fn lambda_log_group(function_name: &str) -> String {
+    format!("/aws/lambda/{}", function_name)
+}
+
+/// AWS console link to a log group's events (one stream's, when given) between two
+/// CloudWatch timestamps. The console reads the part after `#` with `%` written as `$25`.
+pub fn console_logs_url(
+    region: &str,
+    log_group: &str,
+    log_stream: Option<&str>,
+    from_millis: i64,
+    to_millis: i64,
+) -> String {
+    let encode = |text: &str| {
+        text.bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (byte as char).to_string()
+                }
+                _ => format!("$25{:02X}", byte),
+            })
+            .collect::<String>()
+    };
+    let stream = log_stream
+        .map(|stream| format!("/{}", encode(stream)))
+        .unwrap_or_default();
+
+    format!(
+        "https://{region}.console.aws.amazon.com/cloudwatch/home?region={region}\
+         #logsV2:log-groups/log-group/{}/log-events{}$3Fstart$3D{}$26end$3D{}",
+        encode(log_group),
+        stream,
+        from_millis,
+        to_millis,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_profiles_keep_top_level_keys() {
+        let profiles = parse_config_profiles(
+            "[default]\nregion = us-east-1\n\n\
+             # a comment\n[profile admin]\nrole_arn = arn:aws:iam::1:role/admin\n\
+             mfa_serial = arn:aws:iam::1:mfa/me\ns3 =\n  max_concurrent_requests = 20\n\
+             [sso-session corp]\nsso_region = eu-west-1\n",
+        );
+
+        assert_eq!(profiles["default"]["region"], "us-east-1");
+        assert_eq!(profiles["admin"]["role_arn"], "arn:aws:iam::1:role/admin");
+        assert!(!profiles["admin"].contains_key("max_concurrent_requests"));
+        assert_eq!(profiles.len(), 2);
+    }
+
+    #[test]
+    fn role_source_needs_exactly_one_usable_source() {
+        let profile = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        assert_eq!(
+            role_source(&profile(&[("source_profile", "base")])),
+            Ok(RoleSource::Profile("base".to_string()))
+        );
+        assert_eq!(
+            role_source(&profile(&[("credential_source", "EcsContainer")])),
+            Ok(RoleSource::EcsContainer)
+        );
+        assert!(role_source(&profile(&[])).is_err());
+        assert!(role_source(&profile(&[("credential_source", "Shell")])).is_err());
+        assert!(role_source(&profile(&[
+            ("source_profile", "base"),
+            ("credential_source", "Environment")
+        ]))
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn failing_credential_process_reports_its_stderr() {
+        let dir = std::env::temp_dir().join(format!("aws-tui-creds-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("config");
+        std::fs::write(
+            &config,
+            "[profile broken]\ncredential_process = sh -c 'echo boom >&2; exit 1'\n",
+        )
+        .unwrap();
+        // No other test reads the AWS environment
+        std::env::set_var("AWS_CONFIG_FILE", &config);
+        std::env::set_var("AWS_SHARED_CREDENTIALS_FILE", dir.join("credentials"));
+
+        let aws_config = load_aws_config("broken", "us-east-1").await;
+        let error = aws_sdk_sts::Client::new(&aws_config)
+            .get_caller_identity()
+            .send()
+            .await
+            .map_err(anyhow::Error::from)
+            .unwrap_err();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let message = format!("{:#}", error);
+        assert!(message.contains("boom"), "{}", message);
+    }
+}
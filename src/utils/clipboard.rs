@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Clipboard helpers tried in order before falling back to the OSC 52 escape sequence.
+const CLIPBOARD_COMMANDS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip.exe", &[]),
+];
+
+/// Copies `text` to the system clipboard.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    for (program, args) in CLIPBOARD_COMMANDS {
+        if pipe_to_command(program, args, text).is_ok() {
+            return Ok(());
+        }
+    }
+
+    // Works over SSH and in most modern terminals without any helper installed
+    copy_with_osc52(text)
+}
+
+fn pipe_to_command(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Could not open stdin for {}", program))?
+        .write_all(text.as_bytes())?;
+
+    if child.wait()?.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("{} exited with an error", program))
+    }
+}
+
+fn copy_with_osc52(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()?;
+    Ok(())
+}
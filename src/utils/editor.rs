@@ -0,0 +1,56 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use std::fs::{self, File};
+use std::io::IsTerminal;
+use std::process::{Command, Stdio};
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Writes `contents` to a temporary file, opens it in the user's editor and waits for the
+/// editor to exit. The file is removed afterwards, whatever the editor did.
+pub fn open_in_editor(contents: &str, extension: &str) -> Result<()> {
+    edit_in_editor(contents, extension).map(|_| ())
+}
+
+/// Like `open_in_editor`, but returns the file as the editor saved it.
+pub fn edit_in_editor(contents: &str, extension: &str) -> Result<String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    let path = std::env::temp_dir().join(format!(
+        "aws-logs-viewer-{}-{}.{}",
+        std::process::id(),
+        Utc::now().timestamp_millis(),
+        extension
+    ));
+    // Events were piped in, so stdin is the spent pipe; the editor reads the terminal
+    let tty = if std::io::stdin().is_terminal() {
+        None
+    } else {
+        Some(File::open("/dev/tty").context("Could not open the terminal for the editor")?)
+    };
+    fs::write(&path, contents)?;
+
+    // The variable may carry arguments, e.g. "code --wait"
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_EDITOR);
+    let mut command = Command::new(program);
+    command.args(words).arg(&path);
+    if let Some(tty) = tty {
+        command.stdin(Stdio::from(tty));
+    }
+    let status = command
+        .status()
+        .with_context(|| format!("Could not start {}", program));
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    if status?.success() {
+        Ok(edited?)
+    } else {
+        Err(anyhow!("{} exited with an error", program))
+    }
+}
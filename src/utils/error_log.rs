@@ -0,0 +1,64 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::Level;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+/// Overrides `error_log` from config.toml; set it to an empty string to turn logging off.
+pub const ERROR_LOG_ENV: &str = "AWS_TUI_ERROR_LOG";
+
+/// Days of logs kept; each day's go to `<path>.<date>` and older files are removed.
+const MAX_LOG_FILES: usize = 7;
+
+/// The file errors are appended to: the environment variable wins over the config setting.
+pub fn resolve_path(configured: Option<&Path>) -> Option<PathBuf> {
+    match std::env::var_os(ERROR_LOG_ENV) {
+        Some(path) if path.is_empty() => None,
+        Some(path) => Some(PathBuf::from(path)),
+        None => configured.map(Path::to_path_buf),
+    }
+}
+
+/// Sends `tracing` errors, the app's and the AWS SDK's alike, to a daily file named after
+/// `path`. Until this succeeds, `log_error` does nothing.
+pub fn init(path: &Path) -> Result<()> {
+    let directory = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("{} is not a file path", path.display()))?;
+    fs::create_dir_all(directory)?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(file_name.to_string_lossy())
+        .max_log_files(MAX_LOG_FILES)
+        .build(directory)
+        .with_context(|| format!("Cannot open {}", path.display()))?;
+
+    // Written as they happen rather than from a background thread, so nothing is lost
+    // when the app exits or panics
+    tracing_subscriber::fmt()
+        .with_writer(appender)
+        .with_ansi(false)
+        .with_target(false)
+        .with_max_level(Level::ERROR)
+        .try_init()
+        .map_err(|e| anyhow!(e))?;
+
+    // Panics would otherwise vanish with the alternate screen
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log_error("panic", &info.to_string());
+        default_hook(info);
+    }));
+    Ok(())
+}
+
+/// Logs `error` as a `tracing` error, tagged with where it happened.
+/// Writing never interrupts the UI: failures to log are ignored.
+pub fn log_error(context: &str, error: &str) {
+    // Multi-line errors (AWS error chains, helper stderr) stay grouped under one entry
+    tracing::error!("[{}] {}", context, error.replace('\n', "\n    "));
+}
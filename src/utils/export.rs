@@ -0,0 +1,126 @@
+use anyhow::Result;
+use aws_sdk_cloudwatchlogs::types::FilteredLogEvent;
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::utils::time_utils::millis_to_local;
+
+/// File format for exported tables.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    /// Tables ready to paste into GitHub issues and pull requests.
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// Export file name used when the config doesn't set one.
+pub const DEFAULT_EXPORT_FILENAME: &str = "{function}_{kind}_{from}_to_{to}";
+
+/// Fills the placeholders of an export file name template:
+/// `{function}`, `{kind}` (what is exported), `{from}` and `{to}` (the range, with `{to}`
+/// dropping the date when it falls on the same day), and `{now}` (when the export is made).
+pub fn export_stem(
+    template: &str,
+    function_name: &str,
+    kind: &str,
+    from: DateTime<Local>,
+    to: DateTime<Local>,
+) -> String {
+    let to_format = if to.date_naive() == from.date_naive() {
+        "%H-%M"
+    } else {
+        "%Y-%m-%d_%H-%M"
+    };
+    let stem = template
+        .replace("{function}", function_name)
+        .replace("{kind}", kind)
+        .replace("{from}", &from.format("%Y-%m-%d_%H-%M").to_string())
+        .replace("{to}", &to.format(to_format).to_string())
+        .replace("{now}", &Local::now().format("%Y%m%d-%H%M%S").to_string());
+
+    // A template is a file name, not a path
+    stem.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' => '-',
+            c => c,
+        })
+        .collect()
+}
+
+/// Writes `contents` to `<stem>.<extension>` in the working directory and returns the path.
+/// An existing file is never replaced; `-2`, `-3`, ... is added to the stem instead.
+pub fn write_export(stem: &str, format: ExportFormat, contents: &str) -> Result<PathBuf> {
+    let dir = std::env::current_dir()?;
+    let mut path = dir.join(format!("{}.{}", stem, format.extension()));
+    let mut copy = 1;
+    while path.exists() {
+        copy += 1;
+        path = dir.join(format!("{}-{}.{}", stem, copy, format.extension()));
+    }
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// Serializes an event with its CloudWatch metadata. Fields the API didn't return are omitted.
+pub fn event_to_json(event: &FilteredLogEvent) -> Value {
+    let mut object = Map::new();
+    if let Some(timestamp) = event.timestamp {
+        object.insert("timestamp".to_string(), json!(timestamp));
+    }
+    if let Some(ingestion_time) = event.ingestion_time {
+        object.insert("ingestion_time".to_string(), json!(ingestion_time));
+    }
+    if let Some(message) = &event.message {
+        object.insert("message".to_string(), json!(message));
+    }
+    if let Some(log_stream_name) = &event.log_stream_name {
+        object.insert("log_stream_name".to_string(), json!(log_stream_name));
+    }
+    Value::Object(object)
+}
+
+/// One line per event: local timestamp with milliseconds, then the message as logged.
+pub fn event_to_text(event: &FilteredLogEvent) -> String {
+    format!(
+        "{} {}",
+        millis_to_local(event.timestamp.unwrap_or(0)).format("%Y-%m-%d %H:%M:%S%.3f"),
+        event.message.as_deref().unwrap_or("").trim_end()
+    )
+}
+
+/// The events as one Markdown code block under a `heading` line, one `event_to_text` line each
+/// (continuation lines of multi-line messages follow as logged).
+pub fn events_to_markdown(heading: &str, events: &[FilteredLogEvent]) -> String {
+    let lines: Vec<String> = events.iter().map(event_to_text).collect();
+    // The fence must be longer than any run of backticks the messages contain
+    let longest_run = lines
+        .iter()
+        .flat_map(|line| line.split(|c| c != '`'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat((longest_run + 1).max(3));
+    format!(
+        "{}\n\n{}text\n{}\n{}\n",
+        heading,
+        fence,
+        lines.join("\n"),
+        fence
+    )
+}
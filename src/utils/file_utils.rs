@@ -1,7 +1,39 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::function_selection::{FunctionSort, FunctionSummary};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+/// Choices remembered between sessions, stored next to the function caches.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SavedState {
+    /// Region picked for each profile that has none configured.
+    #[serde(default)]
+    pub regions: HashMap<String, String>,
+    /// Last log filter typed for each function, restored when its logs are opened again.
+    #[serde(default)]
+    pub filters: HashMap<String, String>,
+    /// When each function's logs were last closed, in milliseconds since the epoch.
+    #[serde(default)]
+    pub last_visits: HashMap<String, i64>,
+    /// Order the function list was last sorted in.
+    #[serde(default)]
+    pub function_sort: FunctionSort,
+    /// Free-text notes on each function, written with Ctrl+N in the function list.
+    #[serde(default)]
+    pub notes: HashMap<String, FunctionNote>,
+}
+
+/// Findings jotted down about a function during an investigation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionNote {
+    pub text: String,
+    /// When the note was last saved, in milliseconds since the epoch.
+    pub edited_at: i64,
+}
+
 pub fn get_cache_dir() -> Result<PathBuf> {
     let cache_dir = dirs::cache_dir()
         .ok_or_else(|| anyhow::anyhow!("Could not find cache directory"))?
@@ -19,14 +51,23 @@ pub fn get_functions_cache_path(profile_name: &str, region: &str) -> Result<Path
     Ok(cache_dir.join(format!("functions_{}_{}.cache", profile_name, region)))
 }
 
-pub fn cache_functions(profile_name: &str, region: &str, functions: &[String]) -> Result<()> {
+pub fn cache_functions(
+    profile_name: &str,
+    region: &str,
+    functions: &[FunctionSummary],
+) -> Result<()> {
     let cache_path = get_functions_cache_path(profile_name, region)?;
     let cache_content = serde_json::to_string(functions)?;
     fs::write(cache_path, cache_content)?;
     Ok(())
 }
 
-pub fn load_cached_functions(profile_name: &str, region: &str) -> Result<Option<Vec<String>>> {
+/// Reads the cached function list. Caches from before runtimes were stored fail to parse,
+/// which callers treat like having no cache yet.
+pub fn load_cached_functions(
+    profile_name: &str,
+    region: &str,
+) -> Result<Option<Vec<FunctionSummary>>> {
     let cache_path = get_functions_cache_path(profile_name, region)?;
 
     if !cache_path.exists() {
@@ -34,6 +75,30 @@ pub fn load_cached_functions(profile_name: &str, region: &str) -> Result<Option<
     }
 
     let cache_content = fs::read_to_string(cache_path)?;
-    let functions: Vec<String> = serde_json::from_str(&cache_content)?;
+    let functions: Vec<FunctionSummary> = serde_json::from_str(&cache_content)?;
     Ok(Some(functions))
 }
+
+pub fn get_state_path() -> Result<PathBuf> {
+    Ok(get_cache_dir()?.join("state.json"))
+}
+
+/// Reads the saved state, starting fresh when there is none yet.
+pub fn load_state() -> Result<SavedState> {
+    let state_path = get_state_path()?;
+
+    if !state_path.exists() {
+        return Ok(SavedState::default());
+    }
+
+    let state_content = fs::read_to_string(state_path)?;
+    Ok(serde_json::from_str(&state_content)?)
+}
+
+/// Applies `update` to the saved state and writes it back.
+pub fn update_state(update: impl FnOnce(&mut SavedState)) -> Result<()> {
+    let mut state = load_state()?;
+    update(&mut state);
+    fs::write(get_state_path()?, serde_json::to_string_pretty(&state)?)?;
+    Ok(())
+}
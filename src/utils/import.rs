@@ -0,0 +1,55 @@
+use anyhow::{anyhow, bail, Context, Result};
+use aws_sdk_cloudwatchlogs::types::FilteredLogEvent;
+use serde_json::Value;
+
+/// Parses events exported elsewhere: the document `aws logs filter-log-events` prints, or
+/// one JSON event per line. Both the CLI's camelCase field names and the snake_case ones
+/// this app exports are read.
+pub fn parse_events(text: &str) -> Result<Vec<FilteredLogEvent>> {
+    if let Ok(Value::Object(document)) = serde_json::from_str::<Value>(text) {
+        if let Some(Value::Array(events)) = document.get("events") {
+            return events
+                .iter()
+                .enumerate()
+                .map(|(index, event)| {
+                    event_from_json(event).with_context(|| format!("event {}", index + 1))
+                })
+                .collect();
+        }
+    }
+
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str::<Value>(line)
+                .map_err(|e| anyhow!(e))
+                .and_then(|event| event_from_json(&event))
+                .with_context(|| format!("line {}", index + 1))
+        })
+        .collect()
+}
+
+fn event_from_json(event: &Value) -> Result<FilteredLogEvent> {
+    let Value::Object(object) = event else {
+        bail!("expected an object with at least a message");
+    };
+    let field = |camel: &str, snake: &str| object.get(camel).or_else(|| object.get(snake));
+    let text = |camel, snake| {
+        field(camel, snake)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let millis = |camel, snake| field(camel, snake).and_then(Value::as_i64);
+
+    let Some(message) = text("message", "message") else {
+        bail!("expected an object with at least a message");
+    };
+    Ok(FilteredLogEvent::builder()
+        .message(message)
+        .set_timestamp(millis("timestamp", "timestamp"))
+        .set_ingestion_time(millis("ingestionTime", "ingestion_time"))
+        .set_log_stream_name(text("logStreamName", "log_stream_name"))
+        .set_event_id(text("eventId", "event_id"))
+        .build())
+}
@@ -0,0 +1,155 @@
+use aws_sdk_cloudwatchlogs::types::FilteredLogEvent;
+use serde::Serialize;
+
+/// Figures from the `REPORT RequestId: ...` line Lambda writes after each invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvocationReport {
+    pub request_id: String,
+    pub duration_ms: f64,
+    pub billed_duration_ms: f64,
+    pub memory_size_mb: f64,
+    pub max_memory_used_mb: f64,
+    /// Only present on cold starts.
+    pub init_duration_ms: Option<f64>,
+}
+
+impl InvocationReport {
+    /// Parses a REPORT line; any other message gives `None`.
+    pub fn parse(message: &str) -> Option<Self> {
+        let body = message.trim_start().strip_prefix("REPORT ")?;
+        let mut fields = body
+            .split('\t')
+            .filter_map(|field| field.split_once(": "))
+            .map(|(name, value)| (name.trim(), value.trim()));
+
+        let mut report = InvocationReport {
+            request_id: String::new(),
+            duration_ms: 0.0,
+            billed_duration_ms: 0.0,
+            memory_size_mb: 0.0,
+            max_memory_used_mb: 0.0,
+            init_duration_ms: None,
+        };
+        let number = |value: &str| value.split_whitespace().next()?.parse::<f64>().ok();
+
+        fields.try_for_each(|(name, value)| {
+            match name {
+                "RequestId" => report.request_id = value.to_string(),
+                "Duration" => report.duration_ms = number(value)?,
+                "Billed Duration" => report.billed_duration_ms = number(value)?,
+                "Memory Size" => report.memory_size_mb = number(value)?,
+                "Max Memory Used" => report.max_memory_used_mb = number(value)?,
+                "Init Duration" => report.init_duration_ms = Some(number(value)?),
+                _ => {}
+            }
+            Some(())
+        })?;
+
+        (!report.request_id.is_empty()).then_some(report)
+    }
+}
+
+/// Distribution of one REPORT figure across invocations.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricSummary {
+    pub metric: &'static str,
+    pub count: usize,
+    pub min: f64,
+    pub avg: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl MetricSummary {
+    fn from_values(metric: &'static str, mut values: Vec<f64>) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let percentile = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+
+        Some(MetricSummary {
+            metric,
+            count: values.len(),
+            min: values[0],
+            avg: values.iter().sum::<f64>() / values.len() as f64,
+            p50: percentile(0.5),
+            p95: percentile(0.95),
+            max: values[values.len() - 1],
+        })
+    }
+}
+
+/// Performance summary of the invocations whose REPORT lines are among the events.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportSummary {
+    pub invocations: usize,
+    pub cold_starts: usize,
+    pub metrics: Vec<MetricSummary>,
+}
+
+impl ReportSummary {
+    pub fn from_events(events: &[FilteredLogEvent]) -> Self {
+        let reports: Vec<InvocationReport> = events
+            .iter()
+            .filter_map(|event| InvocationReport::parse(event.message.as_deref()?))
+            .collect();
+        let collect = |value: fn(&InvocationReport) -> Option<f64>| {
+            reports.iter().filter_map(value).collect::<Vec<_>>()
+        };
+
+        let metrics = [
+            MetricSummary::from_values("duration_ms", collect(|r| Some(r.duration_ms))),
+            MetricSummary::from_values(
+                "billed_duration_ms",
+                collect(|r| Some(r.billed_duration_ms)),
+            ),
+            MetricSummary::from_values(
+                "max_memory_used_mb",
+                collect(|r| Some(r.max_memory_used_mb)),
+            ),
+            MetricSummary::from_values("init_duration_ms", collect(|r| r.init_duration_ms)),
+        ];
+
+        ReportSummary {
+            invocations: reports.len(),
+            cold_starts: reports
+                .iter()
+                .filter(|r| r.init_duration_ms.is_some())
+                .count(),
+            metrics: metrics.into_iter().flatten().collect(),
+        }
+    }
+
+    /// One row per metric, with a header, for spreadsheets.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("metric,count,min,avg,p50,p95,max\n");
+        for m in &self.metrics {
+            csv.push_str(&format!(
+                "{},{},{:.2},{:.2},{:.2},{:.2},{:.2}\n",
+                m.metric, m.count, m.min, m.avg, m.p50, m.p95, m.max
+            ));
+        }
+        csv.push_str(&format!("invocations,{},,,,,\n", self.invocations));
+        csv.push_str(&format!("cold_starts,{},,,,,\n", self.cold_starts));
+        csv
+    }
+
+    /// The same table in Markdown, with the invocation counts above it.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "{} invocations, {} cold starts\n\n",
+            self.invocations, self.cold_starts
+        );
+        markdown.push_str("| metric | count | min | avg | p50 | p95 | max |\n");
+        markdown.push_str("|---|---:|---:|---:|---:|---:|---:|\n");
+        for m in &self.metrics {
+            markdown.push_str(&format!(
+                "| {} | {} | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} |\n",
+                m.metric, m.count, m.min, m.avg, m.p50, m.p95, m.max
+            ));
+        }
+        markdown
+    }
+}
@@ -0,0 +1,560 @@
+use regex_lite::Regex;
+
+/// Guesses whether a log line came from stderr. CloudWatch doesn't record the origin stream,
+/// so this relies on the level tags runtimes add and on the shape of crash output.
+pub fn looks_like_stderr(message: &str) -> bool {
+    let trimmed = message.trim_start();
+
+    // Node.js `console.error`/`console.warn` and the Lambda runtime log format
+    message.contains("\tERROR\t")
+        || message.contains("\tWARN\t")
+        // Python's default Lambda logger
+        || trimmed.starts_with("[ERROR]")
+        || trimmed.starts_with("[WARNING]")
+        // Tracebacks and stack frames
+        || trimmed.starts_with("Traceback (most recent call last):")
+        || message.lines().any(is_stack_frame)
+        || trimmed.starts_with("Exception in thread")
+        || trimmed.starts_with("panic:")
+        || trimmed.starts_with("goroutine ")
+        // Errors reported by the runtime itself
+        || trimmed.starts_with("{\"errorType\"")
+        || trimmed.starts_with("{\"errorMessage\"")
+        || trimmed.contains("Runtime.ExitError")
+        || trimmed.contains("Runtime.Unhandled")
+        || trimmed.contains("Task timed out after")
+        // Structured logs that say so themselves
+        || json_log_level(message).is_some_and(|level| level >= LogLevel::Warn)
+}
+
+/// Severity a line was logged at, least severe first so levels compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "trace" | "debug" => Some(LogLevel::Debug),
+            "info" | "notice" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" | "err" | "fatal" | "critical" | "crit" | "panic" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    /// pino and bunyan write levels as numbers: 10 trace up to 60 fatal.
+    fn from_number(number: f64) -> Self {
+        match number as i64 {
+            ..=29 => LogLevel::Debug,
+            30..=39 => LogLevel::Info,
+            40..=49 => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }
+    }
+}
+
+/// Fields structured loggers put the level in.
+const LEVEL_KEYS: [&str; 4] = ["level", "severity", "levelname", "log_level"];
+
+/// A JSON log as a value: the whole message, or the object after a prefix such as `ERROR `,
+/// `[WARN] ` or the Lambda runtime's `<time>\t<request id>\tINFO\t`. A level in that prefix
+/// is added as `level` when the object has none of its own, so filters and level checks see it.
+pub fn structured_body(message: &str) -> Option<serde_json::Value> {
+    let trimmed = message.trim();
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) {
+        return Some(value);
+    }
+
+    let (prefix, body) = trimmed.split_at(trimmed.find('{')?);
+    let mut value = serde_json::from_str::<serde_json::Value>(body).ok()?;
+    let prefix_level = prefix
+        .split(['\t', ' '])
+        .map(|token| token.trim_matches(['[', ']', ':']))
+        .rfind(|token| LogLevel::parse(token).is_some());
+    if let (serde_json::Value::Object(object), Some(level)) = (&mut value, prefix_level) {
+        if !LEVEL_KEYS.iter().any(|key| object.contains_key(*key)) {
+            object.insert("level".to_string(), level.into());
+        }
+    }
+    Some(value)
+}
+
+/// Level of a structured log, from its own level field or the prefix the JSON follows.
+pub fn json_log_level(message: &str) -> Option<LogLevel> {
+    let value = structured_body(message)?;
+    LEVEL_KEYS.iter().find_map(|key| match &value[key] {
+        serde_json::Value::String(name) => LogLevel::parse(name),
+        serde_json::Value::Number(number) => number.as_f64().map(LogLevel::from_number),
+        _ => None,
+    })
+}
+
+/// Whether a line is one frame of a stack trace: `at ...` in Java, Node.js and .NET, or
+/// `File "...", line N` in Python.
+pub fn is_stack_frame(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    let indented = trimmed.len() < line.len();
+    (indented && trimmed.starts_with("at ") && trimmed.len() > 3) || is_python_frame(line)
+}
+
+/// Python frames are followed by the source line they point at, indented further.
+pub fn is_python_frame(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("File \"") && trimmed.contains("\", line ")
+}
+
+/// Columns a tab counts for when indentation is measured.
+const TAB_WIDTH: usize = 4;
+
+/// Halves each line's leading indentation (a tab counting as four spaces) and squeezes runs
+/// of spaces and tabs inside lines to a single space, keeping nesting visible in less width.
+pub fn collapse_whitespace(message: &str) -> String {
+    message
+        .lines()
+        .map(|line| {
+            let body = line.trim_start_matches([' ', '\t']);
+            let indent: usize = line[..line.len() - body.len()]
+                .chars()
+                .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+                .sum();
+            let mut collapsed = " ".repeat(indent.div_ceil(2));
+            let mut in_run = false;
+            for c in body.chars() {
+                let blank = c == ' ' || c == '\t';
+                if !(blank && in_run) {
+                    collapsed.push(if blank { ' ' } else { c });
+                }
+                in_run = blank;
+            }
+            collapsed
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Drops a leading ISO-8601 timestamp (`2024-05-01T12:00:00.123Z` and similar) and the
+/// whitespace after it. Anything that isn't clearly such a timestamp is returned unchanged.
+pub fn strip_timestamp_prefix(message: &str) -> &str {
+    let bytes = message.as_bytes();
+    let digits = |range: std::ops::Range<usize>| {
+        bytes
+            .get(range)
+            .is_some_and(|part| part.iter().all(u8::is_ascii_digit))
+    };
+
+    // YYYY-MM-DDTHH:MM:SS
+    let has_date_time = digits(0..4)
+        && bytes.get(4) == Some(&b'-')
+        && digits(5..7)
+        && bytes.get(7) == Some(&b'-')
+        && digits(8..10)
+        && matches!(bytes.get(10), Some(b'T') | Some(b' '))
+        && digits(11..13)
+        && bytes.get(13) == Some(&b':')
+        && digits(14..16)
+        && bytes.get(16) == Some(&b':')
+        && digits(17..19);
+    if !has_date_time {
+        return message;
+    }
+
+    let mut end = 19;
+    // Fractional seconds
+    if matches!(bytes.get(end), Some(b'.') | Some(b',')) {
+        end += 1;
+        while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+            end += 1;
+        }
+    }
+    // Zone: Z, +HH:MM, -HHMM
+    match bytes.get(end) {
+        Some(b'Z') => end += 1,
+        Some(b'+') | Some(b'-') if digits(end + 1..end + 3) => {
+            end += 3;
+            if bytes.get(end) == Some(&b':') {
+                end += 1;
+            }
+            if digits(end..end + 2) {
+                end += 2;
+            }
+        }
+        _ => {}
+    }
+
+    // Only strip when the timestamp is a separate prefix, not the start of a longer token
+    match message[end..].chars().next() {
+        Some(c) if c.is_whitespace() => message[end..].trim_start(),
+        _ => message,
+    }
+}
+
+/// Most words a muting phrase is made of; enough to tell a kind of line apart.
+const MUTE_PHRASE_MAX_WORDS: usize = 6;
+
+/// The part of a message likely shared by every line of its kind, to filter them out
+/// together: the longest run of words without digits (which IDs, counts and times have) or
+/// quotes, from the first line or from the `message`/`msg` field of a JSON log. The run is
+/// an exact substring of the message, so it works as a quoted filter phrase.
+pub fn constant_phrase(message: &str) -> Option<String> {
+    let json = serde_json::from_str::<serde_json::Value>(message.trim()).ok();
+    let text = json
+        .as_ref()
+        .and_then(|value| {
+            ["message", "msg"]
+                .iter()
+                .find_map(|key| value[key].as_str())
+        })
+        .unwrap_or(message);
+    let first_line = strip_timestamp_prefix(text.lines().next()?.trim());
+
+    let is_constant = |word: &str| {
+        !word.is_empty()
+            && word.chars().any(char::is_alphabetic)
+            && !word.chars().any(|c| c.is_ascii_digit() || c == '"')
+    };
+    let mut best: Vec<&str> = Vec::new();
+    // Tab-separated runtime prefixes (request ID, level) are fields of their own
+    for field in first_line.split('\t') {
+        let mut run: Vec<&str> = Vec::new();
+        for word in field.split(' ').chain([""]) {
+            if is_constant(word) && run.len() < MUTE_PHRASE_MAX_WORDS {
+                run.push(word);
+                continue;
+            }
+            if run.join(" ").len() > best.join(" ").len() {
+                best = run.clone();
+            }
+            run.clear();
+            if is_constant(word) {
+                run.push(word);
+            }
+        }
+    }
+
+    let phrase = best.join(" ");
+    // A lone level tag or short word would hide far more than one kind of line
+    (phrase.chars().count() >= 8).then_some(phrase)
+}
+
+/// Whether a message is the line Lambda writes when an invocation ends.
+pub fn ends_invocation(message: &str) -> bool {
+    let trimmed = message.trim_start();
+    trimmed.starts_with("END RequestId: ") || trimmed.starts_with("REPORT RequestId: ")
+}
+
+/// Request ID of the invocation a line was logged in, when the line carries one: the
+/// START/END/REPORT lines, the tab-separated format of the Node.js and Python runtimes, or
+/// a `requestId` field of structured JSON logs.
+pub fn request_id(message: &str) -> Option<String> {
+    let trimmed = message.trim_start();
+    if let Some(rest) = ["START ", "END ", "REPORT "]
+        .iter()
+        .find_map(|prefix| trimmed.strip_prefix(prefix))
+    {
+        let id = rest.strip_prefix("RequestId: ")?;
+        return id.split_whitespace().next().map(str::to_string);
+    }
+
+    if let Some(id) = trimmed.split('\t').find(|field| is_uuid(field)) {
+        return Some(id.to_string());
+    }
+
+    let value: serde_json::Value = serde_json::from_str(trimmed).ok()?;
+    // Platform events nest it one level down
+    let id = [&value, &value["record"]]
+        .into_iter()
+        .find_map(|object| object["requestId"].as_str())
+        .map(str::to_string);
+    id
+}
+
+/// Correlation or trace ID of a message, shared by every line of one transaction across
+/// functions and services. `pattern`'s first group (or whole match) wins; otherwise the first
+/// of `keys` present, as a field of a JSON log at any depth or as `key=value`/`key: value`
+/// in text. An X-Ray header gives just its `Root`, which every hop keeps.
+pub fn correlation_id(message: &str, keys: &[String], pattern: Option<&Regex>) -> Option<String> {
+    if let Some(captures) = pattern.and_then(|pattern| pattern.captures(message)) {
+        let id = captures.get(1).or_else(|| captures.get(0))?.as_str();
+        return (!id.is_empty()).then(|| id.to_string());
+    }
+
+    // Runtimes write JSON logs after their tab-separated prefix
+    let json = [
+        message.trim(),
+        message.rsplit('\t').next().unwrap_or("").trim(),
+    ]
+    .iter()
+    .find_map(|text| serde_json::from_str::<serde_json::Value>(text).ok())
+    .filter(|value| value.is_object());
+    let fields = json.as_ref().map(flatten_json).unwrap_or_default();
+    let field_name = |path: &str| {
+        let last = path.rsplit('.').next().unwrap_or(path);
+        last.split('[').next().unwrap_or(last).to_string()
+    };
+
+    let id = keys.iter().find_map(|key| {
+        fields
+            .iter()
+            .find(|(path, value)| field_name(path).eq_ignore_ascii_case(key) && !value.is_empty())
+            .map(|(_, value)| value.clone())
+            .or_else(|| text_value(message, key))
+    })?;
+    match id.split(';').find_map(|part| part.strip_prefix("Root=")) {
+        Some(root) => Some(root.to_string()),
+        None => Some(id),
+    }
+}
+
+/// The value after the first `key=` or `key:` in a message, up to the next space, quote
+/// or comma. The key is matched case-insensitively, as HTTP header names are.
+fn text_value(message: &str, key: &str) -> Option<String> {
+    let lower = message.to_ascii_lowercase();
+    let key = key.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(&key) {
+        let after = from + found + key.len();
+        from = after;
+        let rest = message[after..].trim_start_matches(['"', '\'']);
+        let Some(rest) = rest.strip_prefix(['=', ':']) else {
+            continue;
+        };
+        let value: String = rest
+            .trim_start_matches([' ', '"', '\''])
+            .chars()
+            .take_while(|c| !c.is_whitespace() && !matches!(c, '"' | '\'' | ',' | '}'))
+            .collect();
+        if !value.is_empty() {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// 8-4-4-4-12 hex digits, the shape of Lambda request IDs.
+fn is_uuid(text: &str) -> bool {
+    let groups: Vec<&str> = text.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Leaf values of a JSON document as `(path, value)` rows, with object keys joined by `.`
+/// and array items as `[i]`. Strings appear without their quotes.
+pub fn flatten_json(value: &serde_json::Value) -> Vec<(String, String)> {
+    fn walk(value: &serde_json::Value, path: String, rows: &mut Vec<(String, String)>) {
+        match value {
+            serde_json::Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    let child_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    walk(child, child_path, rows);
+                }
+            }
+            serde_json::Value::Array(items) if !items.is_empty() => {
+                for (i, child) in items.iter().enumerate() {
+                    walk(child, format!("{}[{}]", path, i), rows);
+                }
+            }
+            serde_json::Value::String(text) => rows.push((path, text.clone())),
+            other => rows.push((path, other.to_string())),
+        }
+    }
+
+    let mut rows = Vec::new();
+    walk(value, String::new(), &mut rows);
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_level_is_added_to_the_object() {
+        let value = structured_body(r#"WARN {"msg":"slow"}"#).unwrap();
+        assert_eq!(value["level"], "WARN");
+        assert_eq!(value["msg"], "slow");
+        assert_eq!(
+            json_log_level(r#"[WARN] {"msg":"slow"}"#),
+            Some(LogLevel::Warn)
+        );
+    }
+
+    #[test]
+    fn lambda_prefix_level_is_read() {
+        let message = "2024-01-01T00:00:00.000Z\tabc-123\tERROR\t{\"msg\":\"boom\"}";
+        assert_eq!(structured_body(message).unwrap()["level"], "ERROR");
+        assert_eq!(json_log_level(message), Some(LogLevel::Error));
+    }
+
+    #[test]
+    fn object_level_overrides_the_prefix() {
+        let message = r#"ERROR {"level":"info","msg":"retrying"}"#;
+        assert_eq!(structured_body(message).unwrap()["level"], "info");
+        assert_eq!(json_log_level(message), Some(LogLevel::Info));
+        assert!(!looks_like_stderr(message));
+    }
+
+    #[test]
+    fn numeric_and_other_level_keys_are_read() {
+        assert_eq!(json_log_level(r#"{"level":50}"#), Some(LogLevel::Error));
+        assert_eq!(json_log_level(r#"{"level":30}"#), Some(LogLevel::Info));
+        assert_eq!(json_log_level(r#"{"level":10}"#), Some(LogLevel::Debug));
+        assert_eq!(
+            json_log_level(r#"{"severity":"WARNING"}"#),
+            Some(LogLevel::Warn)
+        );
+        assert_eq!(
+            json_log_level(r#"{"levelname":"CRITICAL"}"#),
+            Some(LogLevel::Error)
+        );
+    }
+
+    #[test]
+    fn plain_text_has_no_structured_level() {
+        assert!(structured_body("ERROR something failed").is_none());
+        assert_eq!(json_log_level("ERROR something failed"), None);
+        assert_eq!(json_log_level(r#"WARN {not json}"#), None);
+        assert_eq!(json_log_level(r#"{"msg":"no level"}"#), None);
+    }
+
+    #[test]
+    fn timestamp_prefixes_are_stripped() {
+        assert_eq!(
+            strip_timestamp_prefix("2024-05-01T12:00:00.123Z hello"),
+            "hello"
+        );
+        assert_eq!(
+            strip_timestamp_prefix("2024-05-01T12:00:00+05:30\thello"),
+            "hello"
+        );
+        assert_eq!(
+            strip_timestamp_prefix("2024-05-01T12:00:00-0800 hello"),
+            "hello"
+        );
+        assert_eq!(
+            strip_timestamp_prefix("2024-05-01 12:00:00,456 hello"),
+            "hello"
+        );
+    }
+
+    #[test]
+    fn anything_but_a_separate_timestamp_is_kept() {
+        for message in [
+            "2024-05-01T12:00:00Zhello",
+            "2024-05-01T12:00:00.5/abc",
+            "2024-05-01T12:00:00Z",
+            "2024-05-01",
+            "2024-05-01T12:00 hello",
+            "hello 2024-05-01T12:00:00Z",
+            "",
+        ] {
+            assert_eq!(strip_timestamp_prefix(message), message);
+        }
+    }
+
+    fn keys(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn correlation_pattern_group_wins() {
+        let pattern = Regex::new(r"corr=(\w+)").unwrap();
+        let message = r#"corr=abc123 {"traceId":"other"}"#;
+        assert_eq!(
+            correlation_id(message, &keys(&["traceId"]), Some(&pattern)),
+            Some("abc123".to_string())
+        );
+        let whole = Regex::new(r"req-\d+").unwrap();
+        assert_eq!(
+            correlation_id("handling req-42 now", &[], Some(&whole)),
+            Some("req-42".to_string())
+        );
+    }
+
+    #[test]
+    fn correlation_id_is_found_in_nested_json() {
+        let message =
+            "2024-01-01T00:00:00Z\tabc\tINFO\t{\"request\":{\"headers\":{\"X-Correlation-Id\":\"c-1\"}}}";
+        assert_eq!(
+            correlation_id(message, &keys(&["x-correlation-id"]), None),
+            Some("c-1".to_string())
+        );
+    }
+
+    #[test]
+    fn correlation_id_is_found_in_text() {
+        let keys = keys(&["correlationId", "traceId"]);
+        assert_eq!(
+            correlation_id("done traceId: t-9, took 3ms", &keys, None),
+            Some("t-9".to_string())
+        );
+        assert_eq!(
+            correlation_id("start CorrelationID=\"c-2\" user=1", &keys, None),
+            Some("c-2".to_string())
+        );
+        assert_eq!(correlation_id("nothing to see", &keys, None), None);
+    }
+
+    #[test]
+    fn xray_header_gives_its_root() {
+        let message = "X-Amzn-Trace-Id=Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1";
+        assert_eq!(
+            correlation_id(message, &keys(&["X-Amzn-Trace-Id"]), None),
+            Some("1-5759e988-bd862e3fe1be46a994272793".to_string())
+        );
+    }
+
+    #[test]
+    fn constant_phrase_skips_ids_and_counts() {
+        assert_eq!(
+            constant_phrase("Processed 42 records for user 123 in batch"),
+            Some("records for user".to_string())
+        );
+        assert_eq!(
+            constant_phrase("2024-05-01T12:00:00Z Cache miss for key abc1, fetching again"),
+            Some("Cache miss for key".to_string())
+        );
+    }
+
+    #[test]
+    fn constant_phrase_reads_runtime_fields_and_json_messages() {
+        assert_eq!(
+            constant_phrase("2024-05-01T12:00:00.000Z\tabc-123\tINFO\tConnection pool exhausted"),
+            Some("Connection pool exhausted".to_string())
+        );
+        assert_eq!(
+            constant_phrase(r#"{"level":"info","msg":"Order shipped to customer 7"}"#),
+            Some("Order shipped to customer".to_string())
+        );
+    }
+
+    #[test]
+    fn short_or_numeric_lines_have_no_phrase() {
+        assert_eq!(constant_phrase("INFO 12345"), None);
+        assert_eq!(constant_phrase("ok"), None);
+        assert_eq!(constant_phrase(""), None);
+    }
+
+    #[test]
+    fn only_stack_frames_count_as_stderr() {
+        assert!(looks_like_stderr(
+            "Error: boom\n    at handler (/var/task/index.js:3:9)"
+        ));
+        assert!(looks_like_stderr(
+            "  File \"/var/task/app.py\", line 12, in handler"
+        ));
+        assert!(!looks_like_stderr("at 12:00 the batch started"));
+        assert!(!looks_like_stderr("File \"report.csv\" uploaded"));
+    }
+}
@@ -1,2 +1,12 @@
+pub mod aws_utils;
+pub mod clipboard;
+pub mod editor;
+pub mod error_log;
+pub mod export;
 pub mod file_utils;
+pub mod import;
+pub mod lambda_report;
+pub mod log_utils;
+pub mod shutdown;
+pub mod time_utils;
 pub mod ui_utils;
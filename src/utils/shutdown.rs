@@ -0,0 +1,108 @@
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Longest `shutdown` waits for tasks to wind down before giving the terminal back anyway.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Background tasks that stop together when the app quits.
+struct Tasks {
+    /// Set to true once, when the app quits.
+    quit: watch::Sender<bool>,
+    /// Number of tasks started with `spawn` that haven't finished or been dropped yet.
+    running: watch::Sender<usize>,
+}
+
+impl Tasks {
+    fn new() -> Self {
+        Tasks {
+            quit: watch::channel(false).0,
+            running: watch::channel(0).0,
+        }
+    }
+
+    fn spawn<F>(&self, task: F) -> JoinHandle<()>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let mut quit = self.quit.subscribe();
+        let running = RunningTask::start(self.running.clone());
+        tokio::spawn(async move {
+            let _running = running;
+            tokio::select! {
+                _ = task => {}
+                _ = quit.wait_for(|quit| *quit) => {}
+            }
+        })
+    }
+
+    async fn shutdown(&self) {
+        self.quit.send_replace(true);
+        let mut running = self.running.subscribe();
+        let _ = tokio::time::timeout(SHUTDOWN_TIMEOUT, running.wait_for(|count| *count == 0)).await;
+    }
+}
+
+/// The app's tasks, shared by every `spawn`.
+fn app_tasks() -> &'static Tasks {
+    static TASKS: OnceLock<Tasks> = OnceLock::new();
+    TASKS.get_or_init(Tasks::new)
+}
+
+/// Counts a task as running for as long as its future is alive, aborted or not.
+struct RunningTask(watch::Sender<usize>);
+
+impl RunningTask {
+    fn start(running: watch::Sender<usize>) -> Self {
+        running.send_modify(|count| *count += 1);
+        RunningTask(running)
+    }
+}
+
+impl Drop for RunningTask {
+    fn drop(&mut self) {
+        self.0.send_modify(|count| *count -= 1);
+    }
+}
+
+/// Runs `task` in the background like `tokio::spawn`, but drops it at its next await
+/// once `shutdown` has been called.
+pub fn spawn<F>(task: F) -> JoinHandle<()>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    app_tasks().spawn(task)
+}
+
+/// Stops every task started with `spawn` and waits (briefly) for them to finish, so none is
+/// left running once the terminal is restored.
+pub async fn shutdown() {
+    app_tasks().shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn shutdown_stops_looping_tasks() {
+        // Its own tasks, so the app's stay usable by other tests
+        let tasks = Tasks::new();
+        let task = tasks.spawn(async {
+            loop {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+
+        tasks.shutdown().await;
+
+        assert_eq!(*tasks.running.borrow(), 0);
+        // Already over, so this only waits for the runtime to record it
+        let finished = tokio::time::timeout(Duration::from_millis(50), task).await;
+        assert!(finished.is_ok_and(|joined| joined.is_ok()));
+    }
+}
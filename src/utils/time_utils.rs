@@ -0,0 +1,55 @@
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+
+use crate::toml_parser::TimestampMode;
+
+/// Converts a CloudWatch timestamp (milliseconds since the epoch) to local time.
+pub fn millis_to_local(millis: i64) -> DateTime<Local> {
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .unwrap_or_else(|| DateTime::<Local>::from(std::time::UNIX_EPOCH))
+}
+
+/// Formats a CloudWatch timestamp as ISO-8601 in both local time and UTC.
+pub fn iso8601_pair(millis: i64) -> (String, String) {
+    let local = millis_to_local(millis);
+    (
+        local.to_rfc3339_opts(SecondsFormat::Millis, false),
+        local
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Millis, true),
+    )
+}
+
+/// Formats a CloudWatch timestamp for display, with milliseconds when `precise` is set.
+pub fn format_timestamp(millis: i64, mode: TimestampMode, precise: bool) -> String {
+    let format = if precise {
+        "%Y-%m-%d %H:%M:%S%.3f"
+    } else {
+        "%Y-%m-%d %H:%M:%S"
+    };
+    match mode {
+        TimestampMode::Local | TimestampMode::Delta => {
+            millis_to_local(millis).format(format).to_string()
+        }
+        TimestampMode::Utc => format!(
+            "{}Z",
+            millis_to_local(millis).with_timezone(&Utc).format(format)
+        ),
+        TimestampMode::Epoch => millis.to_string(),
+    }
+}
+
+/// Formats the gap between two events compactly: "+12ms", "+3.215s", "+4m05s", "+2h03m", "+1d06h".
+pub fn format_delta(millis: i64) -> String {
+    let sign = if millis < 0 { "-" } else { "+" };
+    let millis = millis.unsigned_abs();
+    let (seconds, minutes, hours) = (millis / 1_000, millis / 60_000, millis / 3_600_000);
+    match millis {
+        0..=999 => format!("{}{}ms", sign, millis),
+        1_000..=59_999 => format!("{}{}.{:03}s", sign, seconds, millis % 1_000),
+        60_000..=3_599_999 => format!("{}{}m{:02}s", sign, minutes, seconds % 60),
+        3_600_000..=86_399_999 => format!("{}{}h{:02}m", sign, hours, minutes % 60),
+        _ => format!("{}{}d{:02}h", sign, hours / 24, hours % 24),
+    }
+}
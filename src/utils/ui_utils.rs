@@ -1,73 +1,207 @@
-use ratatui::prelude::{Color, Line, Span, Style};
+use ratatui::buffer::Buffer;
+use ratatui::prelude::{
+    Alignment, Color, Constraint, Direction, Layout, Line, Modifier, Rect, Span, Style,
+};
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+use std::io::Write;
 
-pub fn format_json(value: &serde_json::Value, indent: usize) -> Vec<Line<'static>> {
-    let mut lines = Vec::new();
+use crate::toml_parser::JsonView;
+use crate::utils::log_utils::{is_python_frame, is_stack_frame};
+
+/// Smallest terminal the screens are laid out for; below it only a note to resize is drawn.
+pub const MIN_TERMINAL_WIDTH: u16 = 60;
+pub const MIN_TERMINAL_HEIGHT: u16 = 16;
+
+/// Frames of a collapsed stack trace left showing; the trace's first lines say where it failed.
+const TRACE_KEEP_FRAMES: usize = 3;
+
+/// Formatted lines kept up to a limit; the ones past it are only counted, not built.
+struct LineSink {
+    lines: Vec<Line<'static>>,
+    total: usize,
+    limit: usize,
+}
+
+impl LineSink {
+    fn new(limit: usize) -> Self {
+        Self {
+            lines: Vec::new(),
+            total: 0,
+            limit,
+        }
+    }
+
+    fn push(&mut self, line: impl FnOnce() -> Line<'static>) {
+        if self.lines.len() < self.limit {
+            self.lines.push(line());
+        }
+        self.total += 1;
+    }
+
+    /// Adds to the end of the last line, unless that one fell past the limit.
+    fn append(&mut self, span: Span<'static>) {
+        if self.lines.len() == self.total {
+            if let Some(last) = self.lines.last_mut() {
+                last.spans.push(span);
+            }
+        }
+    }
+}
+
+/// Lays JSON out one key or element per line, nested values indented under their key.
+fn format_json(value: &serde_json::Value, indent: usize, out: &mut LineSink) {
     let indent_str = " ".repeat(indent);
 
     match value {
         serde_json::Value::Object(map) => {
-            lines.push(Line::from(format!("{}{{", indent_str)));
+            out.push(|| Line::from(format!("{}{{", indent_str)));
             let mut iter = map.iter().peekable();
             while let Some((key, value)) = iter.next() {
                 let comma = if iter.peek().is_some() { "," } else { "" };
                 match value {
                     serde_json::Value::Object(_) | serde_json::Value::Array(_) => {
-                        lines.push(Line::from(vec![
-                            Span::raw(format!("{}  ", indent_str)),
-                            Span::styled(key.clone(), Style::default().fg(Color::Cyan)),
-                            Span::raw(": "),
-                        ]));
-                        lines.extend(format_json(value, indent + 2));
+                        out.push(|| {
+                            Line::from(vec![
+                                Span::raw(format!("{}  ", indent_str)),
+                                Span::styled(key.clone(), Style::default().fg(Color::Cyan)),
+                                Span::raw(": "),
+                            ])
+                        });
+                        format_json(value, indent + 2, out);
                         if !comma.is_empty() {
-                            if let Some(last) = lines.last_mut() {
-                                last.spans.push(Span::raw(comma.to_string()));
-                            }
+                            out.append(Span::raw(comma));
                         }
                     }
-                    _ => {
-                        lines.push(Line::from(vec![
+                    _ => out.push(|| {
+                        Line::from(vec![
                             Span::raw(format!("{}  ", indent_str)),
                             Span::styled(key.clone(), Style::default().fg(Color::Cyan)),
                             Span::raw(": "),
                             format_json_value(value),
                             Span::raw(comma),
-                        ]));
-                    }
+                        ])
+                    }),
                 }
             }
-            lines.push(Line::from(format!("{}}}", indent_str)));
+            out.push(|| Line::from(format!("{}}}", indent_str)));
         }
         serde_json::Value::Array(arr) => {
-            lines.push(Line::from(format!("{}[", indent_str)));
+            out.push(|| Line::from(format!("{}[", indent_str)));
             let mut iter = arr.iter().peekable();
             while let Some(value) = iter.next() {
                 let comma = if iter.peek().is_some() { "," } else { "" };
                 match value {
                     serde_json::Value::Object(_) | serde_json::Value::Array(_) => {
-                        lines.extend(format_json(value, indent + 2));
+                        format_json(value, indent + 2, out);
                         if !comma.is_empty() {
-                            if let Some(last) = lines.last_mut() {
-                                last.spans.push(Span::raw(comma.to_string()));
-                            }
+                            out.append(Span::raw(comma));
                         }
                     }
-                    _ => {
-                        lines.push(Line::from(vec![
+                    _ => out.push(|| {
+                        Line::from(vec![
                             Span::raw(format!("{}  ", indent_str)),
                             format_json_value(value),
                             Span::raw(comma),
-                        ]));
-                    }
+                        ])
+                    }),
                 }
             }
-            lines.push(Line::from(format!("{}]", indent_str)));
+            out.push(|| Line::from(format!("{}]", indent_str)));
         }
-        _ => {
-            lines.push(Line::from(vec![format_json_value(value)]));
+        _ => out.push(|| Line::from(vec![format_json_value(value)])),
+    }
+}
+
+/// Objects and arrays whose one-line form fits in this many columns, indentation included,
+/// are kept on one line by the compact layout.
+const COMPACT_JSON_WIDTH: usize = 80;
+
+/// Like `format_json`, but keeps any object or array that fits on one line there.
+fn format_json_compact(value: &serde_json::Value, indent: usize, out: &mut LineSink) {
+    let indent_str = " ".repeat(indent);
+    let fits = |value: &serde_json::Value, prefix: usize| {
+        prefix + value.to_string().chars().count() <= COMPACT_JSON_WIDTH
+    };
+    if fits(value, indent) {
+        out.push(|| {
+            let mut spans = vec![Span::raw(indent_str.clone())];
+            spans.extend(inline_json(value));
+            Line::from(spans)
+        });
+        return;
+    }
+
+    let (open, close, entries): (_, _, Vec<(Option<&String>, &serde_json::Value)>) = match value {
+        serde_json::Value::Object(map) => {
+            ("{", "}", map.iter().map(|(k, v)| (Some(k), v)).collect())
+        }
+        serde_json::Value::Array(arr) => ("[", "]", arr.iter().map(|v| (None, v)).collect()),
+        _ => return format_json(value, indent, out),
+    };
+
+    out.push(|| Line::from(format!("{}{}", indent_str, open)));
+    let count = entries.len();
+    for (i, (key, value)) in entries.into_iter().enumerate() {
+        let comma = if i + 1 < count { "," } else { "" };
+        let prefix = || {
+            let mut prefix = vec![Span::raw(format!("{}  ", indent_str))];
+            if let Some(key) = key {
+                prefix.push(Span::styled(key.clone(), Style::default().fg(Color::Cyan)));
+                prefix.push(Span::raw(": "));
+            }
+            prefix
+        };
+        let prefix_width = indent + 2 + key.map_or(0, |key| key.chars().count() + 2);
+
+        if fits(value, prefix_width) {
+            out.push(|| {
+                let mut spans = prefix();
+                spans.extend(inline_json(value));
+                spans.push(Span::raw(comma));
+                Line::from(spans)
+            });
+        } else {
+            // Too long for one line: the key gets a line of its own, as in `format_json`
+            if key.is_some() {
+                out.push(|| Line::from(prefix()));
+            }
+            format_json_compact(value, indent + 2, out);
+            out.append(Span::raw(comma));
         }
     }
+    out.push(|| Line::from(format!("{}{}", indent_str, close)));
+}
 
-    lines
+/// A JSON value on one line, colored like `format_json`.
+fn inline_json(value: &serde_json::Value) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    match value {
+        serde_json::Value::Object(map) => {
+            spans.push(Span::raw("{"));
+            for (i, (key, value)) in map.iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::raw(", "));
+                }
+                spans.push(Span::styled(key.clone(), Style::default().fg(Color::Cyan)));
+                spans.push(Span::raw(": "));
+                spans.extend(inline_json(value));
+            }
+            spans.push(Span::raw("}"));
+        }
+        serde_json::Value::Array(arr) => {
+            spans.push(Span::raw("["));
+            for (i, value) in arr.iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::raw(", "));
+                }
+                spans.extend(inline_json(value));
+            }
+            spans.push(Span::raw("]"));
+        }
+        _ => spans.push(format_json_value(value)),
+    }
+    spans
 }
 
 fn format_json_value(value: &serde_json::Value) -> Span<'static> {
@@ -85,3 +219,285 @@ fn format_json_value(value: &serde_json::Value) -> Span<'static> {
         _ => Span::raw(value.to_string()),
     }
 }
+
+/// Wraps lines to `width` columns, breaking after the last space that fits (mid-word when
+/// there is none), and indents each continuation row by `indent` columns so it reads apart
+/// from the next line of the message.
+pub fn wrap_with_indent(
+    lines: Vec<Line<'static>>,
+    width: usize,
+    indent: usize,
+) -> Vec<Line<'static>> {
+    let indent = indent.min(width / 2);
+    let mut rows = Vec::new();
+    for line in lines {
+        let cells: Vec<(char, Style)> = line
+            .spans
+            .iter()
+            .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+            .collect();
+        if width == 0 || cells.len() <= width {
+            rows.push(line);
+            continue;
+        }
+
+        let mut rest = &cells[..];
+        let mut prefix = 0;
+        while !rest.is_empty() {
+            let room = width - prefix;
+            let take = if rest.len() <= room {
+                rest.len()
+            } else {
+                rest[..room]
+                    .iter()
+                    .rposition(|(c, _)| c.is_whitespace())
+                    .map_or(room, |space| space + 1)
+            };
+
+            let mut spans = vec![Span::raw(" ".repeat(prefix))];
+            for (c, style) in &rest[..take] {
+                match spans.last_mut() {
+                    Some(span) if span.style == *style => span.content.to_mut().push(*c),
+                    _ => spans.push(Span::styled(c.to_string(), *style)),
+                }
+            }
+            rows.push(Line::from(spans));
+            rest = &rest[take..];
+            prefix = indent;
+        }
+    }
+    rows
+}
+
+/// Returns a rectangle centered in `area` taking the given percentages of its size.
+pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// Shows a short note in the top-right corner, on the margin row every screen leaves free.
+pub fn draw_corner_note(f: &mut Frame, text: &str) {
+    let area = f.size();
+    let width = (text.chars().count() as u16).min(area.width);
+    let note_area = Rect {
+        x: area.x + area.width - width,
+        y: area.y,
+        width,
+        height: area.height.min(1),
+    };
+    f.render_widget(Clear, note_area);
+    f.render_widget(
+        Paragraph::new(text.to_string()).style(Style::default().fg(Color::Black).bg(Color::Yellow)),
+        note_area,
+    );
+}
+
+/// Draws the resize note instead of a screen when the terminal is below the minimum size,
+/// and says whether it did.
+pub fn draw_too_small(f: &mut Frame) -> bool {
+    let area = f.size();
+    if area.width >= MIN_TERMINAL_WIDTH && area.height >= MIN_TERMINAL_HEIGHT {
+        return false;
+    }
+    let note = Paragraph::new(format!(
+        "Terminal too small ({}×{}); need at least {}×{}",
+        area.width, area.height, MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT
+    ))
+    .style(Style::default().fg(Color::Yellow))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+    // Vertically centered, as far as the height allows
+    let top = area.height / 2;
+    f.render_widget(
+        note,
+        Rect {
+            y: area.y + top.saturating_sub(1),
+            height: area.height - top.saturating_sub(1),
+            ..area
+        },
+    );
+    true
+}
+
+/// Turns a drawn frame monochrome, keeping its distinctions as text attributes: anything
+/// with a background (the selection, search highlights) is reversed, warning and error
+/// colors become bold (red also underlined) and grays are dimmed.
+pub fn strip_colors(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        let mut modifier = cell.modifier;
+        if !matches!(cell.bg, Color::Reset) {
+            modifier |= Modifier::REVERSED;
+        }
+        modifier |= match cell.fg {
+            Color::Red | Color::LightRed => Modifier::BOLD | Modifier::UNDERLINED,
+            Color::Gray | Color::DarkGray => Modifier::DIM,
+            Color::Reset | Color::White | Color::Black => Modifier::empty(),
+            _ => Modifier::BOLD,
+        };
+        cell.set_style(
+            Style::default()
+                .fg(Color::Reset)
+                .bg(Color::Reset)
+                .add_modifier(modifier),
+        );
+    }
+}
+
+/// Sounds the terminal bell, between frames.
+pub fn ring_bell() {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+}
+
+/// First line of an error, short enough for a block title.
+pub fn error_summary(error: &str) -> &str {
+    error.lines().next().unwrap_or_default()
+}
+
+/// The full error text, wrapped, for views that have nothing else to show. Keeps multi-line
+/// details such as a credential helper's stderr readable.
+pub fn error_paragraph<'a>(error: &str, block: Block<'a>) -> Paragraph<'a> {
+    Paragraph::new(error.to_string())
+        .style(Style::default().fg(Color::Red))
+        .wrap(Wrap { trim: false })
+        .block(block)
+}
+
+/// Formats a message for the detail view: JSON laid out as `json_view` says, or plain lines
+/// colored by level, with long stack traces cut to their top frames if `collapse_traces`.
+/// Stops after `max_lines` when given; the second value is always the full line count.
+pub fn format_log_message(
+    message: &str,
+    max_lines: Option<usize>,
+    json_view: JsonView,
+    collapse_traces: bool,
+) -> (Vec<Line<'static>>, usize) {
+    let limit = max_lines.unwrap_or(usize::MAX);
+
+    // Try to parse as JSON first; lines past the limit are counted but never built
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(message) {
+        let mut out = LineSink::new(limit);
+        match json_view {
+            JsonView::Pretty => format_json(&json, 0, &mut out),
+            JsonView::Compact => format_json_compact(&json, 0, &mut out),
+            JsonView::Raw => {
+                for line in message.lines() {
+                    out.push(|| Line::from(line.to_string()));
+                }
+            }
+        }
+        return (out.lines, out.total);
+    }
+
+    let mut lines = Vec::new();
+    let mut total = 0;
+    for row in trace_rows(message, collapse_traces) {
+        total += 1;
+        if lines.len() >= limit {
+            continue;
+        }
+        let line = match row {
+            Ok(line) => line,
+            Err(hidden) => {
+                lines.push(Line::from(Span::styled(
+                    format!("    ... {} more frames (t: expand)", hidden),
+                    Style::default().fg(Color::DarkGray),
+                )));
+                continue;
+            }
+        };
+
+        let line_string = line.to_string();
+        let style = if line.contains("ERROR") || line.contains("error") {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else if line.contains("WARN") || line.contains("warn") {
+            Style::default().fg(Color::Yellow)
+        } else if line.contains("DEBUG") || line.contains("debug") {
+            Style::default().fg(Color::Blue)
+        } else if line.contains("INFO") || line.contains("info") {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(Span::styled(line_string, style)));
+    }
+
+    (lines, total)
+}
+
+/// The message's lines, with all but the top frames of each long stack trace replaced by
+/// the number of frames left out (`Err`).
+fn trace_rows(message: &str, collapse: bool) -> Vec<Result<&str, usize>> {
+    let lines: Vec<&str> = message.lines().collect();
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let mut rows = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        if !is_stack_frame(lines[i]) {
+            rows.push(Ok(lines[i]));
+            i += 1;
+            continue;
+        }
+
+        // Collect the run of frames, with the source line under each Python frame
+        let start = i;
+        let mut frame_starts = Vec::new();
+        while i < lines.len() && is_stack_frame(lines[i]) {
+            frame_starts.push(i);
+            i += 1;
+            if is_python_frame(lines[i - 1])
+                && i < lines.len()
+                && !is_stack_frame(lines[i])
+                && indent(lines[i]) > indent(lines[i - 1])
+            {
+                i += 1;
+            }
+        }
+
+        // Hiding a single frame would save nothing over showing it
+        if collapse && frame_starts.len() > TRACE_KEEP_FRAMES + 1 {
+            rows.extend(
+                lines[start..frame_starts[TRACE_KEEP_FRAMES]]
+                    .iter()
+                    .map(|l| Ok(*l)),
+            );
+            rows.push(Err(frame_starts.len() - TRACE_KEEP_FRAMES));
+        } else {
+            rows.extend(lines[start..i].iter().map(|l| Ok(*l)));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_json_is_capped_but_fully_counted() {
+        let message = serde_json::to_string(&(0..1_000).collect::<Vec<_>>()).unwrap();
+        for view in [JsonView::Pretty, JsonView::Compact] {
+            let (lines, total) = format_log_message(&message, Some(5), view, false);
+            assert_eq!(lines.len(), 5);
+            // One line per element, plus the brackets
+            assert_eq!(total, 1_002);
+            let text: String = lines[1].spans.iter().map(|s| s.content.as_ref()).collect();
+            assert_eq!(text, "  0,");
+        }
+    }
+}